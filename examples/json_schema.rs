@@ -0,0 +1,16 @@
+//! Writes the JSON Schemas of `BidRequest` and `BidResponse`.
+//!
+//! ```sh
+//! cargo run --example json_schema --features json-schema -- schemas/
+//! ```
+
+use std::env;
+
+fn main() {
+    let dir = env::args().nth(1).unwrap_or_else(|| "schemas".to_string());
+
+    let paths = iab::json_schema::write_schemas(&dir).expect("Failed to write schemas");
+    for path in paths {
+        println!("{}", path.display());
+    }
+}
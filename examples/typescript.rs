@@ -0,0 +1,16 @@
+//! Writes the TypeScript definitions of the `OpenRTB` types.
+//!
+//! ```sh
+//! cargo run --example typescript --features typescript -- openrtb2.d.ts
+//! ```
+
+use std::env;
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "openrtb2.d.ts".to_string());
+
+    iab::typescript::write_definitions(&path).expect("Failed to write definitions");
+    println!("{path}");
+}
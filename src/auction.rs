@@ -0,0 +1,574 @@
+//! # Auction
+//!
+//! Per-impression auctions of the bids received for a `BidRequest`.
+//!
+//! [`Auction::run`] takes the request and the `BidResponse`s of the bidders,
+//! converts every price to the auction currency, rejects the bids that are
+//! not eligible (wrong auction, blocked seat, advertiser or creative, unknown
+//! deal, private auction, below floor), and awards each impression to its
+//! highest eligible bid, guaranteed deals first by default (see
+//! [`DealPriority`]). The bids of a seatbid with `group` = 1 win all of
+//! their impressions or none (see [`GroupResolver`]). The clearing price
+//! follows the auction type of `BidRequest.at`, or of `Deal.at` for deal
+//! bids:
+//!
+//! - `1`: first price, the winner pays its bid.
+//! - `2`: second price plus, the winner pays the next highest eligible bid,
+//!   or the floor, plus an increment, never more than its bid.
+//! - `3`: deal price, the winner pays the deal floor.
+//! - `500` and above: exchange-specific, mapped to one of the above with
+//!   [`Auction::exchange_type`].
+//!
+//! ```
+//! use iab::auction::*;
+//! use iab::openrtb2::*;
+//!
+//! let request = BidRequest::builder()
+//!     .id("auction-1")
+//!     .at(2)
+//!     .imp(Imp::banner(300, 250).floor(1.0, "USD"))
+//!     .build()
+//!     .unwrap();
+//!
+//! let bid = |seat: &str, price: f64| {
+//!     BidResponse::builder("auction-1")
+//!         .bid(seat, Bid::builder(format!("{seat}1"), "1", price))
+//!         .build()
+//!         .unwrap()
+//! };
+//!
+//! let outcome = Auction::new().run(&request, &[bid("a", 3.0), bid("b", 2.0)]).unwrap();
+//! let winner = outcome.imps[0].winner.as_ref().unwrap();
+//!
+//! assert_eq!(winner.candidate.bid.id, "a1");
+//! assert_eq!(winner.clearing_price, 2.01);
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+use crate::currency::{currency_or_default, CurrencyConverter, CurrencyError, RateTable, Rounding};
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::types::CurrencyCode;
+use crate::openrtb2::{Bid, BidRequest, BidResponse, Deal, Imp, Price};
+
+mod deal;
+mod filter;
+mod floor;
+mod group;
+mod macros;
+mod merge;
+mod notice;
+mod pod;
+
+pub use deal::{check_deal, DealPriority, DealRejection, DealTier};
+pub use filter::{BidFilter, FilterRejection};
+pub use floor::{resolve_floor, Floor, FloorRule, FloorSource};
+pub use group::{GroupBid, GroupKey, GroupOutcome, GroupResolution, GroupResolver};
+pub use macros::AuctionMacros;
+pub use merge::{BidSource, MergedResponse, ResponseMerger, SeatNaming};
+pub use notice::LossNotice;
+pub use pod::{DedupeBy, Pod, PodAd, PodBuilder, PodRejection, SlotPosition};
+
+/// Increment added to the second price when none is configured.
+#[cfg(not(feature = "decimal"))]
+const DEFAULT_INCREMENT: Price = 0.01;
+
+/// Increment added to the second price when none is configured.
+#[cfg(feature = "decimal")]
+const DEFAULT_INCREMENT: Price =
+    crate::money::Cpm::new(crate::money::Decimal::from_parts(1, 0, 0, false, 2));
+
+/// How the clearing price of a winning bid is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuctionType {
+    /// The winner pays its bid (`at` = 1).
+    FirstPrice,
+    /// The winner pays the next highest eligible bid or the floor, plus an
+    /// increment (`at` = 2).
+    SecondPricePlus,
+    /// The winner pays the deal floor (`Deal.at` = 3).
+    DealPrice,
+}
+
+impl AuctionType {
+    /// Returns the auction type of a standard `at` code.
+    #[must_use]
+    pub fn from_code(at: i64) -> Option<AuctionType> {
+        match at {
+            1 => Some(AuctionType::FirstPrice),
+            2 => Some(AuctionType::SecondPricePlus),
+            3 => Some(AuctionType::DealPrice),
+            _ => None,
+        }
+    }
+}
+
+/// A bid taking part in the auction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Index of the `BidResponse` the bid came from.
+    pub response: usize,
+    /// Index of the `SeatBid` of the bid in `BidResponse.seatbid`.
+    pub seatbid: usize,
+    /// Whether the bids of the seatbid must win as a group (`SeatBid.group`).
+    pub group: bool,
+    /// Buyer seat of the bid (`SeatBid.seat`).
+    pub seat: Option<String>,
+    /// Currency of the bid (`BidResponse.cur`, USD if omitted), or `None` if
+    /// it is not a valid code.
+    pub currency: Option<CurrencyCode>,
+    /// The bid as received.
+    pub bid: Bid,
+    /// Price of the bid in the auction currency.
+    pub price: Price,
+}
+
+impl Candidate {
+    /// Returns the group of the bid, if its seatbid must win as a group.
+    #[must_use]
+    pub fn group_key(&self) -> Option<GroupKey> {
+        self.group.then_some(GroupKey {
+            response: self.response,
+            seatbid: self.seatbid,
+        })
+    }
+}
+
+impl Borrow<Candidate> for Ranked<'_> {
+    fn borrow(&self) -> &Candidate {
+        &self.candidate
+    }
+}
+
+/// An eligible bid, with the floor it met and its deal.
+struct Ranked<'r> {
+    candidate: Candidate,
+    floor: Floor,
+    deal: Option<&'r Deal>,
+}
+
+/// Grouped seats resolved across the impressions of an auction.
+struct Groups {
+    resolution: GroupResolution,
+    /// Reason the bids of each group taken out of the competition lose.
+    excluded: HashMap<GroupKey, LossReason>,
+}
+
+/// The bid awarded an impression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Winner {
+    pub candidate: Candidate,
+    /// Price the winner pays, in the auction currency.
+    pub clearing_price: Price,
+    /// Auction type that set the clearing price.
+    pub auction_type: AuctionType,
+    /// Floor the bid had to meet, in the auction currency.
+    pub floor: Floor,
+}
+
+/// A bid that did not win, with the reason why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loser {
+    pub candidate: Candidate,
+    pub reason: LossReason,
+}
+
+/// Result of the auction of a single impression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpOutcome {
+    /// ID of the impression (`Imp.id`).
+    pub imp_id: String,
+    pub winner: Option<Winner>,
+    /// Every other bid on the impression, highest eligible bids first.
+    pub losers: Vec<Loser>,
+}
+
+/// Result of an auction, with one outcome per impression of the request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuctionOutcome {
+    /// ID of the bid request (`BidRequest.id`).
+    pub auction_id: String,
+    /// Currency of every price of the outcome.
+    pub currency: CurrencyCode,
+    /// Outcomes in the order of `BidRequest.imp`.
+    pub imps: Vec<ImpOutcome>,
+    /// Bids on impressions that are not part of the request.
+    pub rejected: Vec<Loser>,
+    /// Outcome of each seatbid with `SeatBid.group` = 1 and an eligible bid.
+    pub groups: Vec<GroupOutcome>,
+}
+
+impl AuctionOutcome {
+    /// Returns the outcome of an impression.
+    #[must_use]
+    pub fn imp(&self, imp_id: &str) -> Option<&ImpOutcome> {
+        self.imps.iter().find(|imp| imp.imp_id == imp_id)
+    }
+
+    /// Returns the winning bids.
+    pub fn winners(&self) -> impl Iterator<Item = &Winner> {
+        self.imps.iter().filter_map(|imp| imp.winner.as_ref())
+    }
+
+    /// Returns every losing bid, including the rejected ones.
+    pub fn losers(&self) -> impl Iterator<Item = &Loser> {
+        self.imps
+            .iter()
+            .flat_map(|imp| &imp.losers)
+            .chain(&self.rejected)
+    }
+}
+
+/// Auction configuration.
+///
+/// The auction currency is the first of `BidRequest.cur`, or USD. Bids and
+/// floors in other currencies are converted with the
+/// [`converter`](Auction::converter); without one, only amounts already in
+/// the auction currency can be compared.
+#[derive(Clone)]
+pub struct Auction<'a> {
+    converter: Option<&'a dyn CurrencyConverter>,
+    increment: Price,
+    exchange_types: HashMap<i64, AuctionType>,
+    priority: DealPriority,
+}
+
+impl Default for Auction<'_> {
+    fn default() -> Self {
+        Auction {
+            converter: None,
+            increment: DEFAULT_INCREMENT,
+            exchange_types: HashMap::new(),
+            priority: DealPriority::default(),
+        }
+    }
+}
+
+impl<'a> Auction<'a> {
+    /// Creates an auction with an increment of 0.01 and no currency converter.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the converter used for bids and floors in other currencies.
+    #[must_use]
+    pub fn converter(mut self, converter: &'a dyn CurrencyConverter) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Sets the amount added to the second price in second price plus auctions.
+    #[must_use]
+    pub fn increment(mut self, increment: impl Into<Price>) -> Self {
+        self.increment = increment.into();
+        self
+    }
+
+    /// Maps an exchange-specific auction type (`at` of 500 and above) to the
+    /// way it sets clearing prices. Unmapped codes clear as second price plus,
+    /// the `OpenRTB` default.
+    #[must_use]
+    pub fn exchange_type(mut self, at: i64, auction_type: AuctionType) -> Self {
+        self.exchange_types.insert(at, auction_type);
+        self
+    }
+
+    /// Sets the rules ranking deal and open auction bids.
+    #[must_use]
+    pub fn deal_priority(mut self, priority: DealPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Runs a per-impression auction of the bids of the responses. A bid that
+    /// cannot be converted to the auction currency loses with
+    /// [`LossReason::InvalidBidResponse`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the auction currency is invalid, or if a floor cannot be
+    /// converted to it.
+    pub fn run(
+        &self,
+        request: &BidRequest,
+        responses: &[BidResponse],
+    ) -> Result<AuctionOutcome, CurrencyError> {
+        let empty = RateTable::new();
+        let converter = self.converter.unwrap_or(&empty);
+        let first = request.cur.as_ref().and_then(|cur| cur.first().cloned());
+        let currency = currency_or_default(first.as_deref())?;
+
+        let mut bids: HashMap<&str, Vec<(Candidate, Option<LossReason>)>> = HashMap::new();
+        let mut rejected = Vec::new();
+
+        for (index, response) in responses.iter().enumerate() {
+            let response_currency = currency_or_default(response.cur.as_deref());
+            let allowed = match (&response_currency, &request.cur) {
+                (Ok(cur), Some(allowed)) if !allowed.is_empty() => {
+                    allowed.iter().any(|a| cur == a.as_str())
+                }
+                (Ok(_), _) => true,
+                (Err(_), _) => false,
+            };
+
+            for (seatbid_index, seatbid) in response.seatbid.iter().flatten().enumerate() {
+                for bid in &seatbid.bid {
+                    let mut candidate = Candidate {
+                        response: index,
+                        seatbid: seatbid_index,
+                        group: seatbid.group == Some(1),
+                        seat: seatbid.seat.clone(),
+                        currency: response_currency.as_ref().ok().cloned(),
+                        bid: bid.clone(),
+                        price: bid.price,
+                    };
+
+                    let reason = if response.id != request.id {
+                        Some(LossReason::InvalidAuctionId)
+                    } else if !allowed || bid.id.is_empty() || bid.price < Price::default() {
+                        Some(LossReason::InvalidBidResponse)
+                    } else {
+                        // A price that cannot be converted only loses that
+                        // bid, like a bid in a currency that is not allowed.
+                        let price = response_currency.as_ref().map(|from| {
+                            converter.convert(bid.price, from, &currency, Rounding::Exact)
+                        });
+                        match price {
+                            Ok(Ok(price)) => {
+                                candidate.price = price;
+                                None
+                            }
+                            _ => Some(LossReason::InvalidBidResponse),
+                        }
+                    };
+
+                    match request.imp.iter().find(|imp| imp.id == bid.impid) {
+                        Some(imp) => bids
+                            .entry(imp.id.as_str())
+                            .or_default()
+                            .push((candidate, reason)),
+                        None => rejected.push(Loser {
+                            candidate,
+                            reason: reason.unwrap_or(LossReason::InvalidBidResponse),
+                        }),
+                    }
+                }
+            }
+        }
+
+        let mut rankings = Vec::with_capacity(request.imp.len());
+        let mut ineligible = Vec::with_capacity(request.imp.len());
+        for imp in &request.imp {
+            let bids = bids.remove(imp.id.as_str()).unwrap_or_default();
+            let (ranked, losers) = self.rank(request, imp, bids, converter, &currency)?;
+            rankings.push(ranked);
+            ineligible.push(losers);
+        }
+
+        // A group with a bid that cannot win cannot clear; its other bids
+        // lose for the same reason.
+        let mut excluded = HashMap::new();
+        for loser in ineligible.iter().flatten().chain(&rejected) {
+            if let Some(key) = loser.candidate.group_key() {
+                excluded.entry(key).or_insert(loser.reason);
+            }
+        }
+        let resolution = excluded
+            .keys()
+            .fold(GroupResolver::new(), |resolver, key| resolver.exclude(*key))
+            .resolve(&rankings);
+
+        let groups = Groups {
+            resolution,
+            excluded,
+        };
+
+        let imps = rankings
+            .into_iter()
+            .zip(ineligible)
+            .enumerate()
+            .map(|(index, (ranked, losers))| self.award(request, index, ranked, losers, &groups))
+            .collect();
+
+        Ok(AuctionOutcome {
+            auction_id: request.id.clone(),
+            currency,
+            imps,
+            rejected,
+            groups: groups.resolution.groups,
+        })
+    }
+
+    /// Checks the bids on an impression, returning the eligible ones ranked
+    /// best first, and the others.
+    fn rank<'r>(
+        &self,
+        request: &BidRequest,
+        imp: &'r Imp,
+        bids: Vec<(Candidate, Option<LossReason>)>,
+        converter: &dyn CurrencyConverter,
+        currency: &CurrencyCode,
+    ) -> Result<(Vec<Ranked<'r>>, Vec<Loser>), CurrencyError> {
+        let mut eligible = Vec::new();
+        let mut losers = Vec::new();
+
+        for (candidate, reason) in bids {
+            let reason = match reason {
+                Some(reason) => Err(reason),
+                None => eligibility(request, imp, &candidate, converter, currency)?,
+            };
+            match reason {
+                Ok((floor, deal)) => eligible.push(Ranked {
+                    candidate,
+                    floor,
+                    deal,
+                }),
+                Err(reason) => losers.push(Loser { candidate, reason }),
+            }
+        }
+
+        self.priority.rank_by(&mut eligible, |ranked| {
+            (DealTier::of(ranked.deal), ranked.candidate.price)
+        });
+        Ok((eligible, losers))
+    }
+
+    /// Awards an impression to the bid picked among its ranked bids, and sets
+    /// its clearing price.
+    fn award(
+        &self,
+        request: &BidRequest,
+        index: usize,
+        ranked: Vec<Ranked>,
+        ineligible: Vec<Loser>,
+        groups: &Groups,
+    ) -> ImpOutcome {
+        let winner = groups.resolution.winners[index];
+        // Bids of groups that do not clear are out of the competition.
+        let competes = |ranked: &Ranked| groups.resolution.clears(ranked.candidate.group_key());
+        let rank_of = |ranked: &Ranked| self.priority.rank(DealTier::of(ranked.deal));
+
+        let winning = winner.map(|index| &ranked[index]);
+        let winning_rank = winning.map(rank_of);
+        let won_by_deal = winning.is_some_and(|w| w.deal.is_some());
+
+        // The runner-up is the highest competing bid the winner outranked.
+        // Bids of lower ranks do not compete on price with the winner.
+        let runner_up = winner
+            .and_then(|index| ranked.get(index + 1..))
+            .and_then(|below| {
+                below
+                    .iter()
+                    .find(|next| competes(next) && Some(rank_of(next)) == winning_rank)
+            })
+            .map(|next| next.candidate.price);
+
+        let mut won = None;
+        let mut losers = Vec::new();
+        for (index, entry) in ranked.into_iter().enumerate() {
+            if Some(index) == winner {
+                won = Some(entry);
+                continue;
+            }
+            let reason = if !competes(&entry) {
+                let key = entry.candidate.group_key();
+                key.and_then(|key| groups.excluded.get(&key).copied())
+                    .unwrap_or(LossReason::LostToHigherBid)
+            } else if won_by_deal && (entry.deal.is_none() || Some(rank_of(&entry)) != winning_rank)
+            {
+                LossReason::LostToPmpDeal
+            } else {
+                LossReason::LostToHigherBid
+            };
+            losers.push(Loser {
+                candidate: entry.candidate,
+                reason,
+            });
+        }
+
+        let winner = won.map(|won| {
+            let Ranked {
+                candidate,
+                floor,
+                deal,
+            } = won;
+            let at = deal.and_then(|d| d.at).or(request.at);
+            let auction_type = self.auction_type(at);
+            let clearing_price = match auction_type {
+                AuctionType::FirstPrice => candidate.price,
+                AuctionType::SecondPricePlus => {
+                    let second = runner_up.map_or(floor.price, |price| max(price, floor.price));
+                    min(second + self.increment, candidate.price)
+                }
+                AuctionType::DealPrice => floor.price,
+            };
+            Winner {
+                candidate,
+                clearing_price,
+                auction_type,
+                floor,
+            }
+        });
+
+        ImpOutcome {
+            imp_id: request.imp[index].id.clone(),
+            winner,
+            losers: losers.into_iter().chain(ineligible).collect(),
+        }
+    }
+
+    /// Resolves the auction type of an `at` code.
+    fn auction_type(&self, at: Option<i64>) -> AuctionType {
+        at.and_then(|at| {
+            AuctionType::from_code(at).or_else(|| self.exchange_types.get(&at).copied())
+        })
+        .unwrap_or(AuctionType::SecondPricePlus)
+    }
+}
+
+/// Checks whether a bid may compete for an impression, returning the floor
+/// it must meet and its deal.
+fn eligibility<'r>(
+    request: &BidRequest,
+    imp: &'r Imp,
+    candidate: &Candidate,
+    converter: &dyn CurrencyConverter,
+    currency: &CurrencyCode,
+) -> Result<Result<(Floor, Option<&'r Deal>), LossReason>, CurrencyError> {
+    let filter = BidFilter::new(request);
+    if let Some(rejection) = filter
+        .check(candidate.seat.as_deref(), &candidate.bid)
+        .first()
+    {
+        return Ok(Err(rejection.loss_reason()));
+    }
+
+    let deal = match check_deal(imp, &candidate.bid, candidate.seat.as_deref()) {
+        Ok(deal) => deal,
+        Err(rejection) => return Ok(Err(rejection.loss_reason())),
+    };
+
+    let floor = resolve_floor(imp, deal, candidate.bid.dur, currency, converter)?;
+    if candidate.price < floor.price {
+        return Ok(Err(floor.loss_reason()));
+    }
+    Ok(Ok((floor, deal)))
+}
+
+fn max(a: Price, b: Price) -> Price {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn min(a: Price, b: Price) -> Price {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
@@ -0,0 +1,218 @@
+use std::cmp::Ordering;
+
+use super::filter::domain_matches;
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::{Bid, Deal, Imp, Price};
+
+/// Why the private marketplace of an impression does not admit a bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DealRejection {
+    /// `Bid.dealid` matches none of `Pmp.deals`.
+    UnknownDeal,
+    /// The seat of the bid is not in `Deal.wseat`.
+    SeatNotAllowed,
+    /// The deal has a `wadomain` and the bid declares no `adomain`, or one
+    /// that is not in it nor a subdomain of one of its domains.
+    AdvertiserNotAllowed,
+    /// The bid has no deal and `Pmp.private_auction` restricts the
+    /// impression to deals.
+    PrivateAuction,
+}
+
+impl DealRejection {
+    /// Loss reason reported to the bidder.
+    #[must_use]
+    pub fn loss_reason(&self) -> LossReason {
+        match self {
+            DealRejection::UnknownDeal => LossReason::InvalidDealId,
+            DealRejection::SeatNotAllowed => LossReason::BuyerSeatBlocked,
+            DealRejection::AdvertiserNotAllowed => LossReason::NotAllowedInPmpDeal,
+            DealRejection::PrivateAuction => LossReason::LostToPmpDeal,
+        }
+    }
+}
+
+/// Priority class of an eligible bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DealTier {
+    /// Bid on a deal with `guar` = 1.
+    Guaranteed,
+    /// Bid on a non-guaranteed deal.
+    Deal,
+    /// Open auction bid.
+    Open,
+}
+
+impl DealTier {
+    /// Returns the tier of a bid made on the given deal, if any.
+    #[must_use]
+    pub fn of(deal: Option<&Deal>) -> DealTier {
+        match deal {
+            Some(deal) if deal.guar == Some(1) => DealTier::Guaranteed,
+            Some(_) => DealTier::Deal,
+            None => DealTier::Open,
+        }
+    }
+}
+
+/// Checks whether the private marketplace of an impression admits a bid
+/// from a seat, returning the deal the bid is made on.
+///
+/// A bid with a `dealid` must name a deal of `Pmp.deals`, its seat must be
+/// in the `wseat` of the deal, and each of its `adomain` must be a domain of
+/// the `wadomain` of the deal or one of its subdomains, when those are set.
+/// Domains are normalized as in `badv` for [`BidFilter`](super::BidFilter),
+/// so `ford.com` allows `https://www.ford.com/`, but a listed domain never
+/// allows its parent domain. A bid without a `dealid` is admitted unless
+/// `Pmp.private_auction` is 1.
+///
+/// # Errors
+///
+/// Returns the reason the bid is not admitted.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let imp: Imp = Imp::banner(300, 250)
+///     .deal(Deal {
+///         id: "deal-1".to_string(),
+///         wseat: Some(vec!["seat-1".to_string()]),
+///         ..Default::default()
+///     })
+///     .private_auction(true)
+///     .into();
+/// let bid: Bid = Bid::builder("1", "1", 2.0).dealid("deal-1").into();
+///
+/// assert_eq!(check_deal(&imp, &bid, Some("seat-1")).unwrap().unwrap().id, "deal-1");
+/// assert_eq!(check_deal(&imp, &bid, Some("seat-2")), Err(DealRejection::SeatNotAllowed));
+///
+/// let open: Bid = Bid::builder("2", "1", 2.0).into();
+/// assert_eq!(check_deal(&imp, &open, None), Err(DealRejection::PrivateAuction));
+/// ```
+pub fn check_deal<'i>(
+    imp: &'i Imp,
+    bid: &Bid,
+    seat: Option<&str>,
+) -> Result<Option<&'i Deal>, DealRejection> {
+    let pmp = imp.pmp.as_ref();
+    let Some(dealid) = &bid.dealid else {
+        return match pmp.and_then(|p| p.private_auction) {
+            Some(1) => Err(DealRejection::PrivateAuction),
+            _ => Ok(None),
+        };
+    };
+
+    let deal = pmp
+        .and_then(|p| p.deals.as_ref())
+        .and_then(|deals| deals.iter().find(|d| &d.id == dealid))
+        .ok_or(DealRejection::UnknownDeal)?;
+
+    if let Some(wseat) = &deal.wseat {
+        let seat = seat.unwrap_or_default();
+        if !wseat.iter().any(|s| s == seat) {
+            return Err(DealRejection::SeatNotAllowed);
+        }
+    }
+
+    if let Some(wadomain) = &deal.wadomain {
+        let adomain = bid.adomain.as_deref().unwrap_or_default();
+        let allowed = |domain: &String| wadomain.iter().any(|w| domain_matches(w, domain));
+        if adomain.is_empty() || !adomain.iter().all(allowed) {
+            return Err(DealRejection::AdvertiserNotAllowed);
+        }
+    }
+
+    Ok(Some(deal))
+}
+
+/// Exchange rules ranking the eligible bids of an impression.
+///
+/// Bids of a prioritized tier beat every bid of the tiers below it whatever
+/// their price; within a tier, the highest price wins. By default guaranteed
+/// deals are prioritized, and other deals compete with open auction bids on
+/// price.
+///
+/// ```
+/// use iab::auction::*;
+///
+/// let mut bids = [(DealTier::Open, 5.0), (DealTier::Deal, 3.0), (DealTier::Guaranteed, 1.0)];
+///
+/// DealPriority::new().rank_by(&mut bids, |&(tier, price)| (tier, price.into()));
+/// assert_eq!(bids.map(|b| b.0), [DealTier::Guaranteed, DealTier::Open, DealTier::Deal]);
+///
+/// DealPriority::new()
+///     .deals_first(true)
+///     .rank_by(&mut bids, |&(tier, price)| (tier, price.into()));
+/// assert_eq!(bids.map(|b| b.0), [DealTier::Guaranteed, DealTier::Deal, DealTier::Open]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DealPriority {
+    guaranteed_first: bool,
+    deals_first: bool,
+}
+
+impl Default for DealPriority {
+    fn default() -> Self {
+        DealPriority {
+            guaranteed_first: true,
+            deals_first: false,
+        }
+    }
+}
+
+impl DealPriority {
+    /// Creates the default rules, prioritizing guaranteed deals only.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ranks bids on price alone, whatever their deals.
+    #[must_use]
+    pub fn price_only() -> Self {
+        DealPriority {
+            guaranteed_first: false,
+            deals_first: false,
+        }
+    }
+
+    /// Sets whether guaranteed deals beat every other bid.
+    #[must_use]
+    pub fn guaranteed_first(mut self, enabled: bool) -> Self {
+        self.guaranteed_first = enabled;
+        self
+    }
+
+    /// Sets whether deals, guaranteed or not, beat every open auction bid.
+    #[must_use]
+    pub fn deals_first(mut self, enabled: bool) -> Self {
+        self.deals_first = enabled;
+        self
+    }
+
+    /// Returns the rank of a tier; lower ranks beat higher ones regardless
+    /// of price, equal ranks compete on price.
+    #[must_use]
+    pub fn rank(&self, tier: DealTier) -> u8 {
+        match tier {
+            DealTier::Guaranteed if self.guaranteed_first => 0,
+            DealTier::Guaranteed | DealTier::Deal if self.deals_first => 1,
+            _ => 2,
+        }
+    }
+
+    /// Orders two bids, the one that should win first.
+    #[must_use]
+    pub fn compare(&self, a: (DealTier, Price), b: (DealTier, Price)) -> Ordering {
+        self.rank(a.0)
+            .cmp(&self.rank(b.0))
+            .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal))
+    }
+
+    /// Sorts bids so that the one that should win comes first. The sort is
+    /// stable, so that ties go to the bid received first.
+    pub fn rank_by<T>(&self, bids: &mut [T], key: impl Fn(&T) -> (DealTier, Price)) {
+        bids.sort_by(|a, b| self.compare(key(a), key(b)));
+    }
+}
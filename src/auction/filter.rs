@@ -0,0 +1,245 @@
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::{Bid, BidRequest, Imp, SeatBid};
+
+/// Category taxonomy assumed when `cattax` is absent (IAB Tech Lab Content
+/// Category Taxonomy 1.0).
+const DEFAULT_CATTAX: i64 = 1;
+
+/// Why the restrictions of a bid request exclude a bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterRejection {
+    /// The seat is not in `BidRequest.wseat`, or is in `BidRequest.bseat`.
+    SeatBlocked,
+    /// A domain of `Bid.adomain` is, or is a subdomain of, a domain of
+    /// `BidRequest.badv`.
+    AdvertiserBlocked,
+    /// `Bid.bundle` is in `BidRequest.bapp`.
+    AppBlocked,
+    /// A category of `Bid.cat` is, or is a subcategory of, a category of
+    /// `BidRequest.bcat`.
+    CategoryBlocked,
+    /// `BidRequest.acat` is set and a category of `Bid.cat` is not under any
+    /// of its categories, or the bid declares none.
+    CategoryNotAllowed,
+    /// `Bid.cattax` differs from `BidRequest.cattax`, so the categories of
+    /// the bid cannot be checked against `bcat` or `acat`.
+    TaxonomyMismatch,
+    /// An attribute of `Bid.attr` is in the `battr` of the impression.
+    AttributeBlocked,
+    /// The markup of a banner bid is of a type in `Banner.btype`.
+    AdTypeBlocked,
+}
+
+impl FilterRejection {
+    /// Loss reason reported to the bidder.
+    #[must_use]
+    pub fn loss_reason(&self) -> LossReason {
+        match self {
+            FilterRejection::SeatBlocked => LossReason::BuyerSeatBlocked,
+            FilterRejection::AdvertiserBlocked => LossReason::AdvertiserExclusions,
+            FilterRejection::AppBlocked => LossReason::AppBundleExclusions,
+            FilterRejection::CategoryBlocked
+            | FilterRejection::CategoryNotAllowed
+            | FilterRejection::TaxonomyMismatch => LossReason::CategoryExclusions,
+            FilterRejection::AttributeBlocked => LossReason::CreativeAttributeExclusions,
+            FilterRejection::AdTypeBlocked => LossReason::AdTypeExclusions,
+        }
+    }
+}
+
+/// Checks bids against the buyer, advertiser and creative restrictions of a
+/// bid request.
+///
+/// Categories are matched hierarchically on the `-` separator of the IAB
+/// 1.0 notation, so `IAB1` blocks `IAB1-3`. Advertiser domains are matched
+/// case-insensitively, a blocked domain blocking its subdomains too, so
+/// `ford.com` blocks `www.ford.com` but `www.ford.com` does not block
+/// `ford.com`. A top-level domain such as `com` blocks nothing. The `battr`
+/// of the `Banner`, `Video`, `Audio` or `Native` object matching
+/// `Bid.mtype` applies, or those of every object of the impression when the
+/// bid has no `mtype`. The type of banner markup checked against `btype` is
+/// inferred from `Bid.adm`: JavaScript for a `<script>`, iframe for an
+/// `<iframe>`.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest {
+///     bcat: Some(vec!["IAB7".to_string()]),
+///     badv: Some(vec!["ford.com".to_string()]),
+///     ..BidRequest::builder().id("1").imp(Imp::banner(300, 250)).build().unwrap()
+/// };
+/// let filter = BidFilter::new(&request);
+///
+/// let bid: Bid = Bid::builder("1", "1", 2.0).adomain(["www.ford.com"]).cat(["IAB7-39"]).into();
+/// assert_eq!(
+///     filter.check(Some("seat-1"), &bid),
+///     [FilterRejection::AdvertiserBlocked, FilterRejection::CategoryBlocked]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BidFilter<'r> {
+    request: &'r BidRequest,
+}
+
+impl<'r> BidFilter<'r> {
+    /// Creates a filter enforcing the restrictions of a request.
+    #[must_use]
+    pub fn new(request: &'r BidRequest) -> Self {
+        BidFilter { request }
+    }
+
+    /// Returns every reason the request excludes a bid from a seat; the bid
+    /// is allowed when there is none. Impression-level restrictions apply
+    /// only when `Bid.impid` names an impression of the request.
+    pub fn check(&self, seat: Option<&str>, bid: &Bid) -> Vec<FilterRejection> {
+        let request = self.request;
+        let mut reasons = Vec::new();
+
+        let seat = seat.unwrap_or_default();
+        let seat_blocked = request
+            .wseat
+            .as_ref()
+            .is_some_and(|wseat| !wseat.iter().any(|s| s == seat))
+            || request
+                .bseat
+                .as_ref()
+                .is_some_and(|bseat| bseat.iter().any(|s| s == seat));
+        if seat_blocked {
+            reasons.push(FilterRejection::SeatBlocked);
+        }
+
+        let adomain = bid.adomain.iter().flatten();
+        let badv = request.badv.iter().flatten();
+        if adomain
+            .into_iter()
+            .any(|domain| badv.clone().any(|blocked| domain_matches(blocked, domain)))
+        {
+            reasons.push(FilterRejection::AdvertiserBlocked);
+        }
+
+        if let (Some(bundle), Some(bapp)) = (&bid.bundle, &request.bapp) {
+            if bapp.iter().any(|b| b == bundle) {
+                reasons.push(FilterRejection::AppBlocked);
+            }
+        }
+
+        if let Some(reason) = self.check_categories(bid) {
+            reasons.push(reason);
+        }
+
+        if let Some(imp) = request.imp.iter().find(|imp| imp.id == bid.impid) {
+            if let Some(attr) = &bid.attr {
+                if attr
+                    .iter()
+                    .any(|a| blocked_attributes(imp, bid.mtype).any(|b| b == a))
+                {
+                    reasons.push(FilterRejection::AttributeBlocked);
+                }
+            }
+            if let Some(btype) = imp.banner.as_ref().and_then(|b| b.btype.as_ref()) {
+                let banner = bid.mtype.is_none_or(|mtype| mtype == 1);
+                let ad_type = bid.adm.as_deref().and_then(banner_ad_type);
+                if banner && ad_type.is_some_and(|t| btype.contains(&t)) {
+                    reasons.push(FilterRejection::AdTypeBlocked);
+                }
+            }
+        }
+
+        reasons
+    }
+
+    /// Checks the bids of a seat, returning those the request excludes with
+    /// their reasons.
+    #[must_use]
+    pub fn check_seatbid<'b>(&self, seatbid: &'b SeatBid) -> Vec<(&'b Bid, Vec<FilterRejection>)> {
+        seatbid
+            .bid
+            .iter()
+            .map(|bid| (bid, self.check(seatbid.seat.as_deref(), bid)))
+            .filter(|(_, reasons)| !reasons.is_empty())
+            .collect()
+    }
+
+    fn check_categories(self, bid: &Bid) -> Option<FilterRejection> {
+        let request = self.request;
+        if request.bcat.is_none() && request.acat.is_none() {
+            return None;
+        }
+
+        let cat = bid.cat.as_deref().unwrap_or_default();
+        let cattax = request.cattax.unwrap_or(DEFAULT_CATTAX);
+        if !cat.is_empty() && bid.cattax.unwrap_or(DEFAULT_CATTAX) != cattax {
+            return Some(FilterRejection::TaxonomyMismatch);
+        }
+
+        let under = |list: &[String], category: &String| {
+            list.iter().any(|parent| category_matches(parent, category))
+        };
+        if let Some(bcat) = &request.bcat {
+            if cat.iter().any(|c| under(bcat, c)) {
+                return Some(FilterRejection::CategoryBlocked);
+            }
+        }
+        if let Some(acat) = &request.acat {
+            if cat.is_empty() || !cat.iter().all(|c| under(acat, c)) {
+                return Some(FilterRejection::CategoryNotAllowed);
+            }
+        }
+        None
+    }
+}
+
+/// Checks whether a category is, or is a subcategory of, another.
+fn category_matches(parent: &str, category: &str) -> bool {
+    category
+        .strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+/// Checks whether a domain is, or is a subdomain of, a listed domain. A
+/// listed top-level domain, without a dot, matches nothing.
+pub(super) fn domain_matches(listed: &str, domain: &str) -> bool {
+    let listed = normalize_domain(listed);
+    let domain = normalize_domain(domain);
+    listed.contains('.')
+        && domain
+            .strip_suffix(listed.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+}
+
+/// Lowercases a domain and strips any scheme, path, port and trailing dot.
+fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim();
+    let domain = domain.split_once("://").map_or(domain, |(_, rest)| rest);
+    let domain = domain.split(['/', '?', '#']).next().unwrap_or_default();
+    let domain = domain.split(':').next().unwrap_or_default();
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Returns the attributes blocked for a bid of the given media type.
+fn blocked_attributes(imp: &Imp, mtype: Option<i64>) -> impl Iterator<Item = &i64> {
+    let battr = [
+        (1, imp.banner.as_ref().and_then(|b| b.battr.as_ref())),
+        (2, imp.video.as_ref().and_then(|v| v.battr.as_ref())),
+        (3, imp.audio.as_ref().and_then(|a| a.battr.as_ref())),
+        (4, imp.native_markup.as_ref().and_then(|n| n.battr.as_ref())),
+    ];
+    battr
+        .into_iter()
+        .filter(move |(media, _)| mtype.is_none_or(|mtype| mtype == *media))
+        .flat_map(|(_, battr)| battr.into_iter().flatten())
+}
+
+/// Infers the banner ad type of markup (3 = JavaScript, 4 = iframe).
+fn banner_ad_type(adm: &str) -> Option<i64> {
+    let adm = adm.to_ascii_lowercase();
+    if adm.contains("<script") {
+        Some(3)
+    } else if adm.contains("<iframe") {
+        Some(4)
+    } else {
+        None
+    }
+}
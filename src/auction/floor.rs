@@ -0,0 +1,214 @@
+use crate::currency::{currency_or_default, CurrencyConverter, CurrencyError, Rounding};
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::types::CurrencyCode;
+use crate::openrtb2::{Deal, DurFloors, Imp, Price};
+
+/// Object whose floors apply to a bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloorSource {
+    /// The floors of the `Imp` and its `Video` or `Audio` object.
+    Imp,
+    /// The floors of the `Deal` the bid is made on.
+    Deal,
+}
+
+/// Rule that set a floor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FloorRule {
+    /// No floor applies; the floor is zero.
+    None,
+    /// The `bidfloor` of the `Imp` or `Deal`.
+    BidFloor,
+    /// A `DurFloors` band whose `mindur`/`maxdur` range includes the bid duration.
+    Duration {
+        mindur: Option<i64>,
+        maxdur: Option<i64>,
+    },
+    /// `mincpmpersec` multiplied by the bid duration (`Bid.dur`).
+    PerSecond { mincpmpersec: Price, dur: i64 },
+}
+
+/// The binding floor of a bid, and the rule that set it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Floor {
+    /// Minimum price of the bid.
+    pub price: Price,
+    /// Currency of the price.
+    pub currency: CurrencyCode,
+    pub source: FloorSource,
+    pub rule: FloorRule,
+}
+
+impl Floor {
+    /// Loss reason of a bid below this floor.
+    #[must_use]
+    pub fn loss_reason(&self) -> LossReason {
+        match self.source {
+            FloorSource::Imp => LossReason::BelowAuctionFloor,
+            FloorSource::Deal => LossReason::BelowDealFloor,
+        }
+    }
+}
+
+/// Resolves the floor a bid on an impression must meet, converted to the
+/// given currency.
+///
+/// The floors of the deal apply when it defines any, otherwise those of the
+/// impression. Within either, a `durfloors` band including the bid duration
+/// (inclusive of `mindur` and `maxdur`) replaces `bidfloor`, and
+/// `mincpmpersec` multiplied by the duration raises the floor further. The
+/// highest of the applicable floors binds.
+///
+/// # Errors
+///
+/// Fails if a floor currency is invalid, or if a floor cannot be converted.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::currency::RateTable;
+/// use iab::openrtb2::types::CurrencyCode;
+/// use iab::openrtb2::*;
+///
+/// let imp: Imp = Imp::video(["video/mp4"])
+///     .floor(5.0, "USD")
+///     .with_video(Video {
+///         mimes: vec!["video/mp4".to_string()],
+///         durfloors: Some(vec![DurFloors {
+///             mindur: Some(16),
+///             bidfloor: Some(10.0.into()),
+///             ..Default::default()
+///         }]),
+///         ..Default::default()
+///     })
+///     .into();
+/// let usd = CurrencyCode::new("USD").unwrap();
+///
+/// let floor = resolve_floor(&imp, None, Some(30), &usd, &RateTable::new()).unwrap();
+///
+/// assert_eq!(floor.price, 10.0);
+/// assert_eq!(floor.rule, FloorRule::Duration { mindur: Some(16), maxdur: None });
+/// ```
+pub fn resolve_floor(
+    imp: &Imp,
+    deal: Option<&Deal>,
+    duration: Option<i64>,
+    currency: &CurrencyCode,
+    converter: &dyn CurrencyConverter,
+) -> Result<Floor, CurrencyError> {
+    if let Some(deal) = deal {
+        let rules = Rules {
+            bidfloor: deal.bidfloor,
+            durfloors: deal.durfloors.iter().flatten().collect(),
+            mincpmpersec: deal.mincpmpersec.into_iter().collect(),
+        };
+        if let Some((price, rule)) = rules.binding(duration) {
+            let price = convert(converter, price, deal.bidfloorcur.as_deref(), currency)?;
+            return Ok(floor(price, currency, FloorSource::Deal, rule));
+        }
+    }
+
+    let video = imp.video.as_ref().map(|v| (&v.durfloors, v.mincpmpersec));
+    let audio = imp.audio.as_ref().map(|a| (&a.durfloors, a.mincpmpersec));
+    let media = video.into_iter().chain(audio);
+    let rules = Rules {
+        bidfloor: imp.bidfloor,
+        durfloors: media
+            .clone()
+            .flat_map(|(durfloors, _)| durfloors.iter().flatten())
+            .collect(),
+        mincpmpersec: media.filter_map(|(_, mincpmpersec)| mincpmpersec).collect(),
+    };
+
+    match rules.binding(duration) {
+        Some((price, rule)) => {
+            let price = convert(converter, price, imp.bidfloorcur.as_deref(), currency)?;
+            Ok(floor(price, currency, FloorSource::Imp, rule))
+        }
+        None => Ok(floor(
+            Price::default(),
+            currency,
+            FloorSource::Imp,
+            FloorRule::None,
+        )),
+    }
+}
+
+/// Floor rules of an `Imp` or a `Deal`.
+struct Rules<'a> {
+    bidfloor: Option<Price>,
+    durfloors: Vec<&'a DurFloors>,
+    mincpmpersec: Vec<Price>,
+}
+
+impl Rules<'_> {
+    /// Returns the highest applicable floor, if any rule applies.
+    fn binding(&self, duration: Option<i64>) -> Option<(Price, FloorRule)> {
+        let band = duration.and_then(|dur| {
+            self.durfloors.iter().find(|band| {
+                band.bidfloor.is_some()
+                    && band.mindur.is_none_or(|min| dur >= min)
+                    && band.maxdur.is_none_or(|max| dur <= max)
+            })
+        });
+
+        let base = match band {
+            Some(band) => band.bidfloor.map(|price| {
+                let rule = FloorRule::Duration {
+                    mindur: band.mindur,
+                    maxdur: band.maxdur,
+                };
+                (price, rule)
+            }),
+            None => self.bidfloor.map(|price| (price, FloorRule::BidFloor)),
+        };
+
+        let per_second = duration.and_then(|dur| {
+            self.mincpmpersec
+                .iter()
+                .map(|mincpmpersec| {
+                    let rule = FloorRule::PerSecond {
+                        mincpmpersec: *mincpmpersec,
+                        dur,
+                    };
+                    (per_second(*mincpmpersec, dur), rule)
+                })
+                .reduce(|a, b| if b.0 > a.0 { b } else { a })
+        });
+
+        match (base, per_second) {
+            (Some(base), Some(per_second)) if per_second.0 > base.0 => Some(per_second),
+            (Some(base), _) => Some(base),
+            (None, per_second) => per_second,
+        }
+    }
+}
+
+// Durations are far below 2^52 seconds, where `f64` stops being exact.
+#[cfg(not(feature = "decimal"))]
+#[allow(clippy::cast_precision_loss)]
+fn per_second(mincpmpersec: Price, dur: i64) -> Price {
+    mincpmpersec * dur as f64
+}
+
+#[cfg(feature = "decimal")]
+fn per_second(mincpmpersec: Price, dur: i64) -> Price {
+    mincpmpersec * crate::money::Decimal::from(dur)
+}
+
+fn convert(
+    converter: &dyn CurrencyConverter,
+    price: Price,
+    from: Option<&str>,
+    to: &CurrencyCode,
+) -> Result<Price, CurrencyError> {
+    converter.convert(price, &currency_or_default(from)?, to, Rounding::Exact)
+}
+
+fn floor(price: Price, currency: &CurrencyCode, source: FloorSource, rule: FloorRule) -> Floor {
+    Floor {
+        price,
+        currency: currency.clone(),
+        source,
+        rule,
+    }
+}
@@ -0,0 +1,212 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+
+use super::Candidate;
+use crate::openrtb2::Price;
+
+/// Identifies a seatbid by the index of its response and its index in the
+/// response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GroupKey {
+    /// Index of the `BidResponse`.
+    pub response: usize,
+    /// Index of the `SeatBid` in `BidResponse.seatbid`.
+    pub seatbid: usize,
+}
+
+/// The highest ranked bid of a group on one impression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupBid {
+    /// ID of the impression (`Bid.impid`).
+    pub imp_id: String,
+    /// ID of the bid (`Bid.id`).
+    pub bid_id: String,
+    pub price: Price,
+    /// Whether the bid ranks first among the bids left in competition.
+    pub wins: bool,
+}
+
+/// Whether a group of bids clears as a whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupOutcome {
+    pub key: GroupKey,
+    /// Buyer seat of the group (`SeatBid.seat`).
+    pub seat: Option<String>,
+    /// Whether the group wins every impression it bids on.
+    pub clears: bool,
+    /// Bids of the group, one per impression, in the order of the rankings.
+    pub bids: Vec<GroupBid>,
+}
+
+/// Winners of a set of impressions once grouped seats are resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupResolution {
+    /// For each ranking, the index of its winner, if any.
+    pub winners: Vec<Option<usize>>,
+    /// Outcome of each group, in the order the groups first appear.
+    pub groups: Vec<GroupOutcome>,
+}
+
+impl GroupResolution {
+    /// Returns the outcome of a group.
+    #[must_use]
+    pub fn group(&self, key: GroupKey) -> Option<&GroupOutcome> {
+        self.groups.iter().find(|group| group.key == key)
+    }
+
+    /// Returns whether a group clears; bids outside of any group always do.
+    #[must_use]
+    pub fn clears(&self, key: Option<GroupKey>) -> bool {
+        key.is_none_or(|key| self.group(key).is_some_and(|group| group.clears))
+    }
+}
+
+/// Picks the winner of each impression so that the bids of a seatbid with
+/// `SeatBid.group` = 1 win all of their impressions or none.
+///
+/// Each impression comes with its eligible bids, ranked best first by the
+/// caller. A group clears when its highest ranked bid wins every impression
+/// the group bids on. While some group does not, the failing group of
+/// lowest total price is taken out of the competition, which can let the
+/// bids below it, grouped or not, win.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let candidate = |response: usize, group: bool, impid: &str, price: f64| Candidate {
+///     response,
+///     seatbid: 0,
+///     group,
+///     seat: None,
+///     currency: None,
+///     bid: Bid::builder(format!("{response}-{impid}"), impid, price).into(),
+///     price: price.into(),
+/// };
+///
+/// // The group wins the first impression but not the second, so it loses both.
+/// let rankings = [
+///     vec![candidate(0, true, "1", 5.0), candidate(1, false, "1", 4.0)],
+///     vec![candidate(2, false, "2", 3.0), candidate(0, true, "2", 2.0)],
+/// ];
+/// let resolution = GroupResolver::new().resolve(&rankings);
+///
+/// assert_eq!(resolution.winners, [Some(1), Some(0)]);
+/// assert!(!resolution.groups[0].clears);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GroupResolver {
+    excluded: HashSet<GroupKey>,
+}
+
+impl GroupResolver {
+    /// Creates a resolver where every group may clear.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a group out of the competition, such as a group with a bid that
+    /// is not eligible.
+    #[must_use]
+    pub fn exclude(mut self, key: GroupKey) -> Self {
+        self.excluded.insert(key);
+        self
+    }
+
+    /// Resolves the winners of impressions given their ranked bids.
+    #[must_use]
+    pub fn resolve<C: Borrow<Candidate>>(&self, rankings: &[Vec<C>]) -> GroupResolution {
+        let mut keys = Vec::new();
+        for candidate in rankings.iter().flatten() {
+            if let Some(key) = candidate.borrow().group_key() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let mut excluded = self.excluded.clone();
+        loop {
+            let winners = winners(rankings, &excluded);
+            let failing = keys
+                .iter()
+                .filter(|key| !excluded.contains(key))
+                .map(|&key| (key, group_bids(rankings, &winners, key)))
+                .filter(|(_, bids)| bids.iter().any(|bid| !bid.wins))
+                .map(|(key, bids)| (key, total(&bids)))
+                .reduce(|lowest, next| if next.1 <= lowest.1 { next } else { lowest });
+
+            let Some((key, _)) = failing else {
+                let groups = keys
+                    .iter()
+                    .map(|&key| GroupOutcome {
+                        key,
+                        seat: seat(rankings, key),
+                        clears: !excluded.contains(&key),
+                        bids: group_bids(rankings, &winners, key),
+                    })
+                    .collect();
+                return GroupResolution { winners, groups };
+            };
+            excluded.insert(key);
+        }
+    }
+}
+
+/// Returns the first ranked bid of each impression outside the excluded
+/// groups.
+fn winners<C: Borrow<Candidate>>(
+    rankings: &[Vec<C>],
+    excluded: &HashSet<GroupKey>,
+) -> Vec<Option<usize>> {
+    rankings
+        .iter()
+        .map(|ranking| {
+            ranking.iter().position(|candidate| {
+                candidate
+                    .borrow()
+                    .group_key()
+                    .is_none_or(|key| !excluded.contains(&key))
+            })
+        })
+        .collect()
+}
+
+/// Returns the highest ranked bid of a group on each impression.
+fn group_bids<C: Borrow<Candidate>>(
+    rankings: &[Vec<C>],
+    winners: &[Option<usize>],
+    key: GroupKey,
+) -> Vec<GroupBid> {
+    rankings
+        .iter()
+        .zip(winners)
+        .filter_map(|(ranking, winner)| {
+            let index = ranking
+                .iter()
+                .position(|c| c.borrow().group_key() == Some(key))?;
+            let candidate = ranking[index].borrow();
+            Some(GroupBid {
+                imp_id: candidate.bid.impid.clone(),
+                bid_id: candidate.bid.id.clone(),
+                price: candidate.price,
+                wins: *winner == Some(index),
+            })
+        })
+        .collect()
+}
+
+fn seat<C: Borrow<Candidate>>(rankings: &[Vec<C>], key: GroupKey) -> Option<String> {
+    rankings
+        .iter()
+        .flatten()
+        .map(Borrow::borrow)
+        .find(|c: &&Candidate| c.group_key() == Some(key))
+        .and_then(|c| c.seat.clone())
+}
+
+fn total(bids: &[GroupBid]) -> Price {
+    bids.iter()
+        .fold(Price::default(), |sum, bid| sum + bid.price)
+}
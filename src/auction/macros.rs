@@ -0,0 +1,225 @@
+use super::{AuctionOutcome, Candidate, Loser, Winner};
+use crate::crypto::{encrypt_price, PriceKeys, IV_LEN};
+use crate::encoding::{base64_encode, percent_encode, BASE64};
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::{Bid, BidRequest, Price, PriceExt};
+
+/// Suffix of a macro name asking for the Base64 encoded value, as in
+/// `${AUCTION_PRICE:B64}`.
+const B64: &str = ":B64";
+
+/// Values of the `OpenRTB` auction macros for one bid, substituted in its
+/// `nurl`, `burl`, `lurl` and `adm`.
+///
+/// Values are percent-encoded, so they are safe in a URL. With the `:B64`
+/// suffix, as in `${AUCTION_PRICE:B64}`, the value is Base64 encoded first.
+/// Macros without a value are replaced with an empty string, and unknown
+/// macros are left untouched. The price can be encrypted for partners that
+/// share [`PriceKeys`] with the exchange, see
+/// [`encrypt_price`](AuctionMacros::encrypt_price).
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest::builder().id("1").at(1).imp(Imp::banner(300, 250)).build().unwrap();
+/// let response = BidResponse::builder("1")
+///     .bid("seat", Bid::builder("a", "1", 2.0).nurl("https://dsp.com/win?p=${AUCTION_PRICE}"))
+///     .build()
+///     .unwrap();
+///
+/// let outcome = Auction::new().run(&request, &[response]).unwrap();
+/// let winner = outcome.winners().next().unwrap();
+/// let mut bid = winner.candidate.bid.clone();
+/// AuctionMacros::for_winner(&request, &outcome, winner).apply(&mut bid);
+///
+/// assert_eq!(bid.nurl.as_deref(), Some("https://dsp.com/win?p=2"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuctionMacros {
+    /// `${AUCTION_ID}`: ID of the bid request (`BidRequest.id`).
+    pub auction_id: Option<String>,
+    /// `${AUCTION_BID_ID}`: ID of the bid response (`BidResponse.bidid`).
+    pub bid_id: Option<String>,
+    /// `${AUCTION_IMP_ID}`: ID of the impression (`Bid.impid`).
+    pub imp_id: Option<String>,
+    /// `${AUCTION_SEAT_ID}`: buyer seat of the bid (`SeatBid.seat`).
+    pub seat_id: Option<String>,
+    /// `${AUCTION_AD_ID}`: ID of the ad (`Bid.adid`).
+    pub ad_id: Option<String>,
+    /// `${AUCTION_PRICE}`: clearing price, in the currency of the bid.
+    pub price: Option<Price>,
+    /// `${AUCTION_CURRENCY}`: currency of the bid (`BidResponse.cur`).
+    pub currency: Option<String>,
+    /// `${AUCTION_MBR}`: market bid ratio, the clearing price over the bid
+    /// price.
+    pub mbr: Option<f64>,
+    /// `${AUCTION_LOSS}`: reason the bid lost, or won.
+    pub loss: Option<LossReason>,
+    /// `${AUCTION_MIN_TO_WIN}`: lowest price that would have won the
+    /// impression, in the currency of the bid.
+    pub min_to_win: Option<Price>,
+    /// Keys and initialization vector `${AUCTION_PRICE}` is encrypted with.
+    pub price_encryption: Option<(PriceKeys, [u8; IV_LEN])>,
+}
+
+impl AuctionMacros {
+    /// Creates the values known from the request and the bid alone.
+    #[must_use]
+    pub fn new(request: &BidRequest, bid: &Bid) -> Self {
+        AuctionMacros {
+            auction_id: Some(request.id.clone()),
+            imp_id: Some(bid.impid.clone()),
+            ad_id: bid.adid.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates the values for the winner of an impression. Prices of the
+    /// outcome are converted back to the currency of the bid, at the rate its
+    /// price was converted with.
+    pub fn for_winner(request: &BidRequest, _outcome: &AuctionOutcome, winner: &Winner) -> Self {
+        let candidate = &winner.candidate;
+        AuctionMacros {
+            seat_id: candidate.seat.clone(),
+            price: in_bid_currency(candidate, winner.clearing_price),
+            currency: candidate.currency.as_ref().map(ToString::to_string),
+            mbr: ratio(winner.clearing_price, candidate.price),
+            loss: Some(LossReason::BidWon),
+            ..Self::new(request, &candidate.bid)
+        }
+    }
+
+    /// Creates the values for a bid that lost. The minimum to win is the
+    /// price of the winning bid, if the impression has a winner, in the
+    /// currency of the losing bid.
+    pub fn for_loser(request: &BidRequest, outcome: &AuctionOutcome, loser: &Loser) -> Self {
+        let candidate = &loser.candidate;
+        let winner = outcome
+            .imp(&candidate.bid.impid)
+            .and_then(|imp| imp.winner.as_ref());
+        AuctionMacros {
+            seat_id: candidate.seat.clone(),
+            currency: candidate.currency.as_ref().map(ToString::to_string),
+            loss: Some(loser.reason),
+            min_to_win: winner
+                .and_then(|winner| in_bid_currency(candidate, winner.candidate.price)),
+            ..Self::new(request, &candidate.bid)
+        }
+    }
+
+    /// Sets the ID of the bid response (`BidResponse.bidid`).
+    #[must_use]
+    pub fn bid_id(mut self, bid_id: impl Into<String>) -> Self {
+        self.bid_id = Some(bid_id.into());
+        self
+    }
+
+    /// Encrypts `${AUCTION_PRICE}` with the keys and an initialization vector
+    /// unique to this bid, see [`crypto`](crate::crypto).
+    #[must_use]
+    pub fn encrypt_price(mut self, keys: &PriceKeys, iv: [u8; IV_LEN]) -> Self {
+        self.price_encryption = Some((keys.clone(), iv));
+        self
+    }
+
+    /// Returns the text with every known macro substituted.
+    #[must_use]
+    pub fn substitute(&self, text: &str) -> String {
+        let mut substituted = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            substituted.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let Some(end) = after.find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            if let Some(value) = self.value(&after[..end]) {
+                substituted.push_str(&value);
+                rest = &after[end + 1..];
+            } else {
+                // Unknown macros are kept, but may hold known ones.
+                substituted.push_str("${");
+                rest = after;
+            }
+        }
+        substituted.push_str(rest);
+        substituted
+    }
+
+    /// Substitutes the macros of the notice URLs and markup of a bid
+    /// (`nurl`, `burl`, `lurl` and `adm`).
+    pub fn apply(&self, bid: &mut Bid) {
+        let fields = [&mut bid.nurl, &mut bid.burl, &mut bid.lurl, &mut bid.adm];
+        for text in fields.into_iter().flatten() {
+            *text = self.substitute(text);
+        }
+    }
+
+    /// Returns the encoded value of a macro, empty if it has none, or `None`
+    /// if the macro is unknown.
+    fn value(&self, name: &str) -> Option<String> {
+        let (name, b64) = match name.strip_suffix(B64) {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        let value = match name {
+            "AUCTION_ID" => self.auction_id.clone(),
+            "AUCTION_BID_ID" => self.bid_id.clone(),
+            "AUCTION_IMP_ID" => self.imp_id.clone(),
+            "AUCTION_SEAT_ID" => self.seat_id.clone(),
+            "AUCTION_AD_ID" => self.ad_id.clone(),
+            "AUCTION_PRICE" => self.price.map(|price| match &self.price_encryption {
+                Some((keys, iv)) => encrypt_price(keys, price, *iv),
+                None => price.to_string(),
+            }),
+            "AUCTION_CURRENCY" => self.currency.clone(),
+            "AUCTION_MBR" => self.mbr.map(|mbr| mbr.to_string()),
+            "AUCTION_LOSS" => self.loss.map(|loss| loss.to_string()),
+            "AUCTION_MIN_TO_WIN" => self.min_to_win.map(|price| price.to_string()),
+            _ => return None,
+        }
+        .unwrap_or_default();
+
+        let value = if b64 {
+            base64_encode(value.as_bytes(), BASE64, true)
+        } else {
+            value
+        };
+        Some(percent_encode(&value))
+    }
+}
+
+/// Converts an amount in the auction currency to the currency of the bid,
+/// with the ratio of the bid price to its converted price.
+// Equal prices mean the bid was not converted.
+#[cfg(not(feature = "decimal"))]
+#[allow(clippy::float_cmp)]
+fn in_bid_currency(candidate: &Candidate, amount: Price) -> Option<Price> {
+    candidate.currency.as_ref()?;
+    if candidate.price == candidate.bid.price {
+        return Some(amount);
+    }
+    let price = amount * candidate.bid.price / candidate.price;
+    price.is_finite().then_some(price)
+}
+
+#[cfg(feature = "decimal")]
+fn in_bid_currency(candidate: &Candidate, amount: Price) -> Option<Price> {
+    candidate.currency.as_ref()?;
+    let price = if candidate.price == candidate.bid.price {
+        amount.value()
+    } else {
+        amount
+            .value()
+            .checked_mul(candidate.bid.price.value())?
+            .checked_div(candidate.price.value())?
+    };
+    Some(Price::from(price.normalize()))
+}
+
+fn ratio(clearing_price: Price, price: Price) -> Option<f64> {
+    let price = price.to_f64();
+    (price > 0.0).then(|| clearing_price.to_f64() / price)
+}
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use crate::currency::{currency_or_default, CurrencyConverter, CurrencyError, RateTable, Rounding};
+use crate::openrtb2::types::CurrencyCode;
+use crate::openrtb2::{BidRequest, BidResponse, Price, SeatBid};
+
+/// Separator of the bidder and the seat in namespaced seat names, and of the
+/// bidder and the bid ID in renamed bid IDs.
+const SEPARATOR: char = ':';
+
+/// How the seats of each bidder are named in the merged response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SeatNaming {
+    /// Keep `SeatBid.seat` as returned by the bidder.
+    Keep,
+    /// Prefix the seat with the bidder, as in `bidder:seat`; seatbids without
+    /// a seat are named after the bidder.
+    #[default]
+    Namespace,
+    /// Name every seatbid of a bidder after the bidder.
+    Bidder,
+}
+
+/// Origin of a bid of a merged response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidSource {
+    /// ID of the bid in the merged response.
+    pub id: String,
+    /// Name of the bidder that returned the bid.
+    pub bidder: String,
+    /// ID of the bid as returned by the bidder.
+    pub bid_id: String,
+    /// Seat of the bid as returned by the bidder.
+    pub seat: Option<String>,
+}
+
+/// A merged response, with the origin of each of its bids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedResponse {
+    pub response: BidResponse,
+    /// Origins of the bids, in the order of the response.
+    pub sources: Vec<BidSource>,
+    /// Bidders whose response was left out because its ID is not the ID of
+    /// the request, or because its prices cannot be converted to the request
+    /// currency.
+    pub skipped: Vec<String>,
+}
+
+impl MergedResponse {
+    /// Returns the origin of a bid of the merged response.
+    #[must_use]
+    pub fn source(&self, id: &str) -> Option<&BidSource> {
+        self.sources.iter().find(|source| source.id == id)
+    }
+}
+
+/// Combines the responses of several bidders to one request into a single
+/// response.
+///
+/// Prices are converted to the request currency, the first of
+/// `BidRequest.cur` or USD. Seats are renamed according to the
+/// [`SeatNaming`], and a bid whose ID was already used by an earlier bid is
+/// renamed `bidder:id`. Seatbids are carried over whole, so the bids of a
+/// seatbid with `group` = 1 stay together. When no bidder bids, the
+/// combined `nbr` is the reason given by most bidders.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest::builder().id("1").imp(Imp::banner(300, 250)).build().unwrap();
+/// let a = BidResponse::builder("1").bid("seat", Bid::builder("1", "1", 2.0)).build().unwrap();
+/// let b = BidResponse::builder("1").bid("seat", Bid::builder("1", "1", 3.0)).build().unwrap();
+///
+/// let merged = ResponseMerger::new().merge(&request, [("dsp-a", &a), ("dsp-b", &b)]).unwrap();
+/// let seatbids = merged.response.seatbid.as_ref().unwrap();
+///
+/// assert_eq!(seatbids[0].seat.as_deref(), Some("dsp-a:seat"));
+/// assert_eq!(seatbids[1].seat.as_deref(), Some("dsp-b:seat"));
+/// assert_eq!(seatbids[1].bid[0].id, "dsp-b:1");
+/// assert_eq!(merged.source("dsp-b:1").unwrap().bid_id, "1");
+/// ```
+#[derive(Clone)]
+pub struct ResponseMerger<'a> {
+    converter: Option<&'a dyn CurrencyConverter>,
+    rounding: Rounding,
+    seats: SeatNaming,
+}
+
+impl Default for ResponseMerger<'_> {
+    fn default() -> Self {
+        ResponseMerger {
+            converter: None,
+            rounding: Rounding::Exact,
+            seats: SeatNaming::default(),
+        }
+    }
+}
+
+impl<'a> ResponseMerger<'a> {
+    /// Creates a merger namespacing seats, without rounding or currency
+    /// converter.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the converter used for responses in other currencies.
+    #[must_use]
+    pub fn converter(mut self, converter: &'a dyn CurrencyConverter) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Sets the rounding of converted prices.
+    #[must_use]
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets how seats are named in the merged response.
+    #[must_use]
+    pub fn seat_naming(mut self, seats: SeatNaming) -> Self {
+        self.seats = seats;
+        self
+    }
+
+    /// Merges the responses of named bidders to a request. A response with an
+    /// invalid currency, or with a price that cannot be converted, is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request currency is invalid.
+    pub fn merge<'r>(
+        &self,
+        request: &BidRequest,
+        responses: impl IntoIterator<Item = (&'r str, &'r BidResponse)>,
+    ) -> Result<MergedResponse, CurrencyError> {
+        let empty = RateTable::new();
+        let converter = self.converter.unwrap_or(&empty);
+        let first = request.cur.as_ref().and_then(|cur| cur.first().cloned());
+        let currency = currency_or_default(first.as_deref())?;
+
+        let mut seatbids = Vec::new();
+        let mut sources = Vec::new();
+        let mut skipped = Vec::new();
+        let mut ids = HashSet::new();
+        let mut nbrs = Vec::new();
+
+        for (bidder, response) in responses {
+            if response.id != request.id {
+                skipped.push(bidder.to_string());
+                continue;
+            }
+            let Ok(prices) = self.convert(response, converter, &currency) else {
+                skipped.push(bidder.to_string());
+                continue;
+            };
+
+            let mut bids = 0;
+            for (seatbid, prices) in response.seatbid.iter().flatten().zip(prices) {
+                if seatbid.bid.is_empty() {
+                    continue;
+                }
+                let mut merged = SeatBid {
+                    seat: self.seat(bidder, seatbid.seat.as_deref()),
+                    bid: Vec::with_capacity(seatbid.bid.len()),
+                    ..seatbid.clone()
+                };
+                for (original, price) in seatbid.bid.iter().zip(prices) {
+                    let mut bid = original.clone();
+                    bid.price = price;
+                    bid.id = unique_id(&mut ids, bidder, &bid.id);
+                    sources.push(BidSource {
+                        id: bid.id.clone(),
+                        bidder: bidder.to_string(),
+                        bid_id: original.id.clone(),
+                        seat: seatbid.seat.clone(),
+                    });
+                    merged.bid.push(bid);
+                }
+                bids += merged.bid.len();
+                seatbids.push(merged);
+            }
+            if bids == 0 {
+                nbrs.push(response.nbr);
+            }
+        }
+
+        let nbr = seatbids.is_empty().then(|| combined_nbr(&nbrs));
+        let response = BidResponse {
+            id: request.id.clone(),
+            seatbid: (!seatbids.is_empty()).then_some(seatbids),
+            cur: Some(currency.to_string()),
+            nbr,
+            ..Default::default()
+        };
+
+        Ok(MergedResponse {
+            response,
+            sources,
+            skipped,
+        })
+    }
+
+    /// Returns the prices of the bids of a response, by seatbid, converted
+    /// to the request currency.
+    fn convert(
+        &self,
+        response: &BidResponse,
+        converter: &dyn CurrencyConverter,
+        currency: &CurrencyCode,
+    ) -> Result<Vec<Vec<Price>>, CurrencyError> {
+        let from = currency_or_default(response.cur.as_deref())?;
+        response
+            .seatbid
+            .iter()
+            .flatten()
+            .map(|seatbid| {
+                seatbid
+                    .bid
+                    .iter()
+                    .map(|bid| converter.convert(bid.price, &from, currency, self.rounding))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn seat(&self, bidder: &str, seat: Option<&str>) -> Option<String> {
+        match (self.seats, seat) {
+            (SeatNaming::Keep, seat) => seat.map(str::to_string),
+            (SeatNaming::Namespace, Some(seat)) => Some(format!("{bidder}{SEPARATOR}{seat}")),
+            (SeatNaming::Namespace, None) | (SeatNaming::Bidder, _) => Some(bidder.to_string()),
+        }
+    }
+}
+
+/// Returns a bid ID not used yet, renaming taken ones after the bidder.
+fn unique_id(ids: &mut HashSet<String>, bidder: &str, id: &str) -> String {
+    let mut unique = id.to_string();
+    let mut n = 1;
+    while ids.contains(&unique) {
+        unique = match n {
+            1 => format!("{bidder}{SEPARATOR}{id}"),
+            n => format!("{bidder}{SEPARATOR}{id}{SEPARATOR}{n}"),
+        };
+        n += 1;
+    }
+    ids.insert(unique.clone());
+    unique
+}
+
+/// Picks the no-bid reason given by most bidders, the first given on ties.
+/// Unknown reasons (absent or 0) count only when no bidder gave another one.
+fn combined_nbr(nbrs: &[Option<i64>]) -> i64 {
+    let known: Vec<i64> = nbrs.iter().flatten().copied().filter(|&n| n != 0).collect();
+    let count = |nbr: i64| known.iter().filter(|&&n| n == nbr).count();
+    known
+        .iter()
+        .copied()
+        .fold(None, |best: Option<i64>, nbr| match best {
+            Some(best) if count(best) >= count(nbr) => Some(best),
+            _ => Some(nbr),
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,66 @@
+use super::{AuctionMacros, AuctionOutcome};
+use crate::openrtb2::codes::LossReason;
+use crate::openrtb2::{BidRequest, BidResponse};
+
+/// A loss notice to send for a bid, `Bid.lurl` with its macros substituted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossNotice {
+    /// ID of the losing bid (`Bid.id`).
+    pub bid_id: String,
+    /// ID of the impression (`Bid.impid`).
+    pub imp_id: String,
+    /// Buyer seat of the bid (`SeatBid.seat`).
+    pub seat: Option<String>,
+    pub reason: LossReason,
+    /// The URL to call.
+    pub url: String,
+}
+
+impl AuctionOutcome {
+    /// Builds the loss notice of every losing bid with a `Bid.lurl`,
+    /// including the rejected ones, in the order of [`losers`].
+    ///
+    /// The responses are those the auction was run with; they provide
+    /// `${AUCTION_BID_ID}`. See [`AuctionMacros::for_loser`] for the values of
+    /// the other macros.
+    ///
+    /// [`losers`]: AuctionOutcome::losers
+    ///
+    /// ```
+    /// use iab::auction::*;
+    /// use iab::openrtb2::*;
+    ///
+    /// let request = BidRequest::builder().id("1").at(1).imp(Imp::banner(300, 250)).build().unwrap();
+    /// let responses = [BidResponse::builder("1")
+    ///     .bid("a", Bid::builder("a", "1", 2.0))
+    ///     .bid("b", Bid::builder("b", "1", 1.0).lurl("https://dsp.com/loss?r=${AUCTION_LOSS}"))
+    ///     .build()
+    ///     .unwrap()];
+    ///
+    /// let outcome = Auction::new().run(&request, &responses).unwrap();
+    /// let notices = outcome.loss_notices(&request, &responses);
+    ///
+    /// assert_eq!(notices.len(), 1);
+    /// assert_eq!(notices[0].url, "https://dsp.com/loss?r=102");
+    /// ```
+    #[must_use]
+    pub fn loss_notices(&self, request: &BidRequest, responses: &[BidResponse]) -> Vec<LossNotice> {
+        self.losers()
+            .filter_map(|loser| {
+                let candidate = &loser.candidate;
+                let lurl = candidate.bid.lurl.as_deref()?;
+                let mut macros = AuctionMacros::for_loser(request, self, loser);
+                macros.bid_id = responses
+                    .get(candidate.response)
+                    .and_then(|response| response.bidid.clone());
+                Some(LossNotice {
+                    bid_id: candidate.bid.id.clone(),
+                    imp_id: candidate.bid.impid.clone(),
+                    seat: candidate.seat.clone(),
+                    reason: loser.reason,
+                    url: macros.substitute(lurl),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,555 @@
+use std::cmp::Ordering;
+
+use super::Candidate;
+use crate::openrtb2::{Audio, Bid, BidRequest, Imp, Price, Video};
+
+/// Number of selections explored before settling for the best one found.
+const SEARCH_BUDGET: usize = 100_000;
+
+/// Dimension along which ads of a pod must differ, from the `AdCOM` 1.0
+/// List: Pod Deduplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DedupeBy {
+    /// IAB content category (`Bid.cat`).
+    Category,
+    /// Advertiser domain (`Bid.adomain`).
+    AdvertiserDomain,
+    /// Creative ID (`Bid.crid`).
+    CreativeId,
+    /// Campaign ID (`Bid.cid`).
+    CampaignId,
+}
+
+impl DedupeBy {
+    /// Returns the dimension of a pod deduplication code.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<DedupeBy> {
+        match code {
+            1 => Some(DedupeBy::Category),
+            2 => Some(DedupeBy::AdvertiserDomain),
+            3 => Some(DedupeBy::CreativeId),
+            4 => Some(DedupeBy::CampaignId),
+            _ => None,
+        }
+    }
+}
+
+/// Position of an ad within its pod, from the `AdCOM` 1.0 List: Slot Position in Pod.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotPosition {
+    Any,
+    First,
+    Last,
+    FirstOrLast,
+}
+
+impl SlotPosition {
+    /// Returns the position of a slot position code.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<SlotPosition> {
+        match code {
+            0 => Some(SlotPosition::Any),
+            1 => Some(SlotPosition::First),
+            -1 => Some(SlotPosition::Last),
+            2 => Some(SlotPosition::FirstOrLast),
+            _ => None,
+        }
+    }
+
+    fn first(self) -> bool {
+        self != SlotPosition::Last
+    }
+
+    fn last(self) -> bool {
+        self != SlotPosition::First
+    }
+
+    fn middle(self) -> bool {
+        self == SlotPosition::Any
+    }
+
+    /// Positions allowed by both, if any.
+    fn intersect(self, other: SlotPosition) -> Option<SlotPosition> {
+        match (self.first() && other.first(), self.last() && other.last()) {
+            _ if self.middle() && other.middle() => Some(SlotPosition::Any),
+            (true, true) => Some(SlotPosition::FirstOrLast),
+            (true, false) => Some(SlotPosition::First),
+            (false, true) => Some(SlotPosition::Last),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Why a bid was left out of a pod.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PodRejection {
+    /// The bid is not on an impression of the pod.
+    NotInPod,
+    /// The bid is on a dynamic portion of the pod but has no `Bid.dur`.
+    MissingDuration,
+    /// `Bid.dur` is not allowed by `rqddurs` or `minduration`/`maxduration`,
+    /// or exceeds `poddur`.
+    DurationNotAllowed,
+    /// `Bid.slotinpod` cannot be honored by the slot of the impression.
+    SlotNotAvailable,
+    /// The bid is valid but the most valuable pod does not include it.
+    NotSelected,
+}
+
+/// An ad selected for a pod.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodAd {
+    /// ID of the impression the bid was made on (`Imp.id`).
+    pub imp_id: String,
+    pub candidate: Candidate,
+    /// Position of the ad in the pod.
+    pub position: SlotPosition,
+}
+
+/// An assembled ad pod.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pod {
+    /// ID of the pod (`Video.podid` or `Audio.podid`).
+    pub podid: String,
+    /// Selected ads, in playback order.
+    pub ads: Vec<PodAd>,
+    /// Sum of the prices of the selected ads.
+    pub revenue: Price,
+    /// Sum of the durations of the selected ads, in seconds.
+    pub duration: i64,
+    /// Bids left out of the pod.
+    pub rejected: Vec<(Candidate, PodRejection)>,
+    /// Whether every selection of bids was considered. The search stops
+    /// after a fixed number of steps, in which case the pod is the best one
+    /// found so far, and may not be the most valuable.
+    pub exhaustive: bool,
+}
+
+/// Assembles the revenue-maximizing pod from the bids on the impressions
+/// sharing a `podid`.
+///
+/// Each impression of the pod is either a structured slot, holding a single
+/// ad at the position of its `slotinpod`, or a dynamic portion with a
+/// `poddur`, holding up to `maxseq` ads whose durations add up to at most
+/// `poddur`. Bids must have an allowed `Bid.dur`, their `Bid.slotinpod` must be
+/// compatible with their slot, and no two ads may share a value of the
+/// `poddedupe` dimensions.
+///
+/// The search for the best selection is bounded, so that many bids cannot
+/// stall the auction; [`Pod::exhaustive`] tells whether it completed.
+///
+/// ```
+/// use iab::auction::*;
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest::builder()
+///     .id("1")
+///     .imp(Imp::video(["video/mp4"]).with_video(Video {
+///         mimes: vec!["video/mp4".to_string()],
+///         podid: Some("pod-1".to_string()),
+///         poddur: Some(60),
+///         maxseq: Some(3),
+///         ..Default::default()
+///     }))
+///     .build()
+///     .unwrap();
+///
+/// let candidate = |id: &str, price: f64, dur: i64| Candidate {
+///     response: 0,
+///     seatbid: 0,
+///     group: false,
+///     seat: None,
+///     currency: None,
+///     bid: Bid::builder(id, "1", price).dur(dur).into(),
+///     price: price.into(),
+/// };
+///
+/// let pod = PodBuilder::new(&request, "pod-1").assemble([
+///     candidate("a", 20.0, 60),
+///     candidate("b", 12.0, 30),
+///     candidate("c", 11.0, 30),
+/// ]);
+///
+/// assert_eq!(pod.revenue, 23.0);
+/// assert_eq!(pod.duration, 60);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PodBuilder<'r> {
+    request: &'r BidRequest,
+    podid: String,
+    dedupe: Option<Vec<DedupeBy>>,
+}
+
+impl<'r> PodBuilder<'r> {
+    /// Starts assembling the pod with the given ID.
+    pub fn new(request: &'r BidRequest, podid: impl Into<String>) -> Self {
+        PodBuilder {
+            request,
+            podid: podid.into(),
+            dedupe: None,
+        }
+    }
+
+    /// Sets the deduplication dimensions, replacing the `poddedupe` of the
+    /// impressions. `Audio` has no `poddedupe`, so audio pods are only
+    /// deduplicated when set here.
+    #[must_use]
+    pub fn dedupe(mut self, dedupe: impl IntoIterator<Item = DedupeBy>) -> Self {
+        self.dedupe = Some(dedupe.into_iter().collect());
+        self
+    }
+
+    /// Selects the most valuable set of bids for the pod.
+    pub fn assemble(&self, candidates: impl IntoIterator<Item = Candidate>) -> Pod {
+        let slots: Vec<Slot> = self
+            .request
+            .imp
+            .iter()
+            .filter_map(|imp| Slot::of(imp, &self.podid))
+            .collect();
+        let dedupe = self.dedupe.clone().unwrap_or_else(|| {
+            let mut dedupe = Vec::new();
+            let codes = slots
+                .iter()
+                .flat_map(|slot| slot.media.poddedupe.unwrap_or_default());
+            for by in codes.filter_map(|code| DedupeBy::from_code(*code)) {
+                if !dedupe.contains(&by) {
+                    dedupe.push(by);
+                }
+            }
+            dedupe
+        });
+
+        let mut rejected = Vec::new();
+        let mut placements = Vec::new();
+        for candidate in candidates {
+            match slots.iter().position(|s| s.imp.id == candidate.bid.impid) {
+                None => rejected.push((candidate, PodRejection::NotInPod)),
+                Some(slot) => match slots[slot].accepts(&candidate) {
+                    Ok(position) => placements.push(Placement {
+                        slot,
+                        position,
+                        candidate,
+                    }),
+                    Err(reason) => rejected.push((candidate, reason)),
+                },
+            }
+        }
+
+        // Highest prices first, so that the first selections explored are
+        // the greedy ones and the bound prunes early.
+        placements.sort_by(|a, b| {
+            b.candidate
+                .price
+                .partial_cmp(&a.candidate.price)
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut search = Search {
+            slots: &slots,
+            placements: &placements,
+            dedupe: &dedupe,
+            remaining: suffix_sums(&placements),
+            selected: Vec::new(),
+            best: Vec::new(),
+            best_revenue: Price::default(),
+            budget: SEARCH_BUDGET,
+            cut: false,
+        };
+        search.explore(0, Price::default());
+        let (best, exhaustive) = (search.best, !search.cut);
+
+        let mut ads: Vec<PodAd> = Vec::new();
+        let mut duration = 0;
+        let mut revenue = Price::default();
+        for (i, placement) in placements.into_iter().enumerate() {
+            if best.contains(&i) {
+                duration += placement.candidate.bid.dur.unwrap_or_default();
+                revenue += placement.candidate.price;
+                ads.push(PodAd {
+                    imp_id: slots[placement.slot].imp.id.clone(),
+                    candidate: placement.candidate,
+                    position: placement.position,
+                });
+            } else {
+                rejected.push((placement.candidate, PodRejection::NotSelected));
+            }
+        }
+
+        Pod {
+            podid: self.podid.clone(),
+            ads: playback_order(ads),
+            revenue,
+            duration,
+            rejected,
+            exhaustive,
+        }
+    }
+}
+
+/// Pod fields shared by `Video` and `Audio`.
+struct Media<'a> {
+    minduration: Option<i64>,
+    maxduration: Option<i64>,
+    rqddurs: Option<&'a [i64]>,
+    poddur: Option<i64>,
+    maxseq: Option<i64>,
+    slotinpod: Option<i64>,
+    poddedupe: Option<&'a [i64]>,
+}
+
+impl<'a> From<&'a Video> for Media<'a> {
+    fn from(video: &'a Video) -> Self {
+        Media {
+            minduration: video.minduration,
+            maxduration: video.maxduration,
+            rqddurs: video.rqddurs.as_deref(),
+            poddur: video.poddur,
+            maxseq: video.maxseq,
+            slotinpod: video.slotinpod,
+            poddedupe: video.poddedupe.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a Audio> for Media<'a> {
+    fn from(audio: &'a Audio) -> Self {
+        Media {
+            minduration: audio.minduration,
+            maxduration: audio.maxduration,
+            rqddurs: audio.rqddurs.as_deref(),
+            poddur: audio.poddur,
+            maxseq: audio.maxseq,
+            slotinpod: audio.slotinpod,
+            poddedupe: None,
+        }
+    }
+}
+
+/// An impression of the pod.
+struct Slot<'a> {
+    imp: &'a Imp,
+    media: Media<'a>,
+}
+
+impl<'a> Slot<'a> {
+    fn of(imp: &'a Imp, podid: &str) -> Option<Self> {
+        let media = match (&imp.video, &imp.audio) {
+            (Some(video), _) if video.podid.as_deref() == Some(podid) => Media::from(video),
+            (_, Some(audio)) if audio.podid.as_deref() == Some(podid) => Media::from(audio),
+            _ => return None,
+        };
+        Some(Slot { imp, media })
+    }
+
+    fn dynamic(&self) -> bool {
+        self.media.poddur.is_some()
+    }
+
+    /// Maximum number of ads in the slot.
+    fn capacity(&self) -> usize {
+        match (self.dynamic(), self.media.maxseq) {
+            (false, _) => 1,
+            (true, Some(maxseq)) if maxseq > 0 => usize::try_from(maxseq).unwrap_or(usize::MAX),
+            (true, _) => usize::MAX,
+        }
+    }
+
+    /// Position of the ad of a structured slot.
+    fn position(&self) -> SlotPosition {
+        if self.dynamic() {
+            SlotPosition::Any
+        } else {
+            self.media
+                .slotinpod
+                .and_then(SlotPosition::from_code)
+                .unwrap_or(SlotPosition::Any)
+        }
+    }
+
+    /// Checks a bid against the slot, returning the positions it may take.
+    fn accepts(&self, candidate: &Candidate) -> Result<SlotPosition, PodRejection> {
+        let bid = &candidate.bid;
+        match bid.dur {
+            None if self.dynamic() => return Err(PodRejection::MissingDuration),
+            None => {}
+            Some(dur) => {
+                let allowed = match self.media.rqddurs {
+                    Some(rqddurs) => rqddurs.contains(&dur),
+                    None => {
+                        self.media.minduration.is_none_or(|min| dur >= min)
+                            && self.media.maxduration.is_none_or(|max| dur <= max)
+                    }
+                };
+                if !allowed || self.media.poddur.is_some_and(|poddur| dur > poddur) {
+                    return Err(PodRejection::DurationNotAllowed);
+                }
+            }
+        }
+
+        let requested = bid
+            .slotinpod
+            .and_then(SlotPosition::from_code)
+            .unwrap_or(SlotPosition::Any);
+        self.position()
+            .intersect(requested)
+            .ok_or(PodRejection::SlotNotAvailable)
+    }
+}
+
+/// A bid that may be placed in a slot of the pod, at the given positions.
+struct Placement {
+    slot: usize,
+    position: SlotPosition,
+    candidate: Candidate,
+}
+
+/// Branch and bound search of the most valuable selection of placements.
+struct Search<'a> {
+    slots: &'a [Slot<'a>],
+    placements: &'a [Placement],
+    dedupe: &'a [DedupeBy],
+    /// Sum of the prices of the placements from each index onwards.
+    remaining: Vec<Price>,
+    selected: Vec<usize>,
+    best: Vec<usize>,
+    best_revenue: Price,
+    budget: usize,
+    /// Whether selections were left unexplored for lack of budget.
+    cut: bool,
+}
+
+impl Search<'_> {
+    fn explore(&mut self, index: usize, revenue: Price) {
+        if revenue > self.best_revenue {
+            self.best_revenue = revenue;
+            self.best = self.selected.clone();
+        }
+        if index == self.placements.len() || revenue + self.remaining[index] <= self.best_revenue {
+            return;
+        }
+        if self.budget == 0 {
+            self.cut = true;
+            return;
+        }
+        self.budget -= 1;
+
+        if self.fits(index) {
+            self.selected.push(index);
+            self.explore(index + 1, revenue + self.placements[index].candidate.price);
+            self.selected.pop();
+        }
+        self.explore(index + 1, revenue);
+    }
+
+    /// Checks whether a placement can join the current selection.
+    fn fits(&self, index: usize) -> bool {
+        let placement = &self.placements[index];
+        let slot = &self.slots[placement.slot];
+        let in_slot: Vec<&Placement> = self
+            .selected
+            .iter()
+            .map(|i| &self.placements[*i])
+            .filter(|p| p.slot == placement.slot)
+            .collect();
+
+        if in_slot.len() >= slot.capacity() {
+            return false;
+        }
+        if let Some(poddur) = slot.media.poddur {
+            let used: i64 = in_slot
+                .iter()
+                .map(|p| p.candidate.bid.dur.unwrap_or_default())
+                .sum();
+            if used + placement.candidate.bid.dur.unwrap_or_default() > poddur {
+                return false;
+            }
+        }
+
+        let conflicts = self.selected.iter().any(|i| {
+            let other = &self.placements[*i].candidate.bid;
+            self.dedupe
+                .iter()
+                .any(|by| duplicates(*by, &placement.candidate.bid, other))
+        });
+        if conflicts {
+            return false;
+        }
+
+        let positions: Vec<SlotPosition> = self
+            .selected
+            .iter()
+            .map(|i| self.placements[*i].position)
+            .chain([placement.position])
+            .collect();
+        positions_fit(&positions)
+    }
+}
+
+/// Checks whether two bids share a value of a deduplication dimension.
+fn duplicates(by: DedupeBy, a: &Bid, b: &Bid) -> bool {
+    fn overlap(a: Option<&[String]>, b: Option<&[String]>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => a.iter().any(|value| b.contains(value)),
+            _ => false,
+        }
+    }
+    fn same(a: Option<&str>, b: Option<&str>) -> bool {
+        a.is_some() && a == b
+    }
+
+    match by {
+        DedupeBy::Category => overlap(a.cat.as_deref(), b.cat.as_deref()),
+        DedupeBy::AdvertiserDomain => overlap(a.adomain.as_deref(), b.adomain.as_deref()),
+        DedupeBy::CreativeId => same(a.crid.as_deref(), b.crid.as_deref()),
+        DedupeBy::CampaignId => same(a.cid.as_deref(), b.cid.as_deref()),
+    }
+}
+
+/// Checks whether ads with the given position requirements can be ordered
+/// in a pod. A single ad is both first and last.
+fn positions_fit(positions: &[SlotPosition]) -> bool {
+    if positions.len() < 2 {
+        return true;
+    }
+    let count = |position| positions.iter().filter(|p| **p == position).count();
+    let (first, last, either) = (
+        count(SlotPosition::First),
+        count(SlotPosition::Last),
+        count(SlotPosition::FirstOrLast),
+    );
+    first <= 1 && last <= 1 && first + last + either <= 2
+}
+
+/// Orders the ads of a pod, placing those that require the first or last
+/// position at either end and the others by decreasing price.
+fn playback_order(mut ads: Vec<PodAd>) -> Vec<PodAd> {
+    let take = |ads: &mut Vec<PodAd>, position: SlotPosition| {
+        ads.iter()
+            .position(|ad| ad.position == position)
+            .map(|i| ads.remove(i))
+    };
+
+    let mut first = take(&mut ads, SlotPosition::First);
+    let mut last = take(&mut ads, SlotPosition::Last);
+    while let Some(ad) = take(&mut ads, SlotPosition::FirstOrLast) {
+        if first.is_none() {
+            first = Some(ad);
+        } else {
+            last = Some(ad);
+        }
+    }
+
+    let mut ordered: Vec<PodAd> = first.into_iter().collect();
+    ordered.append(&mut ads);
+    ordered.extend(last);
+    ordered
+}
+
+fn suffix_sums(placements: &[Placement]) -> Vec<Price> {
+    let mut sums = vec![Price::default(); placements.len() + 1];
+    for i in (0..placements.len()).rev() {
+        sums[i] = sums[i + 1] + placements[i].candidate.price;
+    }
+    sums
+}
@@ -0,0 +1,216 @@
+//! # Avro
+//!
+//! Apache Avro schemas and object container file support for the `OpenRTB`
+//! types, enabled with the `avro` feature.
+//!
+//! Schemas are derived from the structures in [`crate::openrtb2`], including
+//! their documentation. Every optional field defaults to `null`, so files
+//! written by an older version of this crate can still be read after fields
+//! are added, and fields unknown to the current version are dropped on read.
+//!
+//! Extension objects (`ext`) have no fixed shape and are stored as JSON
+//! encoded strings.
+//!
+//! ```
+//! use iab::avro;
+//! use iab::openrtb2::*;
+//!
+//! let request = BidRequest {
+//!     id: "1".to_string(),
+//!     imp: vec![Imp {
+//!         id: "1".to_string(),
+//!         ..Default::default()
+//!     }],
+//!     ..Default::default()
+//! };
+//!
+//! let requests = vec![request];
+//! let file = avro::write_container(&requests, Vec::new(), avro::Codec::Null).unwrap();
+//! let decoded: Vec<BidRequest> = avro::read_container(&file[..]).unwrap();
+//!
+//! assert_eq!(decoded, requests);
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use apache_avro::schema::{Name, NamespaceRef, UnionSchema};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{Reader, Writer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+pub use apache_avro::{AvroSchema, Codec, Schema};
+
+/// Name of the extension fields stored as JSON encoded strings.
+const EXT_FIELD: &str = "ext";
+
+/// Errors raised while encoding or decoding Avro data.
+#[derive(Debug)]
+pub enum AvroError {
+    /// Avro schema or encoding error.
+    Avro(apache_avro::Error),
+    /// Conversion between the `OpenRTB` structures and JSON failed.
+    Json(serde_json::Error),
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for AvroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AvroError::Avro(e) => write!(f, "avro error: {e}"),
+            AvroError::Json(e) => write!(f, "json error: {e}"),
+            AvroError::Io(e) => write!(f, "io error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AvroError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AvroError::Avro(e) => Some(e),
+            AvroError::Json(e) => Some(e),
+            AvroError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<apache_avro::Error> for AvroError {
+    fn from(e: apache_avro::Error) -> Self {
+        AvroError::Avro(e)
+    }
+}
+
+impl From<serde_json::Error> for AvroError {
+    fn from(e: serde_json::Error) -> Self {
+        AvroError::Json(e)
+    }
+}
+
+impl From<std::io::Error> for AvroError {
+    fn from(e: std::io::Error) -> Self {
+        AvroError::Io(e)
+    }
+}
+
+/// Schema of the `ext` fields: a nullable JSON encoded string.
+pub(crate) fn ext_schema(_: &mut HashSet<Name>, _: NamespaceRef) -> Schema {
+    Schema::Union(
+        UnionSchema::new(vec![Schema::Null, Schema::String])
+            .expect("null and string form a valid union"),
+    )
+}
+
+/// Returns the Avro schema of an `OpenRTB` object, such as `BidRequest` or `BidResponse`.
+#[must_use]
+pub fn schema<T: AvroSchema>() -> Schema {
+    T::get_schema()
+}
+
+/// Writes the Avro schema of an `OpenRTB` object to a file as pretty-printed JSON.
+///
+/// # Errors
+///
+/// Fails if the schema cannot be serialized or the file cannot be written.
+pub fn write_schema<T: AvroSchema>(path: impl AsRef<Path>) -> Result<(), AvroError> {
+    let schema = serde_json::to_string_pretty(&T::get_schema())?;
+    fs::write(path, schema)?;
+    Ok(())
+}
+
+/// Writes records to an Avro object container file, embedding their schema.
+///
+/// Returns the underlying writer once all data has been flushed.
+///
+/// # Errors
+///
+/// Fails if a record does not match the schema of `T`, or if writing fails.
+pub fn write_container<T, W>(records: &[T], writer: W, codec: Codec) -> Result<W, AvroError>
+where
+    T: AvroSchema + Serialize,
+    W: Write,
+{
+    let schema = T::get_schema();
+    let mut writer = Writer::with_codec(&schema, writer, codec)?;
+
+    for record in records {
+        let mut json = serde_json::to_value(record)?;
+        encode_ext(&mut json);
+        let value = AvroValue::try_from(json)?.resolve(&schema)?;
+        writer.append_value(value)?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Reads all records from an Avro object container file.
+///
+/// The schema embedded in the file is resolved against the schema of `T`,
+/// so files written by other versions of this crate can be read.
+///
+/// # Errors
+///
+/// Fails if the container is invalid, if its schema does not resolve against
+/// the schema of `T`, or if a record cannot be decoded.
+pub fn read_container<T, R>(reader: R) -> Result<Vec<T>, AvroError>
+where
+    T: AvroSchema + DeserializeOwned,
+    R: Read,
+{
+    let schema = T::get_schema();
+    let reader = Reader::builder(reader).reader_schema(&schema).build()?;
+
+    let mut records = Vec::new();
+    for value in reader {
+        let mut json = Value::try_from(value?)?;
+        decode_ext(&mut json);
+        records.push(serde_json::from_value(json)?);
+    }
+
+    Ok(records)
+}
+
+/// Replaces every `ext` object with its JSON encoded string.
+fn encode_ext(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == EXT_FIELD {
+                    if !value.is_null() {
+                        *value = Value::String(value.to_string());
+                    }
+                } else {
+                    encode_ext(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(encode_ext),
+        _ => {}
+    }
+}
+
+/// Parses every `ext` string back into its JSON value.
+fn decode_ext(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == EXT_FIELD {
+                    if let Value::String(encoded) = value {
+                        if let Ok(decoded) = serde_json::from_str(encoded) {
+                            *value = decoded;
+                        }
+                    }
+                } else {
+                    decode_ext(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(decode_ext),
+        _ => {}
+    }
+}
@@ -0,0 +1,64 @@
+//! # Crypto
+//!
+//! Protection of the notices an exchange sends to bidders: encrypted
+//! clearing prices and signed notification URLs.
+//!
+//! ## Price encryption
+//!
+//! Exchanges encrypt `${AUCTION_PRICE}` so that clearing prices do not
+//! travel in clear text, with the widely documented HMAC-SHA1 and XOR
+//! scheme:
+//!
+//! 1. The price, in micros of the currency unit, is written as 8 bytes, big
+//!    endian.
+//! 2. A pad, the first 8 bytes of the HMAC-SHA1 of a 16-byte initialization
+//!    vector keyed with the encryption key, is combined with the price by XOR.
+//! 3. A signature, the first 4 bytes of the HMAC-SHA1 of the price bytes
+//!    followed by the initialization vector keyed with the integrity key, is
+//!    appended.
+//! 4. The initialization vector, encrypted price and signature are encoded
+//!    with web-safe Base64, without padding, for 38 characters in total.
+//!
+//! The initialization vector must be unique to each encryption, such as a
+//! timestamp followed by a server ID.
+//!
+//! ```
+//! use iab::crypto::*;
+//!
+//! let keys = PriceKeys::new(b"encryption key", b"integrity key");
+//! let iv = *b"0123456789abcdef";
+//!
+//! let encrypted = encrypt_price(&keys, 1.25.into(), iv);
+//! assert_eq!(encrypted.len(), 38);
+//! assert_eq!(decrypt_price(&keys, &encrypted).unwrap(), 1.25);
+//!
+//! let other = PriceKeys::new(b"encryption key", b"another key");
+//! assert_eq!(decrypt_price(&other, &encrypted), Err(PriceError::IntegrityCheckFailed));
+//! ```
+//!
+//! ## Signed notices
+//!
+//! [`NoticeSigner`] appends the auction, bid and impression IDs, the price
+//! and a timestamp to a notification URL, followed by an HMAC-SHA256 of the
+//! whole URL, so that a forged or modified `Bid.nurl` or `Bid.burl` can be
+//! told apart. [`NoticeVerifier`] checks the signature and rejects notices
+//! outside its time window or already seen within it.
+//!
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use iab::crypto::*;
+//!
+//! let now = SystemTime::now();
+//! let notice = SignedNotice::new("auction-1", "bid-1", "1", 1.25.into(), now);
+//! let url = NoticeSigner::new(b"secret").sign("https://ssp.com/win", &notice);
+//!
+//! let mut verifier = NoticeVerifier::new(b"secret", Duration::from_secs(300));
+//! assert_eq!(verifier.verify(&url, now), Ok(notice));
+//! assert_eq!(verifier.verify(&url, now), Err(NoticeError::Replayed));
+//! ```
+
+mod notice;
+mod price;
+
+pub use notice::{NoticeError, NoticeSigner, NoticeVerifier, SignedNotice};
+pub use price::{decrypt_price, encrypt_price, PriceError, PriceKeys, IV_LEN};
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::encoding::{base64_decode, base64_encode, percent_decode, percent_encode, BASE64_URL};
+use crate::openrtb2::{Bid, Price};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Names of the query parameters appended to a signed URL.
+const AUCTION: &str = "sig_auction";
+const BID: &str = "sig_bid";
+const IMP: &str = "sig_imp";
+const PRICE: &str = "sig_price";
+const TIMESTAMP: &str = "sig_ts";
+const SIGNATURE: &str = "&sig=";
+
+/// Errors raised when verifying a signed notification URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoticeError {
+    /// The URL does not end with a signature.
+    Unsigned,
+    /// The signature does not match, so the URL was altered or signed with
+    /// another key.
+    InvalidSignature,
+    /// A signed parameter is missing or invalid.
+    Malformed(&'static str),
+    /// The timestamp is further from the current time than the window.
+    Expired,
+    /// The notice was already verified within the window.
+    Replayed,
+}
+
+impl fmt::Display for NoticeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoticeError::Unsigned => write!(f, "notification URL is not signed"),
+            NoticeError::InvalidSignature => write!(f, "notification URL signature does not match"),
+            NoticeError::Malformed(name) => write!(f, "missing or invalid {name} parameter"),
+            NoticeError::Expired => write!(f, "notification is outside the time window"),
+            NoticeError::Replayed => write!(f, "notification was already received"),
+        }
+    }
+}
+
+impl std::error::Error for NoticeError {}
+
+/// The values covered by the signature of a notification URL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedNotice {
+    /// ID of the bid request (`BidRequest.id`).
+    pub auction_id: String,
+    /// ID of the bid (`Bid.id`).
+    pub bid_id: String,
+    /// ID of the impression (`Bid.impid`).
+    pub imp_id: String,
+    /// Clearing price, in the auction currency.
+    pub price: Price,
+    /// Time of signing, in seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl SignedNotice {
+    /// Creates the values of a notice signed at the given time.
+    pub fn new(
+        auction_id: impl Into<String>,
+        bid_id: impl Into<String>,
+        imp_id: impl Into<String>,
+        price: Price,
+        time: SystemTime,
+    ) -> Self {
+        SignedNotice {
+            auction_id: auction_id.into(),
+            bid_id: bid_id.into(),
+            imp_id: imp_id.into(),
+            price,
+            timestamp: unix_seconds(time),
+        }
+    }
+}
+
+/// Signs notification URLs with a secret key.
+#[derive(Clone)]
+pub struct NoticeSigner {
+    key: Vec<u8>,
+}
+
+impl NoticeSigner {
+    /// Creates a signer with the secret key.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        NoticeSigner { key: key.into() }
+    }
+
+    /// Returns the URL with the values of the notice appended as query
+    /// parameters, followed by a `sig` parameter signing everything before
+    /// it.
+    #[must_use]
+    pub fn sign(&self, url: &str, notice: &SignedNotice) -> String {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let mut signed = format!(
+            "{url}{separator}{AUCTION}={}&{BID}={}&{IMP}={}&{PRICE}={}&{TIMESTAMP}={}",
+            percent_encode(&notice.auction_id),
+            percent_encode(&notice.bid_id),
+            percent_encode(&notice.imp_id),
+            percent_encode(&notice.price.to_string()),
+            notice.timestamp,
+        );
+        let signature = hmac(&self.key, &signed).finalize().into_bytes();
+        signed.push_str(SIGNATURE);
+        signed.push_str(&base64_encode(&signature, BASE64_URL, false));
+        signed
+    }
+
+    /// Signs the win and billing notice URLs of a bid (`nurl` and `burl`)
+    /// with its clearing price. Auction macros must be substituted first,
+    /// since any later change to the URLs breaks their signature.
+    pub fn sign_bid(&self, bid: &mut Bid, auction_id: &str, price: Price, time: SystemTime) {
+        let notice = SignedNotice::new(auction_id, &bid.id, &bid.impid, price, time);
+        for url in [&mut bid.nurl, &mut bid.burl].into_iter().flatten() {
+            *url = self.sign(url, &notice);
+        }
+    }
+}
+
+/// Keys are left out so they do not end up in logs.
+impl fmt::Debug for NoticeSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoticeSigner").finish_non_exhaustive()
+    }
+}
+
+/// Verifies notification URLs signed by a [`NoticeSigner`] with the same
+/// key.
+///
+/// A notice is accepted once: the signatures verified within the window are
+/// kept to reject replays, and older notices are rejected as expired.
+#[derive(Clone)]
+pub struct NoticeVerifier {
+    key: Vec<u8>,
+    window: Duration,
+    /// Signatures already verified, with their timestamp.
+    seen: HashMap<Vec<u8>, u64>,
+}
+
+impl NoticeVerifier {
+    /// Creates a verifier with the secret key, accepting notices signed at
+    /// most `window` away from the time of verification.
+    pub fn new(key: impl Into<Vec<u8>>, window: Duration) -> Self {
+        NoticeVerifier {
+            key: key.into(),
+            window,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Checks the signature and time of a notification URL received at
+    /// `now`, and returns its signed values.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the URL is not signed with the key, is missing a signed value,
+    /// was signed outside the window, or was already verified.
+    pub fn verify(&mut self, url: &str, now: SystemTime) -> Result<SignedNotice, NoticeError> {
+        let start = url.rfind(SIGNATURE).ok_or(NoticeError::Unsigned)?;
+        let (signed, signature) = (&url[..start], &url[start + SIGNATURE.len()..]);
+        let signature =
+            base64_decode(signature, BASE64_URL).ok_or(NoticeError::InvalidSignature)?;
+        hmac(&self.key, signed)
+            .verify_slice(&signature)
+            .map_err(|_| NoticeError::InvalidSignature)?;
+
+        let notice = parse(signed)?;
+        let now = unix_seconds(now);
+        let window = self.window.as_secs();
+        if notice.timestamp.abs_diff(now) > window {
+            return Err(NoticeError::Expired);
+        }
+
+        self.seen
+            .retain(|_, timestamp| timestamp.abs_diff(now) <= window);
+        if self.seen.insert(signature, notice.timestamp).is_some() {
+            return Err(NoticeError::Replayed);
+        }
+        Ok(notice)
+    }
+}
+
+/// Keys are left out so they do not end up in logs.
+impl fmt::Debug for NoticeVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoticeVerifier")
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the signed values of a URL, the last occurrence of each parameter
+/// winning since the signer appends them.
+fn parse(url: &str) -> Result<SignedNotice, NoticeError> {
+    let query = url.split_once('?').map_or("", |(_, query)| query);
+    let param = |name: &'static str| {
+        query
+            .rsplit('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .and_then(|(_, value)| percent_decode(value))
+            .ok_or(NoticeError::Malformed(name))
+    };
+
+    Ok(SignedNotice {
+        auction_id: param(AUCTION)?,
+        bid_id: param(BID)?,
+        imp_id: param(IMP)?,
+        price: param(PRICE)?
+            .parse()
+            .map_err(|_| NoticeError::Malformed(PRICE))?,
+        timestamp: param(TIMESTAMP)?
+            .parse()
+            .map_err(|_| NoticeError::Malformed(TIMESTAMP))?,
+    })
+}
+
+fn hmac(key: &[u8], message: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message.as_bytes());
+    mac
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
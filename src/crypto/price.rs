@@ -0,0 +1,152 @@
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use crate::encoding::{base64_decode, base64_encode, BASE64_URL};
+use crate::openrtb2::{Price, PriceExt};
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Length of the initialization vector, in bytes.
+pub const IV_LEN: usize = 16;
+
+const PRICE_LEN: usize = 8;
+const SIGNATURE_LEN: usize = 4;
+
+/// Micros in a unit of currency.
+const MICROS: f64 = 1_000_000.0;
+
+/// Errors raised when decrypting a price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriceError {
+    /// The text is not web-safe Base64.
+    InvalidEncoding,
+    /// The decoded message is not 28 bytes long.
+    InvalidLength(usize),
+    /// The signature does not match, so the price was not encrypted with
+    /// these keys or was altered.
+    IntegrityCheckFailed,
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceError::InvalidEncoding => write!(f, "invalid web-safe base64"),
+            PriceError::InvalidLength(len) => {
+                write!(
+                    f,
+                    "encrypted price of {len} bytes, expected {}",
+                    IV_LEN + PRICE_LEN + SIGNATURE_LEN
+                )
+            }
+            PriceError::IntegrityCheckFailed => write!(f, "integrity check failed"),
+        }
+    }
+}
+
+impl std::error::Error for PriceError {}
+
+/// The encryption and integrity keys shared with a partner.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PriceKeys {
+    encryption: Vec<u8>,
+    integrity: Vec<u8>,
+}
+
+impl PriceKeys {
+    /// Creates the keys from their raw bytes.
+    pub fn new(encryption: impl Into<Vec<u8>>, integrity: impl Into<Vec<u8>>) -> Self {
+        PriceKeys {
+            encryption: encryption.into(),
+            integrity: integrity.into(),
+        }
+    }
+
+    /// Creates the keys from their web-safe Base64 encoding, the form in
+    /// which they are usually distributed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a key is not web-safe Base64.
+    pub fn from_base64(encryption: &str, integrity: &str) -> Result<Self, PriceError> {
+        let decode = |key| base64_decode(key, BASE64_URL).ok_or(PriceError::InvalidEncoding);
+        Ok(PriceKeys::new(decode(encryption)?, decode(integrity)?))
+    }
+
+    fn pad(&self, iv: &[u8]) -> [u8; PRICE_LEN] {
+        let mut pad = [0; PRICE_LEN];
+        pad.copy_from_slice(&hmac(&self.encryption, &[iv]).finalize().into_bytes()[..PRICE_LEN]);
+        pad
+    }
+}
+
+/// Keys are left out so they do not end up in logs.
+impl fmt::Debug for PriceKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriceKeys").finish_non_exhaustive()
+    }
+}
+
+/// Encrypts a price with the keys and a unique initialization vector.
+///
+/// The price is rounded to micros; negative prices encrypt as zero.
+#[must_use]
+pub fn encrypt_price(keys: &PriceKeys, price: Price, iv: [u8; IV_LEN]) -> String {
+    let plain = to_micros(price).to_be_bytes();
+    let pad = keys.pad(&iv);
+
+    let mut message = Vec::with_capacity(IV_LEN + PRICE_LEN + SIGNATURE_LEN);
+    message.extend_from_slice(&iv);
+    message.extend(plain.iter().zip(pad).map(|(p, k)| p ^ k));
+    let signature = hmac(&keys.integrity, &[&plain, &iv]).finalize();
+    message.extend_from_slice(&signature.into_bytes()[..SIGNATURE_LEN]);
+
+    base64_encode(&message, BASE64_URL, false)
+}
+
+/// Decrypts a price encrypted with [`encrypt_price`], checking its
+/// signature. Padded input is accepted.
+///
+/// # Errors
+///
+/// Fails if the text is not a price encrypted with these keys.
+pub fn decrypt_price(keys: &PriceKeys, encrypted: &str) -> Result<Price, PriceError> {
+    let message = base64_decode(encrypted, BASE64_URL).ok_or(PriceError::InvalidEncoding)?;
+    if message.len() != IV_LEN + PRICE_LEN + SIGNATURE_LEN {
+        return Err(PriceError::InvalidLength(message.len()));
+    }
+    let (iv, rest) = message.split_at(IV_LEN);
+    let (cipher, signature) = rest.split_at(PRICE_LEN);
+
+    let pad = keys.pad(iv);
+    let mut plain = [0; PRICE_LEN];
+    for (p, (c, k)) in plain.iter_mut().zip(cipher.iter().zip(pad)) {
+        *p = c ^ k;
+    }
+    hmac(&keys.integrity, &[&plain, iv])
+        .verify_truncated_left(signature)
+        .map_err(|_| PriceError::IntegrityCheckFailed)?;
+
+    Ok(from_micros(u64::from_be_bytes(plain)))
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> HmacSha1 {
+    let mut mac = HmacSha1::new_from_slice(key).expect("HMAC takes keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+// The rounded price is non-negative, and the cast saturates above `u64::MAX`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_micros(price: Price) -> u64 {
+    (price.to_f64() * MICROS).round().max(0.0) as u64
+}
+
+// Prices are far below 2^53 micros, where `f64` stops being exact.
+#[allow(clippy::cast_precision_loss)]
+fn from_micros(micros: u64) -> Price {
+    Price::from_f64(micros as f64 / MICROS)
+}
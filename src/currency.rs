@@ -0,0 +1,583 @@
+//! # Currency
+//!
+//! Conversion of floors and bid prices between currencies.
+//!
+//! `BidRequest.cur`, `Imp.bidfloorcur`, `Deal.bidfloorcur` and
+//! `BidResponse.cur` allow multi-currency auctions. A [`CurrencyConverter`]
+//! provides exchange rates, and [`BidRequest::convert_floors`] and
+//! [`BidResponse::convert_prices`] normalize every amount of an object to a
+//! single currency so that bids and floors can be compared.
+//!
+//! [`RateTable`] is an in-memory converter loaded from a JSON or CSV file.
+//!
+//! ```
+//! use iab::currency::*;
+//! use iab::openrtb2::types::CurrencyCode;
+//! use iab::openrtb2::*;
+//!
+//! let rates = RateTable::from_json_str(r#"{"base": "USD", "rates": {"EUR": 0.8}}"#).unwrap();
+//! let usd = CurrencyCode::new("USD").unwrap();
+//!
+//! let mut request = BidRequest::builder()
+//!     .id("1")
+//!     .imp(Imp::banner(300, 250).floor(1.0, "EUR"))
+//!     .build()
+//!     .unwrap();
+//!
+//! request.convert_floors(&rates, &usd, Rounding::Up(2)).unwrap();
+//!
+//! assert_eq!(request.imp[0].bidfloor.unwrap(), 1.25);
+//! assert_eq!(request.imp[0].bidfloorcur.as_deref(), Some("USD"));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::openrtb2::types::{CurrencyCode, InvalidValue};
+use crate::openrtb2::{BidRequest, BidResponse, DurFloors, Price};
+
+/// Currency of amounts whose currency is not specified, per `OpenRTB` 2.6.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+/// Exchange rate, the amount of the target currency bought by one unit of
+/// the source currency.
+#[cfg(not(feature = "decimal"))]
+pub type Rate = f64;
+
+/// Exchange rate, the amount of the target currency bought by one unit of
+/// the source currency.
+#[cfg(feature = "decimal")]
+pub type Rate = crate::money::Decimal;
+
+/// Errors raised while loading rates or converting amounts.
+#[derive(Debug)]
+pub enum CurrencyError {
+    /// No rate is known between the two currencies.
+    UnknownRate {
+        from: CurrencyCode,
+        to: CurrencyCode,
+    },
+    /// A currency code is not a valid ISO-4217 code.
+    InvalidCurrency(InvalidValue),
+    /// A rate is not a positive, finite number.
+    InvalidRate {
+        from: CurrencyCode,
+        to: CurrencyCode,
+    },
+    /// A converted amount is too large for a price.
+    Overflow {
+        from: CurrencyCode,
+        to: CurrencyCode,
+    },
+    /// A rate file is malformed; `line` is 1-based for CSV and 0 for JSON.
+    Parse { line: usize, reason: String },
+    /// A rate file could not be read.
+    Io(io::Error),
+    /// A JSON rate file is not valid JSON.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrencyError::UnknownRate { from, to } => {
+                write!(f, "no exchange rate from {from} to {to}")
+            }
+            CurrencyError::InvalidCurrency(e) => e.fmt(f),
+            CurrencyError::InvalidRate { from, to } => {
+                write!(f, "exchange rate from {from} to {to} must be positive")
+            }
+            CurrencyError::Overflow { from, to } => {
+                write!(f, "amount converted from {from} to {to} is out of range")
+            }
+            CurrencyError::Parse { line: 0, reason } => write!(f, "invalid rates: {reason}"),
+            CurrencyError::Parse { line, reason } => {
+                write!(f, "invalid rates on line {line}: {reason}")
+            }
+            CurrencyError::Io(e) => e.fmt(f),
+            CurrencyError::Json(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CurrencyError::InvalidCurrency(e) => Some(e),
+            CurrencyError::Io(e) => Some(e),
+            CurrencyError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidValue> for CurrencyError {
+    fn from(e: InvalidValue) -> Self {
+        CurrencyError::InvalidCurrency(e)
+    }
+}
+
+impl From<io::Error> for CurrencyError {
+    fn from(e: io::Error) -> Self {
+        CurrencyError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CurrencyError {
+    fn from(e: serde_json::Error) -> Self {
+        CurrencyError::Json(e)
+    }
+}
+
+/// How converted amounts are rounded, with the number of decimal places to keep.
+///
+/// Floors are usually rounded [`Up`](Rounding::Up), so that a converted floor
+/// never accepts a bid the original floor would have rejected, and bids
+/// [`Down`](Rounding::Down), so that a converted bid never exceeds what the
+/// buyer offered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Keep the converted amount as computed.
+    Exact,
+    /// Round to the nearest value, with midpoints away from zero.
+    Nearest(u32),
+    /// Round towards positive infinity.
+    Up(u32),
+    /// Round towards negative infinity.
+    Down(u32),
+}
+
+impl Rounding {
+    /// Rounds a price.
+    #[cfg(not(feature = "decimal"))]
+    #[must_use]
+    pub fn apply(self, price: Price) -> Price {
+        let (places, round): (u32, fn(f64) -> f64) = match self {
+            Rounding::Exact => return price,
+            Rounding::Nearest(places) => (places, f64::round),
+            Rounding::Up(places) => (places, f64::ceil),
+            Rounding::Down(places) => (places, f64::floor),
+        };
+        let factor = 10f64.powi(i32::try_from(places).unwrap_or(i32::MAX));
+        // Drops the representation error of the multiplication, so that
+        // 0.11000000000000001 rounds up to 0.11 and not to 0.12.
+        let scaled = (price * factor * 1e6).round() / 1e6;
+        round(scaled) / factor
+    }
+
+    /// Rounds a price.
+    #[cfg(feature = "decimal")]
+    #[must_use]
+    pub fn apply(self, price: Price) -> Price {
+        use crate::money::RoundingStrategy;
+
+        match self {
+            Rounding::Exact => price,
+            Rounding::Nearest(places) => {
+                price.round_dp(places, RoundingStrategy::MidpointAwayFromZero)
+            }
+            Rounding::Up(places) => price.round_dp(places, RoundingStrategy::ToPositiveInfinity),
+            Rounding::Down(places) => price.round_dp(places, RoundingStrategy::ToNegativeInfinity),
+        }
+    }
+}
+
+/// Source of exchange rates between currencies.
+pub trait CurrencyConverter {
+    /// Returns the rate from one currency to another, if known.
+    fn rate(&self, from: &CurrencyCode, to: &CurrencyCode) -> Option<Rate>;
+
+    /// Converts a price from one currency to another and rounds the result.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no rate is known, or if the converted price is out of range.
+    fn convert(
+        &self,
+        price: Price,
+        from: &CurrencyCode,
+        to: &CurrencyCode,
+        rounding: Rounding,
+    ) -> Result<Price, CurrencyError> {
+        if from == to {
+            return Ok(price);
+        }
+        let rate = self
+            .rate(from, to)
+            .ok_or_else(|| CurrencyError::UnknownRate {
+                from: from.clone(),
+                to: to.clone(),
+            })?;
+        let converted = multiply(price, rate).ok_or_else(|| CurrencyError::Overflow {
+            from: from.clone(),
+            to: to.clone(),
+        })?;
+        Ok(rounding.apply(converted))
+    }
+}
+
+/// In-memory table of exchange rates.
+///
+/// Rates are looked up directly, then inverted, then through a single
+/// intermediate currency, so a table of rates from one base currency converts
+/// between any two of its currencies. The base currency of the table is
+/// tried first as the intermediate, then the other currencies in
+/// alphabetical order, so conversions are the same from run to run.
+///
+/// Tables load from JSON, with a base currency and the rate of each currency
+/// against it:
+///
+/// ```json
+/// {"base": "USD", "rates": {"EUR": 0.92, "JPY": 151.3}}
+/// ```
+///
+/// or from CSV, with one `from,to,rate` row per rate, an optional header and
+/// `#` comments:
+///
+/// ```csv
+/// from,to,rate
+/// USD,EUR,0.92
+/// USD,JPY,151.3
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateTable {
+    base: Option<CurrencyCode>,
+    rates: BTreeMap<(CurrencyCode, CurrencyCode), Rate>,
+}
+
+impl RateTable {
+    /// Creates an empty table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the rate from one currency to another.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the rate is not positive and finite.
+    pub fn insert(
+        &mut self,
+        from: CurrencyCode,
+        to: CurrencyCode,
+        rate: Rate,
+    ) -> Result<(), CurrencyError> {
+        if !(rate > Rate::from(0u8) && rate <= Rate::MAX) {
+            return Err(CurrencyError::InvalidRate { from, to });
+        }
+        self.rates.insert((from, to), rate);
+        Ok(())
+    }
+
+    /// Returns the base currency of the table, preferred as intermediate
+    /// currency for cross rates.
+    #[must_use]
+    pub fn base(&self) -> Option<&CurrencyCode> {
+        self.base.as_ref()
+    }
+
+    /// Sets the base currency of the table. Tables loaded from JSON have the
+    /// base currency of the file.
+    pub fn set_base(&mut self, base: CurrencyCode) {
+        self.base = Some(base);
+    }
+
+    /// Number of rates in the table.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rates.len()
+    }
+
+    /// Returns whether the table has no rates.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Loads a table from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the JSON has no valid `base` currency, or if a rate is not a
+    /// positive number.
+    pub fn from_json_str(json: &str) -> Result<Self, CurrencyError> {
+        let value: Value = serde_json::from_str(json)?;
+        let base = value
+            .get("base")
+            .and_then(Value::as_str)
+            .ok_or_else(|| parse_error(0, "missing \"base\" currency"))?;
+        let base = CurrencyCode::new(base)?;
+        let rates = value
+            .get("rates")
+            .and_then(Value::as_object)
+            .ok_or_else(|| parse_error(0, "missing \"rates\" object"))?;
+
+        let mut table = RateTable::new();
+        table.set_base(base.clone());
+        for (code, rate) in rates {
+            let rate = match rate {
+                Value::Number(n) => parse_rate(0, &n.to_string())?,
+                _ => return Err(parse_error(0, &format!("rate of {code} is not a number"))),
+            };
+            table.insert(base.clone(), CurrencyCode::new(code.as_str())?, rate)?;
+        }
+        Ok(table)
+    }
+
+    /// Loads a table from a CSV string.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a line is not `from,to,rate` with valid currencies and a
+    /// positive rate.
+    pub fn from_csv_str(csv: &str) -> Result<Self, CurrencyError> {
+        let mut table = RateTable::new();
+        for (i, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [from, to, rate] = fields[..] else {
+                return Err(parse_error(i + 1, "expected from,to,rate"));
+            };
+            if i == 0 && from.eq_ignore_ascii_case("from") {
+                continue;
+            }
+            let from = CurrencyCode::new(from)?;
+            let to = CurrencyCode::new(to)?;
+            table.insert(from, to, parse_rate(i + 1, rate)?)?;
+        }
+        Ok(table)
+    }
+
+    /// Loads a table from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read, or as [`from_json_str`](Self::from_json_str).
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, CurrencyError> {
+        Self::from_json_str(&fs::read_to_string(path)?)
+    }
+
+    /// Loads a table from a CSV file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read, or as [`from_csv_str`](Self::from_csv_str).
+    pub fn from_csv_file(path: impl AsRef<Path>) -> Result<Self, CurrencyError> {
+        Self::from_csv_str(&fs::read_to_string(path)?)
+    }
+
+    /// Loads a table from a file, choosing the format from its `.json` or `.csv` extension.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file has another extension, or cannot be loaded.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CurrencyError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => Self::from_json_file(path),
+            Some(e) if e.eq_ignore_ascii_case("csv") => Self::from_csv_file(path),
+            _ => Err(parse_error(0, "expected a .json or .csv file")),
+        }
+    }
+
+    /// Looks up a rate directly or by inverting the opposite rate.
+    fn pair(&self, from: &CurrencyCode, to: &CurrencyCode) -> Option<Rate> {
+        if from == to {
+            return Some(Rate::from(1u8));
+        }
+        if let Some(rate) = self.rates.get(&(from.clone(), to.clone())) {
+            return Some(*rate);
+        }
+        self.rates
+            .get(&(to.clone(), from.clone()))
+            .map(|rate| Rate::from(1u8) / *rate)
+    }
+}
+
+impl CurrencyConverter for RateTable {
+    fn rate(&self, from: &CurrencyCode, to: &CurrencyCode) -> Option<Rate> {
+        if let Some(rate) = self.pair(from, to) {
+            return Some(rate);
+        }
+        let currencies: BTreeSet<&CurrencyCode> =
+            self.rates.keys().flat_map(|(a, b)| [a, b]).collect();
+        self.base
+            .iter()
+            .chain(currencies)
+            .find_map(|via| cross_rate(self.pair(from, via)?, self.pair(via, to)?))
+    }
+}
+
+impl BidRequest {
+    /// Converts every floor of the request to the target currency: the
+    /// impression and deal `bidfloor`, their `durfloors`, and the video and
+    /// audio `mincpmpersec`. Floors without a `bidfloorcur` are in
+    /// [`DEFAULT_CURRENCY`]. Floor currencies are set to the target.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a floor currency is invalid or a floor cannot be converted,
+    /// leaving the request untouched.
+    pub fn convert_floors(
+        &mut self,
+        converter: &impl CurrencyConverter,
+        target: &CurrencyCode,
+        rounding: Rounding,
+    ) -> Result<(), CurrencyError> {
+        let mut imps = self.imp.clone();
+        for imp in &mut imps {
+            let from = currency_or_default(imp.bidfloorcur.as_deref())?;
+            let convert = |price: Price| converter.convert(price, &from, target, rounding);
+
+            let mut has_floor = imp.bidfloor.is_some();
+            convert_option(&mut imp.bidfloor, convert)?;
+            if let Some(video) = &mut imp.video {
+                has_floor |= has_floors(video.mincpmpersec, video.durfloors.as_deref());
+                convert_option(&mut video.mincpmpersec, convert)?;
+                convert_durfloors(&mut video.durfloors, convert)?;
+            }
+            if let Some(audio) = &mut imp.audio {
+                has_floor |= has_floors(audio.mincpmpersec, audio.durfloors.as_deref());
+                convert_option(&mut audio.mincpmpersec, convert)?;
+                convert_durfloors(&mut audio.durfloors, convert)?;
+            }
+            if has_floor || imp.bidfloorcur.is_some() {
+                imp.bidfloorcur = Some(target.to_string());
+            }
+
+            for deal in imp
+                .pmp
+                .iter_mut()
+                .flat_map(|p| p.deals.iter_mut().flatten())
+            {
+                let from = currency_or_default(deal.bidfloorcur.as_deref())?;
+                let convert = |price: Price| converter.convert(price, &from, target, rounding);
+
+                let has_floor = deal.bidfloor.is_some()
+                    || has_floors(deal.mincpmpersec, deal.durfloors.as_deref())
+                    || deal.bidfloorcur.is_some();
+                convert_option(&mut deal.bidfloor, convert)?;
+                convert_option(&mut deal.mincpmpersec, convert)?;
+                convert_durfloors(&mut deal.durfloors, convert)?;
+                if has_floor {
+                    deal.bidfloorcur = Some(target.to_string());
+                }
+            }
+        }
+        self.imp = imps;
+        Ok(())
+    }
+}
+
+impl BidResponse {
+    /// Converts the price of every bid to the target currency, and sets the
+    /// response currency to the target. Responses without a `cur` are in
+    /// [`DEFAULT_CURRENCY`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the response currency is invalid or a price cannot be
+    /// converted, leaving the response untouched.
+    pub fn convert_prices(
+        &mut self,
+        converter: &impl CurrencyConverter,
+        target: &CurrencyCode,
+        rounding: Rounding,
+    ) -> Result<(), CurrencyError> {
+        let from = currency_or_default(self.cur.as_deref())?;
+        let mut seatbids = self.seatbid.clone();
+        for bid in seatbids.iter_mut().flatten().flat_map(|s| s.bid.iter_mut()) {
+            bid.price = converter.convert(bid.price, &from, target, rounding)?;
+        }
+        self.seatbid = seatbids;
+        self.cur = Some(target.to_string());
+        Ok(())
+    }
+}
+
+pub(crate) fn currency_or_default(code: Option<&str>) -> Result<CurrencyCode, InvalidValue> {
+    CurrencyCode::new(code.unwrap_or(DEFAULT_CURRENCY))
+}
+
+fn has_floors(mincpmpersec: Option<Price>, durfloors: Option<&[DurFloors]>) -> bool {
+    mincpmpersec.is_some()
+        || durfloors
+            .into_iter()
+            .flatten()
+            .any(|f| f.bidfloor.is_some())
+}
+
+fn convert_option(
+    price: &mut Option<Price>,
+    convert: impl Fn(Price) -> Result<Price, CurrencyError>,
+) -> Result<(), CurrencyError> {
+    if let Some(value) = price {
+        *value = convert(*value)?;
+    }
+    Ok(())
+}
+
+fn convert_durfloors(
+    durfloors: &mut Option<Vec<DurFloors>>,
+    convert: impl Fn(Price) -> Result<Price, CurrencyError>,
+) -> Result<(), CurrencyError> {
+    for floor in durfloors.iter_mut().flatten() {
+        convert_option(&mut floor.bidfloor, &convert)?;
+    }
+    Ok(())
+}
+
+/// Multiplies a price by a rate, or returns `None` if the result is out of
+/// range.
+#[cfg(not(feature = "decimal"))]
+fn multiply(price: Price, rate: Rate) -> Option<Price> {
+    Some(price * rate).filter(|price| price.is_finite())
+}
+
+/// Multiplies a price by a rate, or returns `None` if the result is out of
+/// range.
+#[cfg(feature = "decimal")]
+fn multiply(price: Price, rate: Rate) -> Option<Price> {
+    price.checked_mul(rate)
+}
+
+/// Returns the rate through an intermediate currency, or `None` if it is out
+/// of range, too large or too small to be a rate.
+#[cfg(not(feature = "decimal"))]
+fn cross_rate(from_via: Rate, via_to: Rate) -> Option<Rate> {
+    Some(from_via * via_to).filter(|rate| *rate > 0.0 && rate.is_finite())
+}
+
+/// Returns the rate through an intermediate currency, or `None` if it is out
+/// of range, too large or too small to be a rate.
+#[cfg(feature = "decimal")]
+fn cross_rate(from_via: Rate, via_to: Rate) -> Option<Rate> {
+    from_via
+        .checked_mul(via_to)
+        .filter(|rate| *rate > Rate::ZERO)
+}
+
+fn parse_rate(line: usize, rate: &str) -> Result<Rate, CurrencyError> {
+    #[cfg(not(feature = "decimal"))]
+    let parsed = rate.parse().ok();
+    #[cfg(feature = "decimal")]
+    let parsed = rate.parse().or_else(|_| Rate::from_scientific(rate)).ok();
+
+    parsed.ok_or_else(|| parse_error(line, &format!("{rate:?} is not a number")))
+}
+
+fn parse_error(line: usize, reason: &str) -> CurrencyError {
+    CurrencyError::Parse {
+        line,
+        reason: reason.to_string(),
+    }
+}
@@ -0,0 +1,98 @@
+//! Percent-encoding and Base64, for values placed in URLs and markup.
+
+/// Alphabet of standard Base64 (RFC 4648, section 4).
+pub(crate) const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Alphabet of URL and filename safe Base64 (RFC 4648, section 5).
+pub(crate) const BASE64_URL: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Upper case hexadecimal digits of percent-encoded bytes.
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Percent-encodes every byte of a value but the unreserved characters of
+/// RFC 3986 (`A-Z a-z 0-9 - . _ ~`).
+pub(crate) fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push('%');
+            encoded.push(char::from(HEX[usize::from(byte >> 4)]));
+            encoded.push(char::from(HEX[usize::from(byte & 0x0F)]));
+        }
+    }
+    encoded
+}
+
+/// Decodes a percent-encoded value, with `+` as a space as in query strings.
+/// Returns `None` if an escape is malformed or the result is not UTF-8.
+pub(crate) fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3)?;
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                let hex = std::str::from_utf8(hex).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Encodes bytes to Base64 with the given alphabet, padded with `=` if
+/// `pad` is set.
+pub(crate) fn base64_encode(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            encoded.push(char::from(alphabet[((n >> (18 - 6 * i)) & 0x3f) as usize]));
+        }
+        if pad {
+            for _ in chunk.len()..3 {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes Base64 with the given alphabet, padded or not. Returns `None` if
+/// the text holds other characters or has an impossible length.
+pub(crate) fn base64_decode(text: &str, alphabet: &[u8; 64]) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut decoded = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let value = u32::try_from(alphabet.iter().position(|a| a == c)?).ok()?;
+            n |= value << (18 - 6 * i);
+        }
+        decoded.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(decoded)
+}
@@ -0,0 +1,60 @@
+//! # JSON Schema
+//!
+//! JSON Schema (draft 2020-12) generation for the `OpenRTB` types, enabled
+//! with the `json-schema` feature.
+//!
+//! Non-optional fields such as `BidRequest.imp` or `Bid.price` are marked as
+//! required, and the documentation of each structure and field becomes its
+//! description.
+//!
+//! ```
+//! use iab::json_schema;
+//! use iab::openrtb2::BidRequest;
+//!
+//! let schema = json_schema::schema_for::<BidRequest>();
+//!
+//! assert_eq!(schema.get("title").unwrap(), "BidRequest");
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use schemars::generate::SchemaSettings;
+
+use crate::openrtb2::{BidRequest, BidResponse};
+
+pub use schemars::{JsonSchema, Schema};
+
+/// Generates the JSON Schema of an `OpenRTB` object, with all nested objects under `$defs`.
+#[must_use]
+pub fn schema_for<T: JsonSchema>() -> Schema {
+    SchemaSettings::draft2020_12()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+/// Writes the schemas of `BidRequest` and `BidResponse` as pretty-printed JSON
+/// into `dir`, creating it if needed. Returns the paths of the written files.
+///
+/// # Errors
+///
+/// Fails if the directory or one of the files cannot be written.
+pub fn write_schemas(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let schemas = [
+        ("BidRequest", schema_for::<BidRequest>()),
+        ("BidResponse", schema_for::<BidResponse>()),
+    ];
+
+    let mut paths = Vec::with_capacity(schemas.len());
+    for (name, schema) in schemas {
+        let path = dir.join(format!("{name}.schema.json"));
+        fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
@@ -22,7 +22,7 @@
 //!     cur: Some(vec!["USD".to_string()]),
 //!     imp: vec![Imp {
 //!         id: "1".to_string(),
-//!         bidfloor: Some(0.03),
+//!         bidfloor: Some(0.03.into()),
 //!         banner: Some(Banner {
 //!             h: Some(250),
 //!             w: Some(300),
@@ -98,4 +98,20 @@
 //! );
 //! ```
 
+pub mod auction;
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod crypto;
+pub mod currency;
+mod encoding;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+#[cfg(feature = "decimal")]
+pub mod money;
+pub mod native;
 pub mod openrtb2;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+pub mod vast;
+pub mod vmap;
+mod xml;
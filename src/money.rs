@@ -0,0 +1,441 @@
+//! # Money
+//!
+//! Exact decimal prices, enabled with the `decimal` feature.
+//!
+//! With this feature, `Bid.price`, `Imp.bidfloor`, `Deal.bidfloor`,
+//! `DurFloors.bidfloor` and the `mincpmpersec` fields use [`Cpm`] instead of
+//! `f64`, through the [`Price`](crate::openrtb2::Price) alias. Prices are
+//! still read from and written to plain JSON numbers, keeping the shortest
+//! decimal representation of the number: `0.03` is exactly three cents.
+//!
+//! [`Money`] pairs an amount with its currency so that amounts in different
+//! currencies cannot be added by mistake, and rounds to the minor unit of the
+//! currency with an explicit [`RoundingStrategy`].
+//!
+//! ```
+//! use iab::money::*;
+//! use iab::openrtb2::types::CurrencyCode;
+//!
+//! let usd = CurrencyCode::new("USD").unwrap();
+//! let price = Cpm::from(0.1) + Cpm::from(0.2);
+//!
+//! assert_eq!(price, Cpm::from(0.3));
+//!
+//! // Revenue of 12345 impressions at a $0.30 CPM, rounded to cents.
+//! let revenue = price.cost(12345, usd).round(RoundingStrategy::MidpointNearestEven);
+//! assert_eq!(revenue.amount().to_string(), "3.70");
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::openrtb2::types::CurrencyCode;
+use crate::openrtb2::PriceExt;
+
+pub use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Errors raised by currency-aware arithmetic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    /// Amounts in different currencies were combined.
+    CurrencyMismatch {
+        left: CurrencyCode,
+        right: CurrencyCode,
+    },
+    /// An iterator of amounts was empty, so its currency is unknown.
+    Empty,
+    /// The result is out of the range of a decimal.
+    Overflow,
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch { left, right } => {
+                write!(f, "cannot combine amounts in {left} and {right}")
+            }
+            MoneyError::Empty => write!(f, "no amounts to combine"),
+            MoneyError::Overflow => write!(f, "amount out of range"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+/// An exact price expressed as CPM (cost per thousand impressions).
+///
+/// The arithmetic operators saturate at the bounds of [`Decimal`] rather
+/// than panic, since prices come from bidders; use
+/// [`checked_add`](Cpm::checked_add) and the other checked methods to detect
+/// overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Cpm(Decimal);
+
+impl Cpm {
+    /// A price of zero.
+    pub const ZERO: Cpm = Cpm(Decimal::ZERO);
+
+    /// Creates a price from an exact decimal.
+    #[must_use]
+    pub const fn new(value: Decimal) -> Self {
+        Cpm(value)
+    }
+
+    /// Returns the price as an exact decimal.
+    #[must_use]
+    pub fn value(&self) -> Decimal {
+        self.0
+    }
+
+    /// Returns the price as the nearest `f64`.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        rust_decimal::prelude::ToPrimitive::to_f64(&self.0).unwrap_or(0.0)
+    }
+
+    /// Returns whether the price is below zero.
+    #[must_use]
+    pub fn is_sign_negative(&self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+
+    /// Price of a single impression, that is the CPM divided by 1000.
+    #[must_use]
+    pub fn per_impression(&self) -> Decimal {
+        self.0 / Decimal::ONE_THOUSAND
+    }
+
+    /// Cost of a number of impressions at this CPM, in the given currency.
+    /// The result is exact, unless out of range; use [`Money::round`] to
+    /// settle it.
+    #[must_use]
+    pub fn cost(&self, impressions: u64, currency: CurrencyCode) -> Money {
+        Money::new(
+            self.per_impression()
+                .saturating_mul(Decimal::from(impressions)),
+            currency,
+        )
+    }
+
+    /// Adds two prices, or returns `None` on overflow.
+    pub fn checked_add(self, other: Cpm) -> Option<Cpm> {
+        self.0.checked_add(other.0).map(Cpm)
+    }
+
+    /// Subtracts a price, or returns `None` on overflow.
+    pub fn checked_sub(self, other: Cpm) -> Option<Cpm> {
+        self.0.checked_sub(other.0).map(Cpm)
+    }
+
+    /// Multiplies the price by a factor, or returns `None` on overflow.
+    pub fn checked_mul(self, factor: Decimal) -> Option<Cpm> {
+        self.0.checked_mul(factor).map(Cpm)
+    }
+
+    /// Rounds the price to a number of decimal places.
+    #[must_use]
+    pub fn round_dp(&self, dp: u32, strategy: RoundingStrategy) -> Cpm {
+        Cpm(self.0.round_dp_with_strategy(dp, strategy))
+    }
+}
+
+/// Converts a floating point number to the price with its shortest decimal
+/// representation, so `0.03` becomes exactly `0.03`.
+///
+/// # Panics
+///
+/// Panics if the number is not finite or out of the range of a decimal, as
+/// `Duration::from_secs_f64` does; use
+/// [`PriceExt::try_from_f64`] for untrusted numbers.
+impl From<f64> for Cpm {
+    fn from(value: f64) -> Self {
+        match Cpm::try_from_f64(value) {
+            Some(price) => price,
+            None => panic!("{value} is not a valid price"),
+        }
+    }
+}
+
+impl PriceExt for Cpm {
+    fn try_from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        Decimal::from_str(&value.to_string())
+            .or_else(|_| Decimal::from_scientific(&format!("{value:e}")))
+            .map(Cpm)
+            .ok()
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Cpm::from(value)
+    }
+
+    fn to_f64(self) -> f64 {
+        Cpm::to_f64(&self)
+    }
+}
+
+impl From<Cpm> for f64 {
+    fn from(value: Cpm) -> Self {
+        value.to_f64()
+    }
+}
+
+impl From<Decimal> for Cpm {
+    fn from(value: Decimal) -> Self {
+        Cpm(value)
+    }
+}
+
+impl From<i64> for Cpm {
+    fn from(value: i64) -> Self {
+        Cpm(Decimal::from(value))
+    }
+}
+
+impl FromStr for Cpm {
+    type Err = rust_decimal::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str(value).map(Cpm)
+    }
+}
+
+impl fmt::Display for Cpm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl PartialEq<f64> for Cpm {
+    fn eq(&self, other: &f64) -> bool {
+        Cpm::try_from_f64(*other) == Some(*self)
+    }
+}
+
+impl PartialOrd<f64> for Cpm {
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        Cpm::try_from_f64(*other).map(|other| self.cmp(&other))
+    }
+}
+
+impl Add for Cpm {
+    type Output = Cpm;
+
+    fn add(self, other: Cpm) -> Cpm {
+        Cpm(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Cpm {
+    type Output = Cpm;
+
+    fn sub(self, other: Cpm) -> Cpm {
+        Cpm(self.0.saturating_sub(other.0))
+    }
+}
+
+impl AddAssign for Cpm {
+    fn add_assign(&mut self, other: Cpm) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Cpm {
+    fn sub_assign(&mut self, other: Cpm) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<Decimal> for Cpm {
+    type Output = Cpm;
+
+    fn mul(self, factor: Decimal) -> Cpm {
+        Cpm(self.0.saturating_mul(factor))
+    }
+}
+
+impl Sum for Cpm {
+    fn sum<I: Iterator<Item = Cpm>>(iter: I) -> Cpm {
+        iter.fold(Cpm::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Cpm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cpm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CpmVisitor;
+
+        impl Visitor<'_> for CpmVisitor {
+            type Value = Cpm;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number within the range of a decimal")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Cpm, E> {
+                Ok(Cpm::from(value))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Cpm, E> {
+                Ok(Cpm(Decimal::from(value)))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Cpm, E> {
+                Cpm::try_from_f64(value)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Float(value), &self))
+            }
+        }
+
+        deserializer.deserialize_f64(CpmVisitor)
+    }
+}
+
+#[cfg(feature = "json-schema")]
+impl schemars::JsonSchema for Cpm {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Cpm".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        f64::json_schema(generator)
+    }
+}
+
+#[cfg(feature = "avro")]
+impl apache_avro::AvroSchemaComponent for Cpm {
+    fn get_schema_in_ctxt(
+        named_schemas: &mut std::collections::HashSet<apache_avro::schema::Name>,
+        enclosing_namespace: apache_avro::schema::NamespaceRef,
+    ) -> apache_avro::Schema {
+        f64::get_schema_in_ctxt(named_schemas, enclosing_namespace)
+    }
+}
+
+/// An exact amount of money in a given currency.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Decimal,
+    currency: CurrencyCode,
+}
+
+impl Money {
+    /// Creates an amount of money.
+    #[must_use]
+    pub fn new(amount: Decimal, currency: CurrencyCode) -> Self {
+        Money { amount, currency }
+    }
+
+    /// Returns the exact amount.
+    #[must_use]
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Returns the currency of the amount.
+    #[must_use]
+    pub fn currency(&self) -> &CurrencyCode {
+        &self.currency
+    }
+
+    /// Adds two amounts in the same currency.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the currencies differ, or if the sum is out of range.
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let amount = self
+            .amount
+            .checked_add(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(amount, self.currency.clone()))
+    }
+
+    /// Subtracts an amount in the same currency.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the currencies differ, or if the difference is out of range.
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let amount = self
+            .amount
+            .checked_sub(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(amount, self.currency.clone()))
+    }
+
+    /// Sums amounts that must all be in the same currency.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there are no amounts, if their currencies differ, or if the
+    /// sum is out of range.
+    pub fn sum<'a>(amounts: impl IntoIterator<Item = &'a Money>) -> Result<Money, MoneyError> {
+        let mut amounts = amounts.into_iter();
+        let first = amounts.next().ok_or(MoneyError::Empty)?.clone();
+        amounts.try_fold(first, |total, amount| total.checked_add(amount))
+    }
+
+    /// Multiplies the amount by a factor, such as an exchange rate or a
+    /// revenue share, saturating at the bounds of a decimal.
+    #[must_use]
+    pub fn scale(&self, factor: Decimal) -> Money {
+        Money::new(self.amount.saturating_mul(factor), self.currency.clone())
+    }
+
+    /// Rounds the amount to the minor unit of its currency, such as cents for `USD`.
+    #[must_use]
+    pub fn round(&self, strategy: RoundingStrategy) -> Money {
+        Money::new(
+            self.amount
+                .round_dp_with_strategy(minor_units(&self.currency), strategy),
+            self.currency.clone(),
+        )
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch {
+                left: self.currency.clone(),
+                right: other.currency.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Number of decimal places of the minor unit of a currency, per ISO-4217.
+#[must_use]
+pub fn minor_units(currency: &CurrencyCode) -> u32 {
+    match currency.as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
@@ -0,0 +1,197 @@
+//! # Native
+//!
+//! The response of the `OpenRTB` Dynamic Native Ads API 1.2, the ad markup
+//! native bids carry in `Bid.adm`.
+//!
+//! [`NativeResponse::parse`] accepts both the 1.2 markup and the markup of
+//! earlier versions, wrapped in a `native` object.
+//!
+//! ```
+//! use iab::native::*;
+//!
+//! let response = NativeResponse::parse(
+//!     r#"{"native": {
+//!       "ver": "1.1",
+//!       "link": {"url": "https://advertiser.com"},
+//!       "assets": [{"id": 1, "title": {"text": "Learn more"}}]
+//!     }}"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(response.link.url, "https://advertiser.com");
+//! assert_eq!(response.assets.unwrap()[0].title.as_ref().unwrap().text, "Learn more");
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Top-level native ad markup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct NativeResponse {
+    /// Version of the Native Markup version in use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ver: Option<String>,
+    /// List of native ad's assets. Required if no `assetsurl`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assets: Option<Vec<Asset>>,
+    /// URL of an alternate source for the assets object.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assetsurl: Option<String>,
+    /// URL where a dynamic creative specification may be found for populating this response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dcourl: Option<String>,
+    /// Destination Link. This is default link object for the ad.
+    pub link: Link,
+    /// Array of impression tracking URLs. NOTE: Deprecated in favor of eventtrackers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imptrackers: Option<Vec<String>>,
+    /// Optional JavaScript impression tracker. NOTE: Deprecated in favor of eventtrackers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jstracker: Option<String>,
+    /// Array of tracking objects to run with the ad, in response to the declared supported methods in the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eventtrackers: Option<Vec<EventTracker>>,
+    /// If support was indicated in the request, URL of a page informing the user about the buyer's targeting activity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<String>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// A single asset of the native ad: a title, image, video or data element.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Asset {
+    /// Unique asset ID, matching the ID of the asset in the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// Set to 1 if asset is required (1 = required, 0 = optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<i64>,
+    /// Title object for title assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TitleAsset>,
+    /// Image object for image assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub img: Option<ImageAsset>,
+    /// Video object for video assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoAsset>,
+    /// Data object for ratings, prices etc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<DataAsset>,
+    /// Link object for call to actions, overriding the default link of the ad.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// Text of a title asset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct TitleAsset {
+    /// The text associated with the text element.
+    pub text: String,
+    /// Length of the title being provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<i64>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// An image asset, such as an icon or the main image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ImageAsset {
+    /// Type of the image element (1 = icon, 3 = main image).
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub img_type: Option<i64>,
+    /// URL of the image asset.
+    pub url: String,
+    /// Width of the image in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub w: Option<i64>,
+    /// Height of the image in pixels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub h: Option<i64>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// A video asset, as a VAST document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct VideoAsset {
+    /// VAST XML.
+    pub vasttag: String,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// A data asset, such as a rating, price or call to action text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct DataAsset {
+    /// Type of the data element. Refer to Native 1.2 Data Asset Types.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<i64>,
+    /// Length of the data element being provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<i64>,
+    /// The formatted string of data to be displayed.
+    pub value: String,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// Destination of a click on the ad or on an asset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Link {
+    /// Landing URL of the clickable link.
+    pub url: String,
+    /// List of third-party tracker URLs to be fired on click of the URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clicktrackers: Option<Vec<String>>,
+    /// Fallback URL for deeplink, used if the URL given in `url` is not supported by the device.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+/// A tracker to fire on an event of the ad.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct EventTracker {
+    /// Type of event to track (1 = impression, 2 = viewable MRC 50%, 3 = viewable MRC 100%, 4 = viewable video 50%).
+    pub event: i64,
+    /// Type of tracking requested (1 = image pixel, 2 = JavaScript).
+    pub method: i64,
+    /// The URL of the image or JavaScript that will be used for the tracking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Custom data for the tracker, such as the parameters of a verification script.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customdata: Option<Value>,
+    /// Placeholder for exchange-specific extensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext: Option<Value>,
+}
+
+impl NativeResponse {
+    /// Parses native ad markup, bare as in version 1.2 or wrapped in a
+    /// `native` object as in earlier versions.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the markup is not a native response.
+    pub fn parse(markup: &str) -> Result<NativeResponse, serde_json::Error> {
+        let mut value: Value = serde_json::from_str(markup)?;
+        if let Some(native) = value.get_mut("native") {
+            value = native.take();
+        }
+        serde_json::from_value(value)
+    }
+}
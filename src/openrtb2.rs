@@ -1,6 +1,73 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+mod builder;
+pub mod codes;
+mod markup;
+mod profile;
+mod split;
+pub mod types;
+mod validation;
+
+pub use builder::{BidBuilder, BidRequestBuilder, BidResponseBuilder, ImpBuilder};
+pub use markup::{Markup, MarkupError, MarkupFormat};
+pub use profile::{BidderProfile, Removal, Shaped};
+pub use split::MergeError;
+pub use validation::ValidationError;
+
+/// Price expressed as CPM, such as `Bid.price` and `Imp.bidfloor`.
+///
+/// An exact decimal [`Cpm`](crate::money::Cpm) with the `decimal` feature, `f64` otherwise.
+#[cfg(not(feature = "decimal"))]
+pub type Price = f64;
+
+/// Price expressed as CPM, such as `Bid.price` and `Imp.bidfloor`.
+///
+/// An exact decimal [`Cpm`](crate::money::Cpm) with the `decimal` feature, `f64` otherwise.
+#[cfg(feature = "decimal")]
+pub type Price = crate::money::Cpm;
+
+/// Conversions between [`Price`] and `f64`, whichever type `Price` is, for
+/// code that must build with and without the `decimal` feature.
+///
+/// ```
+/// use iab::openrtb2::*;
+///
+/// let price = Price::from_f64(1.25);
+/// assert_eq!(price.to_f64(), 1.25);
+/// ```
+pub trait PriceExt: Copy {
+    /// Converts a number to a price, or returns `None` if it is not finite
+    /// or, with the `decimal` feature, out of the range of a decimal.
+    fn try_from_f64(value: f64) -> Option<Self>;
+
+    /// Converts a number to a price.
+    ///
+    /// # Panics
+    ///
+    /// With the `decimal` feature, panics if the number is not finite or out
+    /// of the range of a decimal; use [`try_from_f64`](Self::try_from_f64)
+    /// for untrusted numbers.
+    fn from_f64(value: f64) -> Self;
+
+    /// Returns the price as the nearest `f64`.
+    fn to_f64(self) -> f64;
+}
+
+impl PriceExt for f64 {
+    fn try_from_f64(value: f64) -> Option<Self> {
+        value.is_finite().then_some(value)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
 /// Top-level enum to represent either a `BidRequest` or a `BidResponse`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -12,6 +79,8 @@ pub enum OpenRtb {
 
 /// Top-level bid request object.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct BidRequest {
     /// ID of the bid request, assigned by the exchange.
     pub id: String,
@@ -82,11 +151,14 @@ pub struct BidRequest {
     pub regs: Option<Regs>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Describes the source of the bid request upstream from the exchange.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Source {
     /// Entity responsible for final sale decision (0 = exchange, 1 = upstream source). Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -102,11 +174,14 @@ pub struct Source {
     pub schain: Option<SupplyChain>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Contains legal, governmental, or industry regulations applicable to the request.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Regs {
     /// Flag indicating if request is subject to COPPA (0 = no, 1 = yes).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -125,11 +200,14 @@ pub struct Regs {
     pub gpp_sid: Option<Vec<i64>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Describes an ad placement or impression being auctioned.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Imp {
     /// Unique identifier for this impression within the bid request.
     pub id: String,
@@ -166,7 +244,7 @@ pub struct Imp {
     pub tagid: Option<String>,
     /// Minimum bid for this impression expressed in CPM.
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type to Option<f64>
-    pub bidfloor: Option<f64>,
+    pub bidfloor: Option<Price>,
     /// Currency for bidfloor (ISO-4217 alpha codes).
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type to Option<String>
     pub bidfloorcur: Option<String>,
@@ -199,11 +277,14 @@ pub struct Imp {
     pub refresh: Option<Refresh>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Offers insight into the impression, like viewability or CTR.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Metric {
     /// Type of metric being presented.
     #[serde(rename = "type")]
@@ -215,11 +296,14 @@ pub struct Metric {
     pub vendor: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents a banner impression.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Banner {
     /// Array of Format objects representing permitted banner sizes. Recommended if h/w not specified.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -259,11 +343,14 @@ pub struct Banner {
     pub vcm: Option<i64>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents a video impression.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Video {
     /// Content MIME types supported.
     pub mimes: Vec<String>,
@@ -328,7 +415,7 @@ pub struct Video {
     pub slotinpod: Option<i64>,
     /// Minimum CPM per second for dynamic portion of video ad pod.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub mincpmpersec: Option<f64>,
+    pub mincpmpersec: Option<Price>,
     /// Blocked creative attributes. Refer to `AdCOM 1.0` List: Creative Attributes.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub battr: Option<Vec<i64>>,
@@ -373,11 +460,14 @@ pub struct Video {
     pub durfloors: Option<Vec<DurFloors>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents an audio type impression.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Audio {
     /// Content MIME types supported.
     pub mimes: Vec<String>,
@@ -414,7 +504,7 @@ pub struct Audio {
     pub slotinpod: Option<i64>,
     /// Minimum CPM per second for dynamic portion of audio ad pod.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub mincpmpersec: Option<f64>,
+    pub mincpmpersec: Option<Price>,
     /// Blocked creative attributes. Refer to `AdCOM 1.0` List: Creative Attributes.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub battr: Option<Vec<i64>>,
@@ -456,11 +546,14 @@ pub struct Audio {
     pub durfloors: Option<Vec<DurFloors>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents a native type impression.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Native {
     /// Request payload complying with Native Ad Specification (JSON encoded string).
     pub request: String,
@@ -475,11 +568,14 @@ pub struct Native {
     pub battr: Option<Vec<i64>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents an allowed size (height/width) or Flex Ad parameters.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Format {
     /// Width in DIPS.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -498,11 +594,14 @@ pub struct Format {
     pub wmin: Option<i64>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Private marketplace container for direct deals.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Pmp {
     /// Indicator of auction eligibility (0=all bids, 1=restricted to deals).
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type, removed default
@@ -512,17 +611,20 @@ pub struct Pmp {
     pub deals: Option<Vec<Deal>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Constitutes a specific deal struck between a buyer and a seller.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Deal {
     /// Unique identifier for the direct deal.
     pub id: String,
     /// Minimum bid for this impression expressed in CPM.
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type, removed default
-    pub bidfloor: Option<f64>,
+    pub bidfloor: Option<Price>,
     /// Currency for bidfloor (ISO-4217 alpha codes).
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type, removed default
     pub bidfloorcur: Option<String>,
@@ -540,17 +642,20 @@ pub struct Deal {
     pub guar: Option<i64>,
     /// Minimum CPM per second for video/audio opportunities.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub mincpmpersec: Option<f64>,
+    pub mincpmpersec: Option<Price>,
     /// Container for floor price by duration information (video/audio).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub durfloors: Option<Vec<DurFloors>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Details of the website calling for the impression.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Site {
     /// Exchange-specific site ID. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -606,11 +711,14 @@ pub struct Site {
     pub inventorypartnerdomain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Details of the non-browser application calling for the impression.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct App {
     /// Exchange-specific app ID. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -665,11 +773,14 @@ pub struct App {
     pub inventorypartnerdomain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Entity who directly supplies inventory to and is paid by the exchange.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Publisher {
     /// Exchange-specific seller ID (corresponds to `seller_id` in sellers.json).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -688,11 +799,14 @@ pub struct Publisher {
     pub domain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Describes the content in which the impression appears.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Content {
     /// ID uniquely identifying the content.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -789,11 +903,14 @@ pub struct Content {
     pub channel: Option<Channel>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Defines the producer of the content.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Producer {
     /// Content producer or originator ID.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -812,11 +929,14 @@ pub struct Producer {
     pub domain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Information pertaining to the device.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Device {
     /// Location of the device defined by a Geo object. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -922,11 +1042,14 @@ pub struct Device {
     pub macmd5: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Encapsulates geographic location.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Geo {
     /// Latitude (-90.0 to +90.0).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -970,11 +1093,14 @@ pub struct Geo {
     pub utcoffset: Option<i64>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Information about the human user of the device.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct User {
     /// Exchange-specific ID for the user.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1013,11 +1139,14 @@ pub struct User {
     pub eids: Option<Vec<EID>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Container for specifying additional data about a related object.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Data {
     /// Exchange-specific ID for the data provider.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1030,11 +1159,14 @@ pub struct Data {
     pub segment: Option<Vec<Segment>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Key-value pairs conveying specific units of data within a Data object.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Segment {
     /// ID of the data segment specific to the data provider.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1047,11 +1179,14 @@ pub struct Segment {
     pub value: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Describes the network an ad will be displayed on.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Network {
     /// Unique identifier assigned by the publisher.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1064,11 +1199,14 @@ pub struct Network {
     pub domain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Describes the channel an ad will be displayed on.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Channel {
     /// Unique identifier assigned by the publisher.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1081,11 +1219,14 @@ pub struct Channel {
     pub domain: Option<String>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents the chain of entities involved in the direct flow of payment for inventory.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SupplyChain {
     /// Flag indicating if chain contains all nodes back to owner (0=no, 1=yes).
     pub complete: i64,
@@ -1095,11 +1236,14 @@ pub struct SupplyChain {
     pub ver: String,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Defines the identity of an entity participating in the supply chain.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SupplyChainNode {
     /// Canonical domain name of advertising system.
     pub asi: String,
@@ -1119,11 +1263,14 @@ pub struct SupplyChainNode {
     pub hp: Option<i64>,
     /// Placeholder for advertising-system specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Extended Identifiers support. Contains UIDs from a single source/provider.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct EID {
     /// Canonical domain name of entity that added the ID array element.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1142,11 +1289,14 @@ pub struct EID {
     pub uids: Option<Vec<UID>>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// A single user identifier provided as part of extended identifiers.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct UID {
     /// The identifier for the user.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1156,11 +1306,14 @@ pub struct UID {
     pub atype: Option<i64>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Structured user agent information based on User-Agent Client Hints.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct UserAgent {
     /// Array of `BrandVersion` objects identifying browsers/components. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1185,11 +1338,14 @@ pub struct UserAgent {
     pub source: Option<i64>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Identifies a device's browser/component or platform/OS using User-Agent Client Hints.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct BrandVersion {
     /// Brand identifier.
     pub brand: String,
@@ -1198,11 +1354,14 @@ pub struct BrandVersion {
     pub version: Option<Vec<String>>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Represents the impression multiplier for DOOH/CTV.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Qty {
     /// Quantity of billable events if purchased.
     pub multiplier: f64,
@@ -1214,11 +1373,14 @@ pub struct Qty {
     pub vendor: Option<String>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Details of the Digital Out of Home inventory.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DOOH {
     /// Exchange provided id for placement/grouping. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1246,11 +1408,14 @@ pub struct DOOH {
     pub content: Option<Content>,
     /// Placeholder for exchange-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Details about ad slots being refreshed automatically.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Refresh {
     /// Array of `RefSettings` objects describing refresh mechanics. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1260,11 +1425,14 @@ pub struct Refresh {
     pub count: Option<i64>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Information on how often and what triggers an ad slot refresh.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct RefSettings {
     /// Type of declared auto refresh. Refer to `AdCOM 1.0` List: Auto Refresh Triggers. Recommended.
     #[serde(skip_serializing_if = "Option::is_none")] // Changed type, removed default
@@ -1274,11 +1442,14 @@ pub struct RefSettings {
     pub minint: Option<i64>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Allows specifying price floors for video/audio creatives based on duration ranges.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct DurFloors {
     /// Low end of duration range (seconds). If missing, unbounded.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1288,14 +1459,17 @@ pub struct DurFloors {
     pub maxdur: Option<i64>,
     /// Minimum bid (CPM) for this duration range. Defaults to Imp.bidfloor if outside ranges.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub bidfloor: Option<f64>,
+    pub bidfloor: Option<Price>,
     /// Placeholder for vendor specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Top-level bid response object.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct BidResponse {
     /// ID of the bid request to which this is a response.
     pub id: String,
@@ -1316,11 +1490,14 @@ pub struct BidResponse {
     pub nbr: Option<i64>,
     /// Placeholder for bidder-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// Collection of bids from a specific bidder seat.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct SeatBid {
     /// Array of 1+ Bid objects.
     pub bid: Vec<Bid>,
@@ -1332,18 +1509,21 @@ pub struct SeatBid {
     pub group: Option<i64>,
     /// Placeholder for bidder-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
 
 /// An offer to buy a specific impression.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "avro", derive(apache_avro::AvroSchema))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Bid {
     /// Bidder generated bid ID.
     pub id: String,
     /// ID of the Imp object in the related bid request.
     pub impid: String,
     /// Bid price expressed as CPM.
-    pub price: f64,
+    pub price: Price,
     /// Win notice URL. Macros supported.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nurl: Option<String>,
@@ -1434,5 +1614,6 @@ pub struct Bid {
     pub slotinpod: Option<i64>,
     /// Placeholder for bidder-specific extensions.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "avro", avro(with = crate::avro::ext_schema, default = "null"))]
     pub ext: Option<Value>,
 }
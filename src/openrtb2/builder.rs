@@ -0,0 +1,454 @@
+use serde_json::Value;
+
+use super::codes::NoBidReason;
+use super::{
+    App, Audio, Banner, Bid, BidRequest, BidResponse, Deal, Device, Imp, Metric, Native, Pmp,
+    Price, Regs, SeatBid, Site, Source, User, ValidationError, Video, DOOH,
+};
+
+/// Generates chainable setters for optional fields of the wrapped object.
+///
+/// Scalar fields take anything convertible into the field type, list fields
+/// take any iterator of items convertible into the element type.
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Sets `", stringify!($field), "`.")]
+            #[must_use]
+            pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+                self.inner.$field = Some($field.into());
+                self
+            }
+        )*
+    };
+}
+
+macro_rules! list_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Sets `", stringify!($field), "`.")]
+            #[must_use]
+            pub fn $field<I>(mut self, $field: I) -> Self
+            where
+                I: IntoIterator,
+                I::Item: Into<$ty>,
+            {
+                self.inner.$field = Some($field.into_iter().map(Into::into).collect());
+                self
+            }
+        )*
+    };
+}
+
+/// Builder for a [`BidRequest`], validated on [`build`](BidRequestBuilder::build).
+///
+/// ```
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest::builder()
+///     .id("80ce30c53c16e6ede735f123ef6e32361bfc7b22")
+///     .at(1)
+///     .cur(["USD"])
+///     .imp(Imp::banner(300, 250).floor(0.03, "USD"))
+///     .site(Site {
+///         domain: Some("www.foobar.com".to_string()),
+///         ..Default::default()
+///     })
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(request.imp[0].id, "1");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BidRequestBuilder {
+    inner: BidRequest,
+}
+
+impl BidRequest {
+    /// Starts building a bid request.
+    #[must_use]
+    pub fn builder() -> BidRequestBuilder {
+        BidRequestBuilder::default()
+    }
+}
+
+impl BidRequestBuilder {
+    /// Sets the ID of the bid request.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.inner.id = id.into();
+        self
+    }
+
+    /// Adds an impression. Impressions without an ID are numbered from 1
+    /// in the order they are added.
+    #[must_use]
+    pub fn imp(mut self, imp: impl Into<Imp>) -> Self {
+        let mut imp = imp.into();
+        if imp.id.is_empty() {
+            imp.id = (self.inner.imp.len() + 1).to_string();
+        }
+        self.inner.imp.push(imp);
+        self
+    }
+
+    setters! {
+        site: Site,
+        app: App,
+        dooh: DOOH,
+        device: Device,
+        user: User,
+        at: i64,
+        tmax: i64,
+        cattax: i64,
+        source: Source,
+        regs: Regs,
+        ext: Value,
+    }
+
+    list_setters! {
+        wseat: String,
+        bseat: String,
+        cur: String,
+        wlang: String,
+        wlangb: String,
+        acat: String,
+        bcat: String,
+        badv: String,
+        bapp: String,
+    }
+
+    /// Marks the request as a test request.
+    #[must_use]
+    pub fn test(mut self, test: bool) -> Self {
+        self.inner.test = Some(test.into());
+        self
+    }
+
+    /// Indicates whether the impressions offered represent all that are available.
+    #[must_use]
+    pub fn allimps(mut self, allimps: bool) -> Self {
+        self.inner.allimps = Some(allimps.into());
+        self
+    }
+
+    /// Validates and returns the bid request.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request breaks a rule checked by [`BidRequest::validate`].
+    pub fn build(self) -> Result<BidRequest, ValidationError> {
+        self.inner.validate()?;
+        Ok(self.inner)
+    }
+}
+
+/// Builder for an [`Imp`], started from one of its media constructors.
+///
+/// The impression is validated as part of the enclosing [`BidRequestBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct ImpBuilder {
+    inner: Imp,
+}
+
+impl Imp {
+    /// Starts building a banner impression of the given size.
+    #[must_use]
+    pub fn banner(w: i64, h: i64) -> ImpBuilder {
+        ImpBuilder::default().with_banner(Banner {
+            w: Some(w),
+            h: Some(h),
+            ..Default::default()
+        })
+    }
+
+    /// Starts building a video impression accepting the given MIME types.
+    pub fn video<I>(mimes: I) -> ImpBuilder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        ImpBuilder::default().with_video(Video {
+            mimes: mimes.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Starts building an audio impression accepting the given MIME types.
+    pub fn audio<I>(mimes: I) -> ImpBuilder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        ImpBuilder::default().with_audio(Audio {
+            mimes: mimes.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Starts building a native impression with the given Native Ad Specification request.
+    pub fn native(request: impl Into<String>) -> ImpBuilder {
+        ImpBuilder::default().with_native(Native {
+            request: request.into(),
+            ..Default::default()
+        })
+    }
+}
+
+impl ImpBuilder {
+    /// Sets the ID of the impression.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.inner.id = id.into();
+        self
+    }
+
+    /// Sets the minimum bid and its currency.
+    #[must_use]
+    pub fn floor(mut self, bidfloor: impl Into<Price>, bidfloorcur: impl Into<String>) -> Self {
+        self.inner.bidfloor = Some(bidfloor.into());
+        self.inner.bidfloorcur = Some(bidfloorcur.into());
+        self
+    }
+
+    /// Sets the banner object, making the impression multi-format if another is present.
+    #[must_use]
+    pub fn with_banner(mut self, banner: Banner) -> Self {
+        self.inner.banner = Some(banner);
+        self
+    }
+
+    /// Sets the video object, making the impression multi-format if another is present.
+    #[must_use]
+    pub fn with_video(mut self, video: Video) -> Self {
+        self.inner.video = Some(video);
+        self
+    }
+
+    /// Sets the audio object, making the impression multi-format if another is present.
+    #[must_use]
+    pub fn with_audio(mut self, audio: Audio) -> Self {
+        self.inner.audio = Some(audio);
+        self
+    }
+
+    /// Sets the native object, making the impression multi-format if another is present.
+    #[must_use]
+    pub fn with_native(mut self, native: Native) -> Self {
+        self.inner.native_markup = Some(native);
+        self
+    }
+
+    /// Adds a private marketplace deal.
+    #[must_use]
+    pub fn deal(mut self, deal: Deal) -> Self {
+        self.inner
+            .pmp
+            .get_or_insert_with(Pmp::default)
+            .deals
+            .get_or_insert_with(Vec::new)
+            .push(deal);
+        self
+    }
+
+    /// Restricts the impression to the bids on its deals.
+    #[must_use]
+    pub fn private_auction(mut self, private_auction: bool) -> Self {
+        self.inner
+            .pmp
+            .get_or_insert_with(Pmp::default)
+            .private_auction = Some(private_auction.into());
+        self
+    }
+
+    setters! {
+        displaymanager: String,
+        displaymanagerver: String,
+        tagid: String,
+        clickbrowser: i64,
+        exp: i64,
+        ext: Value,
+    }
+
+    list_setters! {
+        metric: Metric,
+        iframebuster: String,
+    }
+
+    /// Marks the impression as interstitial or full screen.
+    #[must_use]
+    pub fn instl(mut self, instl: bool) -> Self {
+        self.inner.instl = Some(instl.into());
+        self
+    }
+
+    /// Requires secure HTTPS creative assets and markup.
+    #[must_use]
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.inner.secure = Some(secure.into());
+        self
+    }
+
+    /// Indicates the user receives a reward for viewing the ad.
+    #[must_use]
+    pub fn rwdd(mut self, rwdd: bool) -> Self {
+        self.inner.rwdd = Some(rwdd.into());
+        self
+    }
+}
+
+impl From<ImpBuilder> for Imp {
+    fn from(builder: ImpBuilder) -> Self {
+        builder.inner
+    }
+}
+
+/// Builder for a [`BidResponse`], validated on [`build`](BidResponseBuilder::build).
+///
+/// ```
+/// use iab::openrtb2::*;
+///
+/// let response = BidResponse::builder("1234567890")
+///     .cur("USD")
+///     .bid("512", Bid::builder("1", "102", 9.43).adomain(["advertiserdomain.com"]))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(response.seatbid.unwrap()[0].seat.as_deref(), Some("512"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BidResponseBuilder {
+    inner: BidResponse,
+}
+
+impl BidResponse {
+    /// Starts building a response to the bid request with the given ID.
+    pub fn builder(id: impl Into<String>) -> BidResponseBuilder {
+        BidResponseBuilder {
+            inner: BidResponse {
+                id: id.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl BidResponseBuilder {
+    /// Adds a bid on behalf of a seat, grouping bids of the same seat in one `SeatBid`.
+    #[must_use]
+    pub fn bid(mut self, seat: impl Into<String>, bid: impl Into<Bid>) -> Self {
+        let seat = Some(seat.into());
+        let seatbids = self.inner.seatbid.get_or_insert_with(Vec::new);
+        match seatbids.iter_mut().find(|s| s.seat == seat) {
+            Some(seatbid) => seatbid.bid.push(bid.into()),
+            None => seatbids.push(SeatBid {
+                bid: vec![bid.into()],
+                seat,
+                ..Default::default()
+            }),
+        }
+        self
+    }
+
+    /// Adds a complete `SeatBid`.
+    #[must_use]
+    pub fn seatbid(mut self, seatbid: SeatBid) -> Self {
+        self.inner
+            .seatbid
+            .get_or_insert_with(Vec::new)
+            .push(seatbid);
+        self
+    }
+
+    setters! {
+        bidid: String,
+        cur: String,
+        customdata: String,
+        nbr: i64,
+        ext: Value,
+    }
+
+    /// Sets `nbr` from a typed reason.
+    #[must_use]
+    pub fn no_bid_reason(mut self, reason: NoBidReason) -> Self {
+        self.inner.nbr = Some(reason.code());
+        self
+    }
+
+    /// Validates and returns the bid response.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the response breaks a rule checked by [`BidResponse::validate`].
+    pub fn build(self) -> Result<BidResponse, ValidationError> {
+        self.inner.validate()?;
+        Ok(self.inner)
+    }
+}
+
+/// Builder for a [`Bid`], validated as part of the enclosing [`BidResponseBuilder`].
+#[derive(Debug, Clone, Default)]
+pub struct BidBuilder {
+    inner: Bid,
+}
+
+impl Bid {
+    /// Starts building a bid on an impression at the given CPM price.
+    pub fn builder(
+        id: impl Into<String>,
+        impid: impl Into<String>,
+        price: impl Into<Price>,
+    ) -> BidBuilder {
+        BidBuilder {
+            inner: Bid {
+                id: id.into(),
+                impid: impid.into(),
+                price: price.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl BidBuilder {
+    setters! {
+        nurl: String,
+        burl: String,
+        lurl: String,
+        adm: String,
+        adid: String,
+        bundle: String,
+        iurl: String,
+        cid: String,
+        crid: String,
+        tactic: String,
+        cattax: i64,
+        protocol: i64,
+        qagmediarating: i64,
+        language: String,
+        langb: String,
+        dealid: String,
+        w: i64,
+        h: i64,
+        wratio: i64,
+        hratio: i64,
+        exp: i64,
+        dur: i64,
+        mtype: i64,
+        slotinpod: i64,
+        ext: Value,
+    }
+
+    list_setters! {
+        adomain: String,
+        cat: String,
+        attr: i64,
+        apis: i64,
+    }
+}
+
+impl From<BidBuilder> for Bid {
+    fn from(builder: BidBuilder) -> Self {
+        builder.inner
+    }
+}
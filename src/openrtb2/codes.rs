@@ -0,0 +1,443 @@
+//! Typed values of the `OpenRTB` code lists.
+
+use std::fmt;
+
+/// Reason a bid did not win, from the `OpenRTB` 2.6 List: Loss Reason Codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LossReason {
+    /// The bid won the auction.
+    BidWon,
+    /// Internal error.
+    InternalError,
+    /// Impression opportunity expired.
+    ImpressionExpired,
+    /// Invalid bid response.
+    InvalidBidResponse,
+    /// Invalid deal ID.
+    InvalidDealId,
+    /// Invalid auction ID.
+    InvalidAuctionId,
+    /// Invalid (i.e., malformed) advertiser domain.
+    InvalidAdvertiserDomain,
+    /// Missing markup.
+    MissingMarkup,
+    /// Missing creative ID.
+    MissingCreativeId,
+    /// Missing bid price.
+    MissingBidPrice,
+    /// Missing minimum creative approval data.
+    MissingCreativeApprovalData,
+    /// Bid was below the auction floor.
+    BelowAuctionFloor,
+    /// Bid was below the deal floor.
+    BelowDealFloor,
+    /// Lost to a higher bid.
+    LostToHigherBid,
+    /// Lost to a bid for a PMP deal.
+    LostToPmpDeal,
+    /// Buyer seat blocked.
+    BuyerSeatBlocked,
+    /// Creative filtered, general; reason unknown.
+    CreativeFiltered,
+    /// Creative filtered, pending processing by the exchange.
+    CreativePendingProcessing,
+    /// Creative filtered, disapproved by the exchange.
+    CreativeDisapproved,
+    /// Creative filtered, size not allowed.
+    SizeNotAllowed,
+    /// Creative filtered, incorrect creative format.
+    IncorrectCreativeFormat,
+    /// Creative filtered, advertiser exclusions.
+    AdvertiserExclusions,
+    /// Creative filtered, app store ID exclusions.
+    AppBundleExclusions,
+    /// Creative filtered, not secure.
+    NotSecure,
+    /// Creative filtered, language exclusions.
+    LanguageExclusions,
+    /// Creative filtered, category exclusions.
+    CategoryExclusions,
+    /// Creative filtered, creative attribute exclusions.
+    CreativeAttributeExclusions,
+    /// Creative filtered, ad type exclusions.
+    AdTypeExclusions,
+    /// Creative filtered, animation too long.
+    AnimationTooLong,
+    /// Creative filtered, not allowed in PMP deal.
+    NotAllowedInPmpDeal,
+    /// Exchange-specific reason, 1000 and above.
+    ExchangeSpecific(i64),
+}
+
+impl LossReason {
+    /// Returns the numeric code of the reason.
+    #[must_use]
+    pub fn code(&self) -> i64 {
+        match self {
+            LossReason::BidWon => 0,
+            LossReason::InternalError => 1,
+            LossReason::ImpressionExpired => 2,
+            LossReason::InvalidBidResponse => 3,
+            LossReason::InvalidDealId => 4,
+            LossReason::InvalidAuctionId => 5,
+            LossReason::InvalidAdvertiserDomain => 6,
+            LossReason::MissingMarkup => 7,
+            LossReason::MissingCreativeId => 8,
+            LossReason::MissingBidPrice => 9,
+            LossReason::MissingCreativeApprovalData => 10,
+            LossReason::BelowAuctionFloor => 100,
+            LossReason::BelowDealFloor => 101,
+            LossReason::LostToHigherBid => 102,
+            LossReason::LostToPmpDeal => 103,
+            LossReason::BuyerSeatBlocked => 104,
+            LossReason::CreativeFiltered => 200,
+            LossReason::CreativePendingProcessing => 201,
+            LossReason::CreativeDisapproved => 202,
+            LossReason::SizeNotAllowed => 203,
+            LossReason::IncorrectCreativeFormat => 204,
+            LossReason::AdvertiserExclusions => 205,
+            LossReason::AppBundleExclusions => 206,
+            LossReason::NotSecure => 207,
+            LossReason::LanguageExclusions => 208,
+            LossReason::CategoryExclusions => 209,
+            LossReason::CreativeAttributeExclusions => 210,
+            LossReason::AdTypeExclusions => 211,
+            LossReason::AnimationTooLong => 212,
+            LossReason::NotAllowedInPmpDeal => 213,
+            LossReason::ExchangeSpecific(code) => *code,
+        }
+    }
+
+    /// Returns the reason for a numeric code, if the code is defined.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<LossReason> {
+        Some(match code {
+            0 => LossReason::BidWon,
+            1 => LossReason::InternalError,
+            2 => LossReason::ImpressionExpired,
+            3 => LossReason::InvalidBidResponse,
+            4 => LossReason::InvalidDealId,
+            5 => LossReason::InvalidAuctionId,
+            6 => LossReason::InvalidAdvertiserDomain,
+            7 => LossReason::MissingMarkup,
+            8 => LossReason::MissingCreativeId,
+            9 => LossReason::MissingBidPrice,
+            10 => LossReason::MissingCreativeApprovalData,
+            100 => LossReason::BelowAuctionFloor,
+            101 => LossReason::BelowDealFloor,
+            102 => LossReason::LostToHigherBid,
+            103 => LossReason::LostToPmpDeal,
+            104 => LossReason::BuyerSeatBlocked,
+            200 => LossReason::CreativeFiltered,
+            201 => LossReason::CreativePendingProcessing,
+            202 => LossReason::CreativeDisapproved,
+            203 => LossReason::SizeNotAllowed,
+            204 => LossReason::IncorrectCreativeFormat,
+            205 => LossReason::AdvertiserExclusions,
+            206 => LossReason::AppBundleExclusions,
+            207 => LossReason::NotSecure,
+            208 => LossReason::LanguageExclusions,
+            209 => LossReason::CategoryExclusions,
+            210 => LossReason::CreativeAttributeExclusions,
+            211 => LossReason::AdTypeExclusions,
+            212 => LossReason::AnimationTooLong,
+            213 => LossReason::NotAllowedInPmpDeal,
+            code if code >= 1000 => LossReason::ExchangeSpecific(code),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for LossReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Reason a bidder did not bid (`BidResponse.nbr`), from the `OpenRTB` 2.6
+/// List: No-Bid Reason Codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoBidReason {
+    /// Unknown error.
+    UnknownError,
+    /// Technical error.
+    TechnicalError,
+    /// Invalid request.
+    InvalidRequest,
+    /// Known web crawler.
+    KnownWebCrawler,
+    /// Suspected non-human traffic.
+    SuspectedNonHumanTraffic,
+    /// Cloud, data center, or proxy IP.
+    ProxyIp,
+    /// Unsupported device.
+    UnsupportedDevice,
+    /// Blocked publisher or site.
+    BlockedPublisher,
+    /// Unmatched user.
+    UnmatchedUser,
+    /// Daily user cap met.
+    DailyUserCapMet,
+    /// Daily domain cap met.
+    DailyDomainCapMet,
+    /// Ads.txt authorization unavailable.
+    AdsTxtUnavailable,
+    /// Ads.txt authorization violation.
+    AdsTxtViolation,
+    /// Ads.cert authentication unavailable.
+    AdsCertUnavailable,
+    /// Ads.cert authentication violation.
+    AdsCertViolation,
+    /// Insufficient auction time.
+    InsufficientAuctionTime,
+    /// Incomplete `SupplyChain`.
+    IncompleteSupplyChain,
+    /// Blocked `SupplyChain` node.
+    BlockedSupplyChainNode,
+    /// Exchange-specific reason, 500 and above.
+    ExchangeSpecific(i64),
+}
+
+impl NoBidReason {
+    /// Returns the numeric code of the reason.
+    #[must_use]
+    pub fn code(&self) -> i64 {
+        match self {
+            NoBidReason::UnknownError => 0,
+            NoBidReason::TechnicalError => 1,
+            NoBidReason::InvalidRequest => 2,
+            NoBidReason::KnownWebCrawler => 3,
+            NoBidReason::SuspectedNonHumanTraffic => 4,
+            NoBidReason::ProxyIp => 5,
+            NoBidReason::UnsupportedDevice => 6,
+            NoBidReason::BlockedPublisher => 7,
+            NoBidReason::UnmatchedUser => 8,
+            NoBidReason::DailyUserCapMet => 9,
+            NoBidReason::DailyDomainCapMet => 10,
+            NoBidReason::AdsTxtUnavailable => 11,
+            NoBidReason::AdsTxtViolation => 12,
+            NoBidReason::AdsCertUnavailable => 13,
+            NoBidReason::AdsCertViolation => 14,
+            NoBidReason::InsufficientAuctionTime => 15,
+            NoBidReason::IncompleteSupplyChain => 16,
+            NoBidReason::BlockedSupplyChainNode => 17,
+            NoBidReason::ExchangeSpecific(code) => *code,
+        }
+    }
+
+    /// Returns the reason for a numeric code, if the code is defined.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<NoBidReason> {
+        Some(match code {
+            0 => NoBidReason::UnknownError,
+            1 => NoBidReason::TechnicalError,
+            2 => NoBidReason::InvalidRequest,
+            3 => NoBidReason::KnownWebCrawler,
+            4 => NoBidReason::SuspectedNonHumanTraffic,
+            5 => NoBidReason::ProxyIp,
+            6 => NoBidReason::UnsupportedDevice,
+            7 => NoBidReason::BlockedPublisher,
+            8 => NoBidReason::UnmatchedUser,
+            9 => NoBidReason::DailyUserCapMet,
+            10 => NoBidReason::DailyDomainCapMet,
+            11 => NoBidReason::AdsTxtUnavailable,
+            12 => NoBidReason::AdsTxtViolation,
+            13 => NoBidReason::AdsCertUnavailable,
+            14 => NoBidReason::AdsCertViolation,
+            15 => NoBidReason::InsufficientAuctionTime,
+            16 => NoBidReason::IncompleteSupplyChain,
+            17 => NoBidReason::BlockedSupplyChainNode,
+            code if code >= 500 => NoBidReason::ExchangeSpecific(code),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for NoBidReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Type of the creative markup of a bid (`Bid.mtype`), from the `OpenRTB` 2.6
+/// List: Creative Markup Types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MarkupType {
+    Banner,
+    Video,
+    Audio,
+    Native,
+}
+
+impl MarkupType {
+    /// Every markup type, in the order of their codes.
+    pub const ALL: [MarkupType; 4] = [
+        MarkupType::Banner,
+        MarkupType::Video,
+        MarkupType::Audio,
+        MarkupType::Native,
+    ];
+
+    /// Returns the numeric code of the type.
+    #[must_use]
+    pub fn code(&self) -> i64 {
+        match self {
+            MarkupType::Banner => 1,
+            MarkupType::Video => 2,
+            MarkupType::Audio => 3,
+            MarkupType::Native => 4,
+        }
+    }
+
+    /// Returns the type for a numeric code, if the code is defined.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<MarkupType> {
+        Some(match code {
+            1 => MarkupType::Banner,
+            2 => MarkupType::Video,
+            3 => MarkupType::Audio,
+            4 => MarkupType::Native,
+            _ => return None,
+        })
+    }
+
+    /// Returns the lowercase name of the type, as in `banner`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarkupType::Banner => "banner",
+            MarkupType::Video => "video",
+            MarkupType::Audio => "audio",
+            MarkupType::Native => "native",
+        }
+    }
+}
+
+impl fmt::Display for MarkupType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Protocol of a video or audio creative (`Video.protocols`, `Bid.protocol`),
+/// from the `AdCOM` 1.0 List: Creative Subtypes - Audio/Video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Protocol {
+    Vast10,
+    Vast20,
+    Vast30,
+    Vast10Wrapper,
+    Vast20Wrapper,
+    Vast30Wrapper,
+    Vast40,
+    Vast40Wrapper,
+    Daast10,
+    Daast10Wrapper,
+    Vast41,
+    Vast41Wrapper,
+    Vast42,
+    Vast42Wrapper,
+    Vast43,
+    Vast43Wrapper,
+}
+
+impl Protocol {
+    /// Every protocol, in the order of their codes.
+    pub const ALL: [Protocol; 16] = [
+        Protocol::Vast10,
+        Protocol::Vast20,
+        Protocol::Vast30,
+        Protocol::Vast10Wrapper,
+        Protocol::Vast20Wrapper,
+        Protocol::Vast30Wrapper,
+        Protocol::Vast40,
+        Protocol::Vast40Wrapper,
+        Protocol::Daast10,
+        Protocol::Daast10Wrapper,
+        Protocol::Vast41,
+        Protocol::Vast41Wrapper,
+        Protocol::Vast42,
+        Protocol::Vast42Wrapper,
+        Protocol::Vast43,
+        Protocol::Vast43Wrapper,
+    ];
+
+    /// Returns the numeric code of the protocol.
+    #[must_use]
+    pub fn code(&self) -> i64 {
+        match self {
+            Protocol::Vast10 => 1,
+            Protocol::Vast20 => 2,
+            Protocol::Vast30 => 3,
+            Protocol::Vast10Wrapper => 4,
+            Protocol::Vast20Wrapper => 5,
+            Protocol::Vast30Wrapper => 6,
+            Protocol::Vast40 => 7,
+            Protocol::Vast40Wrapper => 8,
+            Protocol::Daast10 => 9,
+            Protocol::Daast10Wrapper => 10,
+            Protocol::Vast41 => 11,
+            Protocol::Vast41Wrapper => 12,
+            Protocol::Vast42 => 13,
+            Protocol::Vast42Wrapper => 14,
+            Protocol::Vast43 => 15,
+            Protocol::Vast43Wrapper => 16,
+        }
+    }
+
+    /// Returns the protocol for a numeric code, if the code is defined.
+    #[must_use]
+    pub fn from_code(code: i64) -> Option<Protocol> {
+        Protocol::ALL
+            .into_iter()
+            .find(|protocol| protocol.code() == code)
+    }
+
+    /// Returns the protocol of a VAST version, as in `4.2`, inline or
+    /// wrapper.
+    #[must_use]
+    pub fn vast(version: &str, wrapper: bool) -> Option<Protocol> {
+        Protocol::ALL.into_iter().find(|protocol| {
+            protocol.vast_version() == Some(version) && protocol.is_wrapper() == wrapper
+        })
+    }
+
+    /// Returns the VAST version of the protocol, as in `4.2`, or `None` for
+    /// DAAST.
+    #[must_use]
+    pub fn vast_version(&self) -> Option<&'static str> {
+        match self {
+            Protocol::Vast10 | Protocol::Vast10Wrapper => Some("1.0"),
+            Protocol::Vast20 | Protocol::Vast20Wrapper => Some("2.0"),
+            Protocol::Vast30 | Protocol::Vast30Wrapper => Some("3.0"),
+            Protocol::Vast40 | Protocol::Vast40Wrapper => Some("4.0"),
+            Protocol::Vast41 | Protocol::Vast41Wrapper => Some("4.1"),
+            Protocol::Vast42 | Protocol::Vast42Wrapper => Some("4.2"),
+            Protocol::Vast43 | Protocol::Vast43Wrapper => Some("4.3"),
+            Protocol::Daast10 | Protocol::Daast10Wrapper => None,
+        }
+    }
+
+    /// Returns whether the protocol is for wrappers of a creative served
+    /// elsewhere.
+    #[must_use]
+    pub fn is_wrapper(&self) -> bool {
+        matches!(
+            self,
+            Protocol::Vast10Wrapper
+                | Protocol::Vast20Wrapper
+                | Protocol::Vast30Wrapper
+                | Protocol::Vast40Wrapper
+                | Protocol::Daast10Wrapper
+                | Protocol::Vast41Wrapper
+                | Protocol::Vast42Wrapper
+                | Protocol::Vast43Wrapper
+        )
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
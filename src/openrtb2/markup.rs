@@ -0,0 +1,193 @@
+use std::fmt;
+
+use super::codes::MarkupType;
+use super::split::offers;
+use super::{Bid, Imp};
+use crate::native::NativeResponse;
+use crate::vast::{Vast, VastError};
+
+/// The ad markup of a bid (`Bid.adm`), decoded.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum Markup {
+    /// No markup: the ad is served by the win notice (`Bid.nurl`).
+    Empty,
+    /// HTML or JavaScript, for banners.
+    Html(String),
+    /// A VAST document, for video and audio.
+    Vast(Vast),
+    /// A native ad.
+    Native(NativeResponse),
+}
+
+/// Format of ad markup, as found by looking at its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkupFormat {
+    Html,
+    Vast,
+    Native,
+}
+
+impl MarkupFormat {
+    /// Returns the format of the markup of a media type.
+    #[must_use]
+    pub fn of(media: MarkupType) -> MarkupFormat {
+        match media {
+            MarkupType::Banner => MarkupFormat::Html,
+            MarkupType::Video | MarkupType::Audio => MarkupFormat::Vast,
+            MarkupType::Native => MarkupFormat::Native,
+        }
+    }
+
+    /// Returns the format of markup from its first characters: a JSON
+    /// object is native, an XML document with a `VAST` root is VAST, and
+    /// anything else is HTML.
+    #[must_use]
+    pub fn sniff(markup: &str) -> MarkupFormat {
+        let mut rest = markup.trim_start_matches('\u{feff}').trim_start();
+        if rest.starts_with('{') {
+            return MarkupFormat::Native;
+        }
+        // Skips the XML declaration and comments before the root element.
+        loop {
+            let end = if rest.starts_with("<?") {
+                "?>"
+            } else if rest.starts_with("<!--") {
+                "-->"
+            } else {
+                break;
+            };
+            match rest.find(end) {
+                Some(length) => rest = rest[length + end.len()..].trim_start(),
+                None => return MarkupFormat::Html,
+            }
+        }
+        let root = rest
+            .strip_prefix('<')
+            .map(str::trim_start)
+            .filter(|tag| tag.starts_with("VAST"))
+            .map(|tag| &tag["VAST".len()..]);
+        match root {
+            Some(after) if after.starts_with(|c: char| c.is_whitespace() || c == '>') => {
+                MarkupFormat::Vast
+            }
+            _ => MarkupFormat::Html,
+        }
+    }
+}
+
+/// Errors raised when decoding the markup of a bid.
+#[derive(Debug)]
+pub enum MarkupError {
+    /// `Bid.mtype` is not a known markup type.
+    UnknownType(i64),
+    /// `Bid.mtype` is a media type the impression does not offer.
+    UnofferedType(MarkupType),
+    /// The markup is not of the media type of the bid: `Bid.mtype` or,
+    /// without it, the only media type of the impression.
+    Mismatch {
+        expected: MarkupType,
+        found: MarkupFormat,
+    },
+    /// The markup looks like VAST but cannot be parsed.
+    InvalidVast(VastError),
+    /// The markup looks like native markup but cannot be parsed.
+    InvalidNative(serde_json::Error),
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkupError::UnknownType(code) => write!(f, "unknown markup type {code}"),
+            MarkupError::UnofferedType(media) => {
+                write!(f, "impression does not offer {} markup", media.name())
+            }
+            MarkupError::Mismatch { expected, found } => {
+                write!(f, "expected {} markup, found {found:?}", expected.name())
+            }
+            MarkupError::InvalidVast(e) => write!(f, "invalid VAST markup: {e}"),
+            MarkupError::InvalidNative(e) => write!(f, "invalid native markup: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MarkupError::InvalidVast(e) => Some(e),
+            MarkupError::InvalidNative(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Bid {
+    /// Decodes the ad markup (`adm`).
+    ///
+    /// The expected media type is `mtype` or, without it, the only media
+    /// type the impression offers, if given. The format is found from the
+    /// markup itself, and must match the expected media type if there is
+    /// one. A bid without markup is [`Markup::Empty`], its ad served by the
+    /// win notice.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `mtype` is unknown or not offered by the impression, or if
+    /// the markup is not of the expected media type or cannot be decoded.
+    ///
+    /// ```
+    /// use iab::openrtb2::*;
+    ///
+    /// let imp: Imp = Imp::banner(300, 250).into();
+    /// let bid: Bid = Bid::builder("1", "1", 1.0).adm("<div>ad</div>").into();
+    /// assert_eq!(bid.markup(Some(&imp)).unwrap(), Markup::Html("<div>ad</div>".to_string()));
+    ///
+    /// let bid: Bid = Bid::builder("1", "1", 1.0).adm(r#"{"link": {"url": "https://a.com"}}"#).into();
+    /// assert!(matches!(
+    ///     bid.markup(Some(&imp)),
+    ///     Err(MarkupError::Mismatch { found: MarkupFormat::Native, .. })
+    /// ));
+    /// ```
+    pub fn markup(&self, imp: Option<&Imp>) -> Result<Markup, MarkupError> {
+        let Some(adm) = self.adm.as_deref().filter(|adm| !adm.trim().is_empty()) else {
+            return Ok(Markup::Empty);
+        };
+
+        let expected = match self.mtype {
+            Some(code) => {
+                let media = MarkupType::from_code(code).ok_or(MarkupError::UnknownType(code))?;
+                if imp.is_some_and(|imp| !offers(imp, media)) {
+                    return Err(MarkupError::UnofferedType(media));
+                }
+                Some(media)
+            }
+            None => imp.and_then(only_media_type),
+        };
+
+        let found = MarkupFormat::sniff(adm);
+        if let Some(expected) = expected.filter(|media| MarkupFormat::of(*media) != found) {
+            return Err(MarkupError::Mismatch { expected, found });
+        }
+
+        match found {
+            MarkupFormat::Html => Ok(Markup::Html(adm.to_string())),
+            MarkupFormat::Vast => Vast::parse(adm)
+                .map(Markup::Vast)
+                .map_err(MarkupError::InvalidVast),
+            MarkupFormat::Native => NativeResponse::parse(adm)
+                .map(Markup::Native)
+                .map_err(MarkupError::InvalidNative),
+        }
+    }
+}
+
+/// Returns the media type of an impression offering only one.
+fn only_media_type(imp: &Imp) -> Option<MarkupType> {
+    let mut offered = MarkupType::ALL
+        .into_iter()
+        .filter(|media| offers(imp, *media));
+    match (offered.next(), offered.next()) {
+        (Some(media), None) => Some(media),
+        _ => None,
+    }
+}
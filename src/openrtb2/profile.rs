@@ -0,0 +1,486 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::codes::MarkupType;
+use super::{BidRequest, Content};
+
+/// Clears fields of an object, recording the path of each one that was set.
+macro_rules! strip {
+    ($removed:expr, $path:expr, $object:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            if $object.$field.take().is_some() {
+                $removed.push(Removal::Field {
+                    path: join($path, stringify!($field)),
+                });
+            }
+        )*
+    };
+}
+
+/// Something a [`BidderProfile`] took out of a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Removal {
+    /// An object or field the bidder does not support, by path, such as
+    /// `dooh` or `imp[0].video.podid`. Paths are those of the original
+    /// request.
+    Field { path: String },
+    /// An impression left without any media type the bidder supports.
+    UnsupportedImp { id: String },
+    /// An impression beyond the number the bidder takes.
+    ExcessImp { id: String },
+    /// An impression dropped for the request to fit the bidder's payload
+    /// size.
+    OversizedImp { id: String },
+    /// An extended ID from a source the bidder does not take.
+    Eid { source: Option<String> },
+    /// Segments beyond the number the bidder takes, from `user.data[index]`.
+    Segments { data: usize, count: usize },
+}
+
+/// A request shaped for a bidder, with what was removed from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shaped {
+    pub request: BidRequest,
+    /// Removals, in the order they were made.
+    pub removed: Vec<Removal>,
+}
+
+impl Shaped {
+    /// Returns whether no impression is left, in which case the request
+    /// should not be sent.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.request.imp.is_empty()
+    }
+}
+
+/// What a bidder accepts, used to transform a request into the shape the
+/// bidder takes.
+///
+/// A profile supports everything by default; each setter declares a
+/// restriction. [`shape`](BidderProfile::shape) applies them in order:
+///
+/// 1. Removes `dooh` and the fields introduced in `OpenRTB` 2.6 if the
+///    bidder does not support them, moving those `OpenRTB` 2.5 carries in
+///    extensions.
+/// 2. Removes the media objects of unsupported types from each impression,
+///    then the impressions left without any.
+/// 3. Keeps the extended IDs of the accepted sources, and caps the number of
+///    `user.data` segments.
+/// 4. Keeps the first impressions up to the maximum count, then drops the
+///    last ones until the serialized request fits the maximum payload size.
+/// 5. Lowers `tmax` by the bidder's latency margin, and caps it.
+///
+/// ```
+/// use iab::openrtb2::codes::MarkupType;
+/// use iab::openrtb2::*;
+///
+/// let request = BidRequest::builder()
+///     .id("1")
+///     .tmax(200)
+///     .imp(Imp::banner(300, 250))
+///     .imp(Imp::video(["video/mp4"]))
+///     .build()
+///     .unwrap();
+///
+/// let profile = BidderProfile::new()
+///     .media_types([MarkupType::Banner])
+///     .tmax_margin(50);
+/// let shaped = profile.shape(&request);
+///
+/// assert_eq!(shaped.request.imp.len(), 1);
+/// assert_eq!(shaped.request.tmax, Some(150));
+/// assert!(shaped.removed.contains(&Removal::UnsupportedImp { id: "2".to_string() }));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BidderProfile {
+    media_types: HashSet<MarkupType>,
+    dooh: bool,
+    openrtb_26: bool,
+    max_imps: Option<usize>,
+    max_bytes: Option<usize>,
+    eid_sources: Option<HashSet<String>>,
+    max_segments: Option<usize>,
+    tmax_margin: i64,
+    max_tmax: Option<i64>,
+}
+
+impl Default for BidderProfile {
+    fn default() -> Self {
+        BidderProfile {
+            media_types: MarkupType::ALL.into_iter().collect(),
+            dooh: true,
+            openrtb_26: true,
+            max_imps: None,
+            max_bytes: None,
+            eid_sources: None,
+            max_segments: None,
+            tmax_margin: 0,
+            max_tmax: None,
+        }
+    }
+}
+
+impl BidderProfile {
+    /// Creates a profile of a bidder that supports everything.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the media types the bidder supports.
+    #[must_use]
+    pub fn media_types(mut self, types: impl IntoIterator<Item = MarkupType>) -> Self {
+        self.media_types = types.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the bidder supports digital out-of-home inventory
+    /// (`dooh`).
+    #[must_use]
+    pub fn dooh(mut self, supported: bool) -> Self {
+        self.dooh = supported;
+        self
+    }
+
+    /// Sets whether the bidder supports the fields introduced in `OpenRTB`
+    /// 2.6, such as pods, `qty`, `sua` and `regs.gpp`. Without them, the
+    /// fields that `OpenRTB` 2.5 carries in extensions are moved there:
+    /// `source.schain`, `regs.gdpr`, `regs.us_privacy`, `user.consent` and
+    /// `user.eids`.
+    #[must_use]
+    pub fn openrtb_26(mut self, supported: bool) -> Self {
+        self.openrtb_26 = supported;
+        self
+    }
+
+    /// Sets the maximum number of impressions per request.
+    #[must_use]
+    pub fn max_imps(mut self, max: usize) -> Self {
+        self.max_imps = Some(max);
+        self
+    }
+
+    /// Sets the maximum size of the request serialized as JSON, in bytes.
+    #[must_use]
+    pub fn max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = Some(max);
+        self
+    }
+
+    /// Sets the sources of `user.eids` the bidder takes, such as
+    /// `liveramp.com`.
+    #[must_use]
+    pub fn eid_sources<I>(mut self, sources: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.eid_sources = Some(
+            sources
+                .into_iter()
+                .map(|s| s.into().to_ascii_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets the maximum number of segments across `user.data`.
+    #[must_use]
+    pub fn max_segments(mut self, max: usize) -> Self {
+        self.max_segments = Some(max);
+        self
+    }
+
+    /// Sets the time taken from `tmax` for the round trip to the bidder, in
+    /// milliseconds.
+    #[must_use]
+    pub fn tmax_margin(mut self, margin: i64) -> Self {
+        self.tmax_margin = margin;
+        self
+    }
+
+    /// Sets the maximum `tmax` the bidder is given, in milliseconds.
+    #[must_use]
+    pub fn max_tmax(mut self, max: i64) -> Self {
+        self.max_tmax = Some(max);
+        self
+    }
+
+    /// Returns the request shaped for the bidder, with what was removed.
+    #[must_use]
+    pub fn shape(&self, request: &BidRequest) -> Shaped {
+        let mut request = request.clone();
+        let mut removed = Vec::new();
+
+        if !self.dooh {
+            strip!(removed, "", request, [dooh]);
+        }
+        if !self.openrtb_26 {
+            strip_26(&mut request, &mut removed);
+        }
+        self.strip_media(&mut request, &mut removed);
+        self.prune_user(&mut request, &mut removed);
+        if !self.openrtb_26 {
+            // After pruning, so that only the accepted extended IDs move.
+            move_25(&mut request, &mut removed);
+        }
+        self.cap_imps(&mut request, &mut removed);
+
+        if let Some(tmax) = &mut request.tmax {
+            *tmax = tmax.saturating_sub(self.tmax_margin).max(0);
+        }
+        if let Some(max) = self.max_tmax {
+            request.tmax = Some(request.tmax.map_or(max, |tmax| tmax.min(max)));
+        }
+
+        Shaped { request, removed }
+    }
+
+    fn strip_media(&self, request: &mut BidRequest, removed: &mut Vec<Removal>) {
+        let supports = |media| self.media_types.contains(&media);
+        let mut imps = Vec::with_capacity(request.imp.len());
+        for (i, mut imp) in request.imp.drain(..).enumerate() {
+            let path = format!("imp[{i}]");
+            let offered = imp.banner.is_some()
+                || imp.video.is_some()
+                || imp.audio.is_some()
+                || imp.native_markup.is_some();
+            if !supports(MarkupType::Banner) {
+                strip!(removed, &path, imp, [banner]);
+            }
+            if !supports(MarkupType::Video) {
+                strip!(removed, &path, imp, [video]);
+            }
+            if !supports(MarkupType::Audio) {
+                strip!(removed, &path, imp, [audio]);
+            }
+            if !supports(MarkupType::Native) {
+                strip!(removed, &path, imp, [native_markup]);
+            }
+
+            let left = imp.banner.is_some()
+                || imp.video.is_some()
+                || imp.audio.is_some()
+                || imp.native_markup.is_some();
+            if offered && !left {
+                removed.push(Removal::UnsupportedImp { id: imp.id });
+            } else {
+                imps.push(imp);
+            }
+        }
+        request.imp = imps;
+    }
+
+    fn prune_user(&self, request: &mut BidRequest, removed: &mut Vec<Removal>) {
+        let Some(user) = &mut request.user else {
+            return;
+        };
+
+        if let (Some(sources), Some(eids)) = (&self.eid_sources, &mut user.eids) {
+            eids.retain(|eid| {
+                let source = eid.source.as_deref().map(str::to_ascii_lowercase);
+                let keep = source.is_some_and(|source| sources.contains(&source));
+                if !keep {
+                    removed.push(Removal::Eid {
+                        source: eid.source.clone(),
+                    });
+                }
+                keep
+            });
+            if eids.is_empty() {
+                user.eids = None;
+            }
+        }
+
+        if let (Some(max), Some(data)) = (self.max_segments, &mut user.data) {
+            let mut left = max;
+            for (i, data) in data.iter_mut().enumerate() {
+                let Some(segments) = &mut data.segment else {
+                    continue;
+                };
+                let kept = segments.len().min(left);
+                left -= kept;
+                if kept < segments.len() {
+                    removed.push(Removal::Segments {
+                        data: i,
+                        count: segments.len() - kept,
+                    });
+                    segments.truncate(kept);
+                }
+            }
+        }
+    }
+
+    fn cap_imps(&self, request: &mut BidRequest, removed: &mut Vec<Removal>) {
+        if let Some(max) = self.max_imps {
+            for imp in request.imp.drain(max.min(request.imp.len())..) {
+                removed.push(Removal::ExcessImp { id: imp.id });
+            }
+        }
+        if let Some(max) = self.max_bytes {
+            while !request.imp.is_empty() && size(request) > max {
+                if let Some(imp) = request.imp.pop() {
+                    removed.push(Removal::OversizedImp { id: imp.id });
+                }
+            }
+        }
+    }
+}
+
+/// Removes the fields introduced in `OpenRTB` 2.6 that `OpenRTB` 2.5 has no
+/// place for.
+fn strip_26(request: &mut BidRequest, removed: &mut Vec<Removal>) {
+    strip!(removed, "", request, [dooh, wlangb, acat, cattax]);
+
+    for (i, imp) in request.imp.iter_mut().enumerate() {
+        let path = format!("imp[{i}]");
+        strip!(removed, &path, imp, [rwdd, ssai, qty, dt, refresh]);
+        if let Some(video) = &mut imp.video {
+            strip!(
+                removed,
+                &join(&path, "video"),
+                video,
+                [
+                    maxseq,
+                    poddur,
+                    podid,
+                    podseq,
+                    rqddurs,
+                    plcmt,
+                    slotinpod,
+                    mincpmpersec,
+                    poddedupe,
+                    durfloors,
+                ]
+            );
+        }
+        if let Some(audio) = &mut imp.audio {
+            strip!(
+                removed,
+                &join(&path, "audio"),
+                audio,
+                [
+                    maxseq,
+                    poddur,
+                    podid,
+                    podseq,
+                    rqddurs,
+                    slotinpod,
+                    mincpmpersec,
+                    durfloors,
+                ]
+            );
+        }
+        let deals = imp
+            .pmp
+            .iter_mut()
+            .flat_map(|p| p.deals.iter_mut().flatten());
+        for (j, deal) in deals.enumerate() {
+            let path = format!("{path}.pmp.deals[{j}]");
+            strip!(removed, &path, deal, [guar, mincpmpersec, durfloors]);
+        }
+    }
+
+    if let Some(site) = &mut request.site {
+        strip!(
+            removed,
+            "site",
+            site,
+            [cattax, kwarray, inventorypartnerdomain]
+        );
+        if let Some(content) = &mut site.content {
+            strip_content_26(content, "site.content", removed);
+        }
+    }
+    if let Some(app) = &mut request.app {
+        strip!(
+            removed,
+            "app",
+            app,
+            [cattax, kwarray, inventorypartnerdomain]
+        );
+        if let Some(content) = &mut app.content {
+            strip_content_26(content, "app.content", removed);
+        }
+    }
+    if let Some(device) = &mut request.device {
+        strip!(removed, "device", device, [sua, langb]);
+    }
+    if let Some(user) = &mut request.user {
+        strip!(removed, "user", user, [kwarray]);
+    }
+    if let Some(regs) = &mut request.regs {
+        strip!(removed, "regs", regs, [gpp, gpp_sid]);
+    }
+}
+
+fn strip_content_26(content: &mut Content, path: &str, removed: &mut Vec<Removal>) {
+    strip!(
+        removed,
+        path,
+        content,
+        [cattax, kwarray, langb, network, channel]
+    );
+}
+
+/// Moves the fields of `OpenRTB` 2.6 that `OpenRTB` 2.5 carries in
+/// extensions: the supply chain to `source.ext`, the GDPR and US privacy
+/// signals to `regs.ext`, and the consent string and extended IDs to
+/// `user.ext`.
+fn move_25(request: &mut BidRequest, removed: &mut Vec<Removal>) {
+    if let Some(source) = &mut request.source {
+        let schain = source.schain.take();
+        move_to_ext(&mut source.ext, "source", "schain", schain, removed);
+    }
+    if let Some(regs) = &mut request.regs {
+        let gdpr = regs.gdpr.take();
+        move_to_ext(&mut regs.ext, "regs", "gdpr", gdpr, removed);
+        let us_privacy = regs.us_privacy.take();
+        move_to_ext(&mut regs.ext, "regs", "us_privacy", us_privacy, removed);
+    }
+    if let Some(user) = &mut request.user {
+        let consent = user.consent.take();
+        move_to_ext(&mut user.ext, "user", "consent", consent, removed);
+        let eids = user.eids.take();
+        move_to_ext(&mut user.ext, "user", "eids", eids, removed);
+    }
+}
+
+/// Sets a field of an extension, which is removed instead if the extension
+/// is not an object.
+fn move_to_ext<T: Serialize>(
+    ext: &mut Option<Value>,
+    path: &str,
+    field: &str,
+    value: Option<T>,
+    removed: &mut Vec<Removal>,
+) {
+    let Some(value) = value else {
+        return;
+    };
+    match ext.get_or_insert_with(|| Value::Object(Map::new())) {
+        Value::Object(ext) => {
+            let value = serde_json::to_value(value).unwrap_or(Value::Null);
+            ext.insert(field.to_string(), value);
+        }
+        _ => removed.push(Removal::Field {
+            path: join(path, field),
+        }),
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+/// Size of the request serialized as JSON, in bytes.
+fn size(request: &BidRequest) -> usize {
+    serde_json::to_vec(request).map_or(0, |json| json.len())
+}
@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use super::codes::MarkupType;
+use super::{BidRequest, BidResponse, Imp, SeatBid};
+use crate::currency::DEFAULT_CURRENCY;
+
+/// Separator of the parts of a derived request ID, as in `request:imp:1` or
+/// `request:video`.
+const SEPARATOR: char = ':';
+
+/// Marks the derived ID of a request split by impression.
+const IMP: &str = "imp";
+
+/// A response that cannot be merged into the response to the original
+/// request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+    /// The response ID is neither the request ID nor one derived from it.
+    UnknownResponse { id: String },
+    /// A bid is for an impression that was not in the sub-request answered.
+    UnknownImp { response: String, impid: String },
+    /// The response is in another currency than an earlier one.
+    CurrencyMismatch {
+        response: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::UnknownResponse { id } => write!(f, "unknown response ID {id}"),
+            MergeError::UnknownImp { response, impid } => {
+                write!(f, "response {response}: unknown impression {impid}")
+            }
+            MergeError::CurrencyMismatch {
+                response,
+                expected,
+                found,
+            } => write!(
+                f,
+                "response {response}: currency {found}, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// The part of the original request a response answers.
+enum Part<'r> {
+    Whole,
+    Imp(&'r str),
+    Media(MarkupType),
+}
+
+impl BidRequest {
+    /// Splits the request into one request per impression, for bidders that
+    /// take a single `Imp` per request.
+    ///
+    /// Each sub-request keeps every top-level field of the request, and its
+    /// ID is derived from the request and impression IDs, as in
+    /// `request:imp:1`. Impression IDs are kept, so bids on a sub-request
+    /// refer to the original impressions.
+    ///
+    /// ```
+    /// use iab::openrtb2::*;
+    ///
+    /// let request = BidRequest::builder()
+    ///     .id("auction")
+    ///     .imp(Imp::banner(300, 250))
+    ///     .imp(Imp::video(["video/mp4"]))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let requests = request.split_by_imp();
+    /// assert_eq!(requests.len(), 2);
+    /// assert_eq!(requests[1].id, "auction:imp:2");
+    /// assert_eq!(requests[1].imp[0].id, "2");
+    /// ```
+    #[must_use]
+    pub fn split_by_imp(&self) -> Vec<BidRequest> {
+        self.imp
+            .iter()
+            .map(|imp| self.part(&format!("{IMP}{SEPARATOR}{}", imp.id), vec![imp.clone()]))
+            .collect()
+    }
+
+    /// Splits the request into one request per media type offered, for
+    /// bidders that take banner, video, audio and native in separate requests.
+    ///
+    /// The request for a media type holds the impressions offering it, with
+    /// the objects of the other types removed, so a multi-format impression
+    /// is part of several requests. Each sub-request keeps every top-level
+    /// field of the request, and its ID is derived from the request ID and
+    /// the media type, as in `request:video`. Sub-requests come in the order
+    /// of [`MarkupType::ALL`], and media types without impressions have none.
+    #[must_use]
+    pub fn split_by_media_type(&self) -> Vec<BidRequest> {
+        MarkupType::ALL
+            .into_iter()
+            .filter_map(|media| {
+                let imps: Vec<Imp> = self
+                    .imp
+                    .iter()
+                    .filter(|imp| offers(imp, media))
+                    .map(|imp| only(imp, media))
+                    .collect();
+                (!imps.is_empty()).then(|| self.part(media.name(), imps))
+            })
+            .collect()
+    }
+
+    /// Merges the responses to the sub-requests of [`split_by_imp`] or
+    /// [`split_by_media_type`] into a response to this request.
+    ///
+    /// Responses are matched to their sub-request by ID, and a response to
+    /// the request itself is taken as is. Bids must be for an impression of
+    /// the sub-request answered. Bids answering a media type sub-request
+    /// without `Bid.mtype` are given that type, since the original impression
+    /// may offer several. Bids of the same seat are combined into one
+    /// seatbid, except for seatbids with `group` = 1, which are kept whole. A
+    /// bid whose ID was already used is renamed after its sub-request, as in
+    /// `video:1`. When no response has a bid, `nbr` is the first reason
+    /// given.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a response does not answer this request or a sub-request of
+    /// it, if a bid is for an impression the sub-request does not have, or if
+    /// responses with bids are in different currencies.
+    ///
+    /// [`split_by_imp`]: BidRequest::split_by_imp
+    /// [`split_by_media_type`]: BidRequest::split_by_media_type
+    ///
+    /// ```
+    /// use iab::openrtb2::*;
+    ///
+    /// let request = BidRequest::builder()
+    ///     .id("auction")
+    ///     .imp(Imp::video(["video/mp4"]).with_banner(Banner::default()))
+    ///     .build()
+    ///     .unwrap();
+    /// let split = request.split_by_media_type();
+    ///
+    /// let banner = BidResponse::builder(&split[0].id)
+    ///     .bid("seat", Bid::builder("1", "1", 1.0))
+    ///     .build()
+    ///     .unwrap();
+    /// let video = BidResponse::builder(&split[1].id)
+    ///     .bid("seat", Bid::builder("1", "1", 2.0))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let response = request.merge_responses([&banner, &video]).unwrap();
+    /// let seatbids = response.seatbid.unwrap();
+    ///
+    /// assert_eq!(response.id, "auction");
+    /// assert_eq!(seatbids.len(), 1);
+    /// assert_eq!(seatbids[0].bid[1].id, "video:1");
+    /// assert_eq!(seatbids[0].bid[1].mtype, Some(2));
+    /// ```
+    pub fn merge_responses<'r>(
+        &self,
+        responses: impl IntoIterator<Item = &'r BidResponse>,
+    ) -> Result<BidResponse, MergeError> {
+        let mut seatbids: Vec<SeatBid> = Vec::new();
+        let mut ids = HashSet::new();
+        let mut currency: Option<&str> = None;
+        let mut bidid = None;
+        let mut customdata = None;
+        let mut nbr = None;
+
+        for response in responses {
+            let part = self
+                .part_of(&response.id)
+                .ok_or_else(|| MergeError::UnknownResponse {
+                    id: response.id.clone(),
+                })?;
+            bidid = bidid.or_else(|| response.bidid.clone());
+            customdata = customdata.or_else(|| response.customdata.clone());
+
+            if response.seatbid.iter().flatten().all(|s| s.bid.is_empty()) {
+                nbr = nbr.or(response.nbr.filter(|&nbr| nbr != 0));
+                continue;
+            }
+            let cur = response.cur.as_deref().unwrap_or(DEFAULT_CURRENCY);
+            match currency {
+                Some(expected) if expected != cur => {
+                    return Err(MergeError::CurrencyMismatch {
+                        response: response.id.clone(),
+                        expected: expected.to_string(),
+                        found: cur.to_string(),
+                    });
+                }
+                Some(_) => {}
+                None => currency = Some(cur),
+            }
+
+            for seatbid in response.seatbid.iter().flatten() {
+                let mut merged = Vec::with_capacity(seatbid.bid.len());
+                for bid in &seatbid.bid {
+                    let imp = self
+                        .imp
+                        .iter()
+                        .find(|imp| imp.id == bid.impid)
+                        .filter(|imp| match part {
+                            Part::Whole => true,
+                            Part::Imp(id) => imp.id == id,
+                            Part::Media(media) => offers(imp, media),
+                        });
+                    if imp.is_none() {
+                        return Err(MergeError::UnknownImp {
+                            response: response.id.clone(),
+                            impid: bid.impid.clone(),
+                        });
+                    }
+
+                    let mut bid = bid.clone();
+                    if let Part::Media(media) = part {
+                        bid.mtype = bid.mtype.or(Some(media.code()));
+                    }
+                    if !ids.insert(bid.id.clone()) {
+                        let part = response.id.get(self.id.len() + 1..);
+                        bid.id = unique_id(&mut ids, part.unwrap_or(&response.id), &bid.id);
+                    }
+                    merged.push(bid);
+                }
+
+                let same_seat = seatbids.iter_mut().find(|s| {
+                    s.seat == seatbid.seat && s.group != Some(1) && seatbid.group != Some(1)
+                });
+                match same_seat {
+                    Some(same_seat) => same_seat.bid.extend(merged),
+                    None if !merged.is_empty() => seatbids.push(SeatBid {
+                        bid: merged,
+                        ..seatbid.clone()
+                    }),
+                    None => {}
+                }
+            }
+        }
+
+        let nbr = seatbids.is_empty().then(|| nbr.unwrap_or(0));
+        Ok(BidResponse {
+            id: self.id.clone(),
+            seatbid: (!seatbids.is_empty()).then_some(seatbids),
+            bidid,
+            cur: currency.map(str::to_string),
+            customdata,
+            nbr,
+            ext: None,
+        })
+    }
+
+    /// Returns a sub-request with a derived ID and the given impressions.
+    fn part(&self, suffix: &str, imp: Vec<Imp>) -> BidRequest {
+        BidRequest {
+            id: format!("{}{SEPARATOR}{suffix}", self.id),
+            imp,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the part of the request a response ID answers.
+    fn part_of(&self, id: &str) -> Option<Part<'_>> {
+        if id == self.id {
+            return Some(Part::Whole);
+        }
+        let suffix = id.strip_prefix(&self.id)?.strip_prefix(SEPARATOR)?;
+        if let Some(imp_id) = suffix
+            .strip_prefix(IMP)
+            .and_then(|s| s.strip_prefix(SEPARATOR))
+        {
+            return self
+                .imp
+                .iter()
+                .find(|imp| imp.id == imp_id)
+                .map(|imp| Part::Imp(&imp.id));
+        }
+        MarkupType::ALL
+            .into_iter()
+            .find(|media| media.name() == suffix)
+            .map(Part::Media)
+    }
+}
+
+/// Returns whether an impression offers a media type.
+pub(super) fn offers(imp: &Imp, media: MarkupType) -> bool {
+    match media {
+        MarkupType::Banner => imp.banner.is_some(),
+        MarkupType::Video => imp.video.is_some(),
+        MarkupType::Audio => imp.audio.is_some(),
+        MarkupType::Native => imp.native_markup.is_some(),
+    }
+}
+
+/// Returns the impression with the objects of other media types removed.
+fn only(imp: &Imp, media: MarkupType) -> Imp {
+    let keep = |other: MarkupType| media == other;
+    Imp {
+        banner: imp.banner.clone().filter(|_| keep(MarkupType::Banner)),
+        video: imp.video.clone().filter(|_| keep(MarkupType::Video)),
+        audio: imp.audio.clone().filter(|_| keep(MarkupType::Audio)),
+        native_markup: imp
+            .native_markup
+            .clone()
+            .filter(|_| keep(MarkupType::Native)),
+        ..imp.clone()
+    }
+}
+
+/// Returns a bid ID not used yet, prefixing taken ones with the part of the
+/// request they answer.
+fn unique_id(ids: &mut HashSet<String>, part: &str, id: &str) -> String {
+    let mut unique = format!("{part}{SEPARATOR}{id}");
+    let mut n = 2;
+    while ids.contains(&unique) {
+        unique = format!("{part}{SEPARATOR}{id}{SEPARATOR}{n}");
+        n += 1;
+    }
+    ids.insert(unique.clone());
+    unique
+}
@@ -0,0 +1,472 @@
+//! Typed identifiers and codes.
+//!
+//! The `OpenRTB` structures keep identifiers and codes as plain strings. The
+//! newtypes in this module are an opt-in layer on top of them: they are
+//! validated on construction, serialize as the plain string, and keep
+//! values such as `Imp.id` and `Bid.dealid` from being mixed up.
+//!
+//! ```
+//! use iab::openrtb2::types::*;
+//! use iab::openrtb2::*;
+//!
+//! let imp = Imp {
+//!     id: "1".to_string(),
+//!     bidfloorcur: Some("EUR".to_string()),
+//!     ..Default::default()
+//! };
+//!
+//! assert_eq!(imp.imp_id().unwrap(), ImpId::new("1").unwrap());
+//! assert_eq!(imp.floor_currency().unwrap(), Some(CurrencyCode::new("EUR").unwrap()));
+//! assert!(CurrencyCode::new("USA").is_err());
+//! assert!(CountryCode::new("USA").is_ok());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::codes::NoBidReason;
+use super::{Bid, BidRequest, BidResponse, Deal, Geo, Imp, SeatBid};
+
+/// A string that is not a valid value for the newtype it was converted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// What the value was expected to be, such as `currency code`.
+    pub expected: &'static str,
+    /// The rejected value.
+    pub value: String,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {}: {:?}", self.expected, self.value)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// Defines a validated string newtype that serializes as the plain string.
+macro_rules! newtype {
+    ($(#[$doc:meta])* $name:ident, $expected:literal, $check:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = concat!("Creates a ", $expected, ".")]
+            ///
+            /// # Errors
+            ///
+            #[doc = concat!("Fails if the value is not a valid ", $expected, ".")]
+            pub fn new(value: impl Into<String>) -> Result<Self, InvalidValue> {
+                let value = value.into();
+                let check: fn(&str) -> bool = $check;
+                if check(&value) {
+                    Ok($name(value))
+                } else {
+                    Err(InvalidValue {
+                        expected: $expected,
+                        value,
+                    })
+                }
+            }
+
+            /// Returns the value as a string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = InvalidValue;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                $name::new(value)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = InvalidValue;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                $name::new(value)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = InvalidValue;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $name::new(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+newtype!(
+    /// ID of an impression (`Imp.id`, `Bid.impid`).
+    ImpId,
+    "impression ID",
+    |v| !v.is_empty()
+);
+
+newtype!(
+    /// ID of a bid (`Bid.id`).
+    BidId,
+    "bid ID",
+    |v| !v.is_empty()
+);
+
+newtype!(
+    /// ID of a direct deal (`Deal.id`, `Bid.dealid`).
+    DealId,
+    "deal ID",
+    |v| !v.is_empty()
+);
+
+newtype!(
+    /// ID of a buyer seat (`SeatBid.seat`, `BidRequest.wseat`, `Deal.wseat`).
+    SeatId,
+    "seat ID",
+    |v| !v.is_empty()
+);
+
+newtype!(
+    /// ISO-4217 alphabetic currency code, such as `USD`.
+    CurrencyCode,
+    "currency code",
+    |v| CURRENCY_CODES.binary_search(&v).is_ok()
+);
+
+newtype!(
+    /// ISO-3166-1 alpha-3 country code, such as `USA`, as used by `Geo.country`.
+    CountryCode,
+    "country code",
+    |v| COUNTRY_CODES.binary_search(&v).is_ok()
+);
+
+newtype!(
+    /// ISO-639-1 alpha-2 language code, such as `en`.
+    LanguageCode,
+    "language code",
+    |v| LANGUAGE_CODES.binary_search(&v).is_ok()
+);
+
+newtype!(
+    /// Domain name, such as `ford.com`.
+    Domain,
+    "domain",
+    is_domain
+);
+
+/// Checks the syntax of a domain name: dot-separated labels of letters,
+/// digits and inner hyphens, with at least two labels.
+fn is_domain(value: &str) -> bool {
+    let value = value.strip_suffix('.').unwrap_or(value);
+    value.len() <= 253
+        && value.split('.').count() >= 2
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+/// Converts an optional string field into its newtype.
+fn optional<T>(value: Option<&str>) -> Result<Option<T>, InvalidValue>
+where
+    T: TryFrom<String, Error = InvalidValue>,
+{
+    value
+        .map(|value| T::try_from(value.to_string()))
+        .transpose()
+}
+
+/// Converts an optional list field into its newtypes.
+fn list<T>(values: Option<&[String]>) -> Result<Vec<T>, InvalidValue>
+where
+    T: TryFrom<String, Error = InvalidValue>,
+{
+    values
+        .into_iter()
+        .flatten()
+        .cloned()
+        .map(T::try_from)
+        .collect()
+}
+
+impl BidRequest {
+    /// Allowed currencies for bids (`cur`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn currencies(&self) -> Result<Vec<CurrencyCode>, InvalidValue> {
+        list(self.cur.as_deref())
+    }
+
+    /// Allowed buyer seats (`wseat`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn allowed_seats(&self) -> Result<Vec<SeatId>, InvalidValue> {
+        list(self.wseat.as_deref())
+    }
+
+    /// Blocked buyer seats (`bseat`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn blocked_seats(&self) -> Result<Vec<SeatId>, InvalidValue> {
+        list(self.bseat.as_deref())
+    }
+
+    /// Blocked advertiser domains (`badv`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn blocked_advertisers(&self) -> Result<Vec<Domain>, InvalidValue> {
+        list(self.badv.as_deref())
+    }
+}
+
+impl Imp {
+    /// ID of the impression (`id`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn imp_id(&self) -> Result<ImpId, InvalidValue> {
+        ImpId::new(self.id.clone())
+    }
+
+    /// Currency of the impression floor (`bidfloorcur`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn floor_currency(&self) -> Result<Option<CurrencyCode>, InvalidValue> {
+        optional(self.bidfloorcur.as_deref())
+    }
+}
+
+impl Deal {
+    /// ID of the deal (`id`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn deal_id(&self) -> Result<DealId, InvalidValue> {
+        DealId::new(self.id.clone())
+    }
+
+    /// Currency of the deal floor (`bidfloorcur`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn floor_currency(&self) -> Result<Option<CurrencyCode>, InvalidValue> {
+        optional(self.bidfloorcur.as_deref())
+    }
+
+    /// Allowed buyer seats for the deal (`wseat`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn allowed_seats(&self) -> Result<Vec<SeatId>, InvalidValue> {
+        list(self.wseat.as_deref())
+    }
+
+    /// Allowed advertiser domains for the deal (`wadomain`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn allowed_advertisers(&self) -> Result<Vec<Domain>, InvalidValue> {
+        list(self.wadomain.as_deref())
+    }
+}
+
+impl Geo {
+    /// Country of the location (`country`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn country_code(&self) -> Result<Option<CountryCode>, InvalidValue> {
+        optional(self.country.as_deref())
+    }
+}
+
+impl BidResponse {
+    /// Currency of the bids (`cur`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn currency(&self) -> Result<Option<CurrencyCode>, InvalidValue> {
+        optional(self.cur.as_deref())
+    }
+
+    /// Reason for not bidding (`nbr`), if given with a defined code.
+    pub fn no_bid_reason(&self) -> Option<NoBidReason> {
+        self.nbr.and_then(NoBidReason::from_code)
+    }
+}
+
+impl SeatBid {
+    /// Buyer seat the bids are made on behalf of (`seat`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn seat_id(&self) -> Result<Option<SeatId>, InvalidValue> {
+        optional(self.seat.as_deref())
+    }
+}
+
+impl Bid {
+    /// ID of the bid (`id`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn bid_id(&self) -> Result<BidId, InvalidValue> {
+        BidId::new(self.id.clone())
+    }
+
+    /// ID of the impression the bid is for (`impid`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn imp_id(&self) -> Result<ImpId, InvalidValue> {
+        ImpId::new(self.impid.clone())
+    }
+
+    /// ID of the deal the bid pertains to (`dealid`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn deal_id(&self) -> Result<Option<DealId>, InvalidValue> {
+        optional(self.dealid.as_deref())
+    }
+
+    /// Advertiser domains of the creative (`adomain`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if one of the values is invalid.
+    pub fn advertiser_domains(&self) -> Result<Vec<Domain>, InvalidValue> {
+        list(self.adomain.as_deref())
+    }
+
+    /// Language of the creative (`language`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value is invalid.
+    pub fn language_code(&self) -> Result<Option<LanguageCode>, InvalidValue> {
+        optional(self.language.as_deref())
+    }
+}
+
+/// Active ISO-4217 alphabetic currency codes, sorted, as of the 2025
+/// amendments (`XCG` replacing `ANG`, `ZWG` replacing `ZWL`).
+const CURRENCY_CODES: [&str; 179] = [
+    "AED", "AFN", "ALL", "AMD", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT", "BGN",
+    "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD",
+    "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD",
+    "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF", "XPD",
+    "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+/// ISO-3166-1 alpha-3 country codes, sorted.
+const COUNTRY_CODES: [&str; 249] = [
+    "ABW", "AFG", "AGO", "AIA", "ALA", "ALB", "AND", "ARE", "ARG", "ARM", "ASM", "ATA", "ATF",
+    "ATG", "AUS", "AUT", "AZE", "BDI", "BEL", "BEN", "BES", "BFA", "BGD", "BGR", "BHR", "BHS",
+    "BIH", "BLM", "BLR", "BLZ", "BMU", "BOL", "BRA", "BRB", "BRN", "BTN", "BVT", "BWA", "CAF",
+    "CAN", "CCK", "CHE", "CHL", "CHN", "CIV", "CMR", "COD", "COG", "COK", "COL", "COM", "CPV",
+    "CRI", "CUB", "CUW", "CXR", "CYM", "CYP", "CZE", "DEU", "DJI", "DMA", "DNK", "DOM", "DZA",
+    "ECU", "EGY", "ERI", "ESH", "ESP", "EST", "ETH", "FIN", "FJI", "FLK", "FRA", "FRO", "FSM",
+    "GAB", "GBR", "GEO", "GGY", "GHA", "GIB", "GIN", "GLP", "GMB", "GNB", "GNQ", "GRC", "GRD",
+    "GRL", "GTM", "GUF", "GUM", "GUY", "HKG", "HMD", "HND", "HRV", "HTI", "HUN", "IDN", "IMN",
+    "IND", "IOT", "IRL", "IRN", "IRQ", "ISL", "ISR", "ITA", "JAM", "JEY", "JOR", "JPN", "KAZ",
+    "KEN", "KGZ", "KHM", "KIR", "KNA", "KOR", "KWT", "LAO", "LBN", "LBR", "LBY", "LCA", "LIE",
+    "LKA", "LSO", "LTU", "LUX", "LVA", "MAC", "MAF", "MAR", "MCO", "MDA", "MDG", "MDV", "MEX",
+    "MHL", "MKD", "MLI", "MLT", "MMR", "MNE", "MNG", "MNP", "MOZ", "MRT", "MSR", "MTQ", "MUS",
+    "MWI", "MYS", "MYT", "NAM", "NCL", "NER", "NFK", "NGA", "NIC", "NIU", "NLD", "NOR", "NPL",
+    "NRU", "NZL", "OMN", "PAK", "PAN", "PCN", "PER", "PHL", "PLW", "PNG", "POL", "PRI", "PRK",
+    "PRT", "PRY", "PSE", "PYF", "QAT", "REU", "ROU", "RUS", "RWA", "SAU", "SDN", "SEN", "SGP",
+    "SGS", "SHN", "SJM", "SLB", "SLE", "SLV", "SMR", "SOM", "SPM", "SRB", "SSD", "STP", "SUR",
+    "SVK", "SVN", "SWE", "SWZ", "SXM", "SYC", "SYR", "TCA", "TCD", "TGO", "THA", "TJK", "TKL",
+    "TKM", "TLS", "TON", "TTO", "TUN", "TUR", "TUV", "TWN", "TZA", "UGA", "UKR", "UMI", "URY",
+    "USA", "UZB", "VAT", "VCT", "VEN", "VGB", "VIR", "VNM", "VUT", "WLF", "WSM", "YEM", "ZAF",
+    "ZMB", "ZWE",
+];
+
+/// ISO-639-1 alpha-2 language codes, sorted.
+const LANGUAGE_CODES: [&str; 184] = [
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use super::{Bid, BidRequest, BidResponse, DurFloors, Imp, Price, PriceExt, SupplyChain};
+
+/// A violation of the `OpenRTB` 2.6 required-field or mutual-exclusion rules.
+///
+/// Paths are relative to the validated object, such as `imp[0].video.mimes`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// A required field is missing or empty.
+    Missing { path: String },
+    /// Two fields that must not be used together are both present.
+    MutuallyExclusive {
+        path: String,
+        fields: (&'static str, &'static str),
+    },
+    /// A field holds a value outside of its allowed range or format.
+    Invalid { path: String, reason: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Missing { path } => write!(f, "{path} is required"),
+            ValidationError::MutuallyExclusive { path, fields } if path.is_empty() => {
+                write!(f, "{} and {} are mutually exclusive", fields.0, fields.1)
+            }
+            ValidationError::MutuallyExclusive { path, fields } => {
+                write!(
+                    f,
+                    "{path}: {} and {} are mutually exclusive",
+                    fields.0, fields.1
+                )
+            }
+            ValidationError::Invalid { path, reason } => write!(f, "{path}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+type Check = Result<(), ValidationError>;
+
+impl BidRequest {
+    /// Checks the request against the `OpenRTB` 2.6 required-field and
+    /// mutual-exclusion rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violation found.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        required_str("id", &self.id)?;
+        if self.imp.is_empty() {
+            return Err(missing("imp"));
+        }
+
+        let distribution = [self.site.is_some(), self.app.is_some(), self.dooh.is_some()];
+        if distribution.iter().filter(|present| **present).count() > 1 {
+            let fields = match distribution {
+                [true, true, _] => ("site", "app"),
+                [true, _, true] => ("site", "dooh"),
+                _ => ("app", "dooh"),
+            };
+            return Err(exclusive("", fields));
+        }
+
+        exclusive_options("", ("wseat", &self.wseat), ("bseat", &self.bseat))?;
+        exclusive_options("", ("wlang", &self.wlang), ("wlangb", &self.wlangb))?;
+        exclusive_options("", ("acat", &self.acat), ("bcat", &self.bcat))?;
+        flag("at", self.at.filter(|at| *at < 500), &[1, 2])?;
+        flag("test", self.test, &[0, 1])?;
+        flag("allimps", self.allimps, &[0, 1])?;
+        for (i, cur) in self.cur.iter().flatten().enumerate() {
+            currency(&format!("cur[{i}]"), cur)?;
+        }
+
+        let mut ids = HashSet::new();
+        for (i, imp) in self.imp.iter().enumerate() {
+            let path = format!("imp[{i}]");
+            imp.validate_at(&path)?;
+            if !ids.insert(imp.id.as_str()) {
+                return Err(invalid(
+                    &join(&path, "id"),
+                    "must be unique within the request",
+                ));
+            }
+        }
+
+        if let Some(schain) = self.source.as_ref().and_then(|s| s.schain.as_ref()) {
+            schain.validate_at("source.schain")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Imp {
+    fn validate_at(&self, path: &str) -> Check {
+        required_str(&join(path, "id"), &self.id)?;
+
+        if self.banner.is_none()
+            && self.video.is_none()
+            && self.audio.is_none()
+            && self.native_markup.is_none()
+        {
+            return Err(invalid(
+                path,
+                "at least one of banner, video, audio or native is required",
+            ));
+        }
+
+        non_negative(&join(path, "bidfloor"), self.bidfloor)?;
+        if let Some(cur) = &self.bidfloorcur {
+            currency(&join(path, "bidfloorcur"), cur)?;
+        }
+        flag(&join(path, "instl"), self.instl, &[0, 1])?;
+        flag(&join(path, "secure"), self.secure, &[0, 1])?;
+
+        if let Some(video) = &self.video {
+            let path = join(path, "video");
+            media(
+                &path,
+                &video.mimes,
+                video.minduration,
+                video.maxduration,
+                video.rqddurs.as_deref(),
+            )?;
+            non_negative(&join(&path, "mincpmpersec"), video.mincpmpersec)?;
+            durfloors(&path, video.durfloors.as_deref())?;
+        }
+        if let Some(audio) = &self.audio {
+            let path = join(path, "audio");
+            media(
+                &path,
+                &audio.mimes,
+                audio.minduration,
+                audio.maxduration,
+                audio.rqddurs.as_deref(),
+            )?;
+            non_negative(&join(&path, "mincpmpersec"), audio.mincpmpersec)?;
+            durfloors(&path, audio.durfloors.as_deref())?;
+        }
+        if let Some(native) = &self.native_markup {
+            required_str(&join(path, "native.request"), &native.request)?;
+        }
+
+        if let Some(pmp) = &self.pmp {
+            flag(
+                &join(path, "pmp.private_auction"),
+                pmp.private_auction,
+                &[0, 1],
+            )?;
+            for (i, deal) in pmp.deals.iter().flatten().enumerate() {
+                let path = format!("{path}.pmp.deals[{i}]");
+                required_str(&join(&path, "id"), &deal.id)?;
+                non_negative(&join(&path, "bidfloor"), deal.bidfloor)?;
+                if let Some(cur) = &deal.bidfloorcur {
+                    currency(&join(&path, "bidfloorcur"), cur)?;
+                }
+                flag(&join(&path, "guar"), deal.guar, &[0, 1])?;
+                durfloors(&path, deal.durfloors.as_deref())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SupplyChain {
+    fn validate_at(&self, path: &str) -> Check {
+        flag(&join(path, "complete"), Some(self.complete), &[0, 1])?;
+        required_str(&join(path, "ver"), &self.ver)?;
+        if self.nodes.is_empty() {
+            return Err(missing(&join(path, "nodes")));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            let path = format!("{path}.nodes[{i}]");
+            required_str(&join(&path, "asi"), &node.asi)?;
+            required_str(&join(&path, "sid"), &node.sid)?;
+        }
+        Ok(())
+    }
+}
+
+impl BidResponse {
+    /// Checks the response against the `OpenRTB` 2.6 required-field rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violation found.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        required_str("id", &self.id)?;
+        if let Some(cur) = &self.cur {
+            currency("cur", cur)?;
+        }
+
+        for (i, seatbid) in self.seatbid.iter().flatten().enumerate() {
+            let path = format!("seatbid[{i}]");
+            if seatbid.bid.is_empty() {
+                return Err(missing(&join(&path, "bid")));
+            }
+            flag(&join(&path, "group"), seatbid.group, &[0, 1])?;
+            for (j, bid) in seatbid.bid.iter().enumerate() {
+                bid.validate_at(&format!("{path}.bid[{j}]"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Bid {
+    fn validate_at(&self, path: &str) -> Check {
+        required_str(&join(path, "id"), &self.id)?;
+        required_str(&join(path, "impid"), &self.impid)?;
+        non_negative(&join(path, "price"), Some(self.price))?;
+        flag(&join(path, "mtype"), self.mtype, &[1, 2, 3, 4])?;
+        Ok(())
+    }
+}
+
+/// Shared rules of `Video` and `Audio` objects.
+fn media(
+    path: &str,
+    mimes: &[String],
+    minduration: Option<i64>,
+    maxduration: Option<i64>,
+    rqddurs: Option<&[i64]>,
+) -> Check {
+    if mimes.is_empty() {
+        return Err(missing(&join(path, "mimes")));
+    }
+    if rqddurs.is_some() {
+        if minduration.is_some() {
+            return Err(exclusive(path, ("rqddurs", "minduration")));
+        }
+        if maxduration.is_some() {
+            return Err(exclusive(path, ("rqddurs", "maxduration")));
+        }
+    }
+    if let (Some(min), Some(max)) = (minduration, maxduration) {
+        if min > max {
+            return Err(invalid(
+                &join(path, "minduration"),
+                "must not exceed maxduration",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn durfloors(path: &str, durfloors: Option<&[DurFloors]>) -> Check {
+    for (i, floor) in durfloors.into_iter().flatten().enumerate() {
+        let path = format!("{path}.durfloors[{i}]");
+        non_negative(&join(&path, "bidfloor"), floor.bidfloor)?;
+        if let (Some(min), Some(max)) = (floor.mindur, floor.maxdur) {
+            if min > max {
+                return Err(invalid(&join(&path, "mindur"), "must not exceed maxdur"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn required_str(path: &str, value: &str) -> Check {
+    if value.is_empty() {
+        return Err(missing(path));
+    }
+    Ok(())
+}
+
+fn exclusive_options<T>(
+    path: &str,
+    a: (&'static str, &Option<T>),
+    b: (&'static str, &Option<T>),
+) -> Check {
+    if a.1.is_some() && b.1.is_some() {
+        return Err(exclusive(path, (a.0, b.0)));
+    }
+    Ok(())
+}
+
+fn flag(path: &str, value: Option<i64>, allowed: &[i64]) -> Check {
+    match value {
+        Some(value) if !allowed.contains(&value) => {
+            Err(invalid(path, &format!("{value} is not one of {allowed:?}")))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn non_negative(path: &str, value: Option<Price>) -> Check {
+    match value {
+        Some(value) if !value.to_f64().is_finite() || value < Price::default() => {
+            Err(invalid(path, "must be a non-negative number"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// ISO-4217 alpha codes are three upper case letters. Whether the code is
+/// in use is left to [`CurrencyCode`](super::types::CurrencyCode), since
+/// the list changes over time.
+fn currency(path: &str, code: &str) -> Check {
+    if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(invalid(path, "must be an ISO-4217 alpha code"));
+    }
+    Ok(())
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+fn missing(path: &str) -> ValidationError {
+    ValidationError::Missing {
+        path: path.to_string(),
+    }
+}
+
+fn exclusive(path: &str, fields: (&'static str, &'static str)) -> ValidationError {
+    ValidationError::MutuallyExclusive {
+        path: path.to_string(),
+        fields,
+    }
+}
+
+fn invalid(path: &str, reason: &str) -> ValidationError {
+    ValidationError::Invalid {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
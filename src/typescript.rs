@@ -0,0 +1,187 @@
+//! # TypeScript
+//!
+//! TypeScript type definitions (`.d.ts`) for the `OpenRTB` types, enabled
+//! with the `typescript` feature.
+//!
+//! Definitions are generated from the JSON Schema of the Rust structures, so
+//! they follow the same wire names (`native`, `ref`, `type`), optionality and
+//! deprecations as the serde implementation.
+//!
+//! ```
+//! use iab::typescript;
+//!
+//! let definitions = typescript::definitions();
+//!
+//! assert!(definitions.contains("export interface BidRequest {"));
+//! assert!(definitions.contains("  imp: Imp[];"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::json_schema::schema_for;
+use crate::openrtb2::{BidRequest, BidResponse};
+
+/// Prefix of references to schema definitions.
+const DEFS_PREFIX: &str = "#/$defs/";
+
+/// Generates the TypeScript interfaces for `BidRequest`, `BidResponse` and every nested object.
+#[must_use]
+pub fn definitions() -> String {
+    let mut interfaces = BTreeMap::new();
+
+    for schema in [
+        schema_for::<BidRequest>().to_value(),
+        schema_for::<BidResponse>().to_value(),
+    ] {
+        if let Some(Value::Object(defs)) = schema.get("$defs") {
+            for (name, def) in defs {
+                interfaces.insert(name.clone(), def.clone());
+            }
+        }
+        if let Some(Value::String(title)) = schema.get("title") {
+            interfaces.insert(title.clone(), schema.clone());
+        }
+    }
+
+    let mut out = String::from("// Generated from the iab crate. Do not edit by hand.\n");
+    for (name, schema) in &interfaces {
+        out.push('\n');
+        write_interface(&mut out, name, schema);
+    }
+    out
+}
+
+/// Writes the TypeScript interfaces to a `.d.ts` file.
+///
+/// # Errors
+///
+/// Fails if the file cannot be written.
+pub fn write_definitions(path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, definitions())
+}
+
+/// Writes a single exported interface for an object schema.
+fn write_interface(out: &mut String, name: &str, schema: &Value) {
+    write_doc(out, "", schema);
+    let _ = writeln!(out, "export interface {name} {{");
+
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        for (field, property) in properties {
+            write_doc(out, "  ", property);
+            let optional = if required.contains(&field.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            let _ = writeln!(out, "  {field}{optional}: {};", type_of(property));
+        }
+    }
+
+    out.push_str("}\n");
+}
+
+/// Writes the description and deprecation of a schema as a `JSDoc` comment.
+fn write_doc(out: &mut String, indent: &str, schema: &Value) {
+    let description = schema.get("description").and_then(Value::as_str);
+    let deprecated = schema.get("deprecated").and_then(Value::as_bool) == Some(true);
+
+    match (description, deprecated) {
+        (None, false) => {}
+        (Some(description), false) => {
+            let _ = writeln!(out, "{indent}/** {} */", escape_doc(description));
+        }
+        (description, true) => {
+            let _ = writeln!(out, "{indent}/**");
+            if let Some(description) = description {
+                let _ = writeln!(out, "{indent} * {}", escape_doc(description));
+            }
+            let _ = writeln!(out, "{indent} * @deprecated");
+            let _ = writeln!(out, "{indent} */");
+        }
+    }
+}
+
+/// Prevents descriptions from closing the surrounding comment.
+fn escape_doc(description: &str) -> String {
+    description.replace("*/", "*\\/").replace('\n', " ")
+}
+
+/// Maps a property schema to a TypeScript type. Nullability is expressed by
+/// the property being optional, so `null` is dropped from unions.
+fn type_of(schema: &Value) -> String {
+    let Some(schema) = schema.as_object() else {
+        return "unknown".to_string();
+    };
+
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        return reference
+            .strip_prefix(DEFS_PREFIX)
+            .unwrap_or(reference)
+            .to_string();
+    }
+
+    if let Some(Value::Array(variants)) = schema.get("anyOf").or_else(|| schema.get("oneOf")) {
+        let types: Vec<String> = variants
+            .iter()
+            .filter(|v| v.get("type").and_then(Value::as_str) != Some("null"))
+            .map(type_of)
+            .collect();
+        return union(&types);
+    }
+
+    match schema.get("type") {
+        Some(Value::String(primitive)) => primitive_type(primitive, schema),
+        Some(Value::Array(primitives)) => {
+            let types: Vec<String> = primitives
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|p| *p != "null")
+                .map(|p| primitive_type(p, schema))
+                .collect();
+            union(&types)
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Maps a JSON Schema primitive type to a TypeScript type.
+fn primitive_type(primitive: &str, schema: &Map<String, Value>) -> String {
+    match primitive {
+        "integer" | "number" => "number".to_string(),
+        "string" => "string".to_string(),
+        "boolean" => "boolean".to_string(),
+        "array" => {
+            let items = schema
+                .get("items")
+                .map_or_else(|| "unknown".to_string(), type_of);
+            if items.contains(' ') {
+                format!("({items})[]")
+            } else {
+                format!("{items}[]")
+            }
+        }
+        "object" => "Record<string, unknown>".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Joins alternative types, falling back to `unknown` when there are none.
+fn union(types: &[String]) -> String {
+    if types.is_empty() {
+        "unknown".to_string()
+    } else {
+        types.join(" | ")
+    }
+}
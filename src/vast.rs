@@ -0,0 +1,925 @@
+//! # VAST
+//!
+//! The IAB Video Ad Serving Template (VAST) documents that video and audio
+//! bids carry in `Bid.adm`, from VAST 2.0 to 4.3.
+//!
+//! [`Vast::parse`] reads a document into typed ads, inline or wrappers,
+//! with their creatives, media files, trackers, companions, verifications
+//! and extensions. Markup is read leniently, since hand-written VAST with
+//! stray spaces in its tags is common in bid responses. [`Vast::to_xml`]
+//! writes the document back; elements without a field here, such as
+//! `NonLinearAds` or `Icons`, are left out.
+//!
+//! [`Trackers`] adds the trackers of an exchange to the ads of VAST markup,
+//! keeping the rest of the markup as is, or wraps a creative served by the
+//! win notice in a new document. [`VastMacros`] fills the IAB macros of
+//! tracking URLs, such as `[IFA]` or `[TIMESTAMP]`, from a bid request.
+//!
+//! ```
+//! use std::time::Duration;
+//! use iab::openrtb2::codes::Protocol;
+//! use iab::vast::*;
+//!
+//! let vast = Vast::parse(
+//!     r#"<VAST version="4.2">
+//!       <Ad id="1">
+//!         <InLine>
+//!           <AdSystem>DSP</AdSystem>
+//!           <AdTitle>Spring sale</AdTitle>
+//!           <Impression><![CDATA[https://dsp.com/imp]]></Impression>
+//!           <Creatives>
+//!             <Creative>
+//!               <Linear>
+//!                 <Duration>00:00:15</Duration>
+//!                 <MediaFiles>
+//!                   <MediaFile delivery="progressive" type="video/mp4" width="1280" height="720">
+//!                     <![CDATA[https://cdn.dsp.com/ad.mp4]]>
+//!                   </MediaFile>
+//!                 </MediaFiles>
+//!               </Linear>
+//!             </Creative>
+//!           </Creatives>
+//!         </InLine>
+//!       </Ad>
+//!     </VAST>"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(vast.protocol(), Some(Protocol::Vast42));
+//! assert_eq!(vast.duration(), Some(Duration::from_secs(15)));
+//! assert_eq!(vast.media_files().next().unwrap().width, Some(1280));
+//! assert_eq!(Vast::parse(&vast.to_xml()).unwrap(), vast);
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::openrtb2::codes::Protocol;
+use crate::xml::{self, Element, XmlError};
+
+mod macros;
+mod trackers;
+
+pub use macros::VastMacros;
+pub use trackers::{wrapper_protocol, Trackers, WrapError};
+
+/// XML declaration written before documents.
+pub(crate) const PROLOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// Errors raised when parsing a VAST document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VastError {
+    /// The markup is not well-formed XML.
+    InvalidXml {
+        /// Byte offset of the error in the markup.
+        position: usize,
+        message: &'static str,
+    },
+    /// The root element is not `VAST`.
+    NotVast { root: String },
+    /// A required element is missing, such as `InLine` or `Wrapper` in an
+    /// `Ad`.
+    MissingElement {
+        parent: &'static str,
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for VastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VastError::InvalidXml { position, message } => {
+                write!(f, "invalid XML: {message} at byte {position}")
+            }
+            VastError::NotVast { root } => write!(f, "root element {root} is not VAST"),
+            VastError::MissingElement { parent, name } => {
+                write!(f, "missing {name} element in {parent}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VastError {}
+
+impl From<XmlError> for VastError {
+    fn from(error: XmlError) -> Self {
+        VastError::InvalidXml {
+            position: error.position,
+            message: error.message,
+        }
+    }
+}
+
+/// A VAST document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vast {
+    /// Version of the document, as in `4.2`.
+    pub version: String,
+    /// The ads, empty for a response without ads.
+    pub ads: Vec<Ad>,
+    /// Error URLs of a response without ads.
+    pub errors: Vec<String>,
+}
+
+/// An ad of a VAST document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ad {
+    pub id: Option<String>,
+    /// Position of the ad in a pod, absent for a standalone ad.
+    pub sequence: Option<u32>,
+    /// Whether the ad is conditional, such as a VPAID ad that may not play
+    /// (`conditionalAd`, VAST 4).
+    pub conditional_ad: Option<bool>,
+    /// Type of the ad, `video`, `audio` or `hybrid` (`adType`, VAST 4.1).
+    pub ad_type: Option<String>,
+    pub kind: AdKind,
+}
+
+/// Whether an ad holds its creatives or points to another VAST document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdKind {
+    InLine(InLine),
+    Wrapper(Wrapper),
+}
+
+/// An ad with its creatives.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InLine {
+    pub ad_system: AdSystem,
+    pub ad_title: String,
+    /// Identifier of the ad across the parties serving it (VAST 4.1).
+    pub ad_serving_id: Option<String>,
+    pub description: Option<String>,
+    pub advertiser: Option<String>,
+    pub impressions: Vec<Impression>,
+    /// Error URLs, called with `[ERRORCODE]` substituted on failure.
+    pub errors: Vec<String>,
+    pub creatives: Vec<Creative>,
+    pub ad_verifications: Vec<Verification>,
+    pub extensions: Vec<Extension>,
+}
+
+/// An ad pointing to a VAST document served elsewhere. Its trackers are
+/// fired along with those of the wrapped ad.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Wrapper {
+    pub ad_system: AdSystem,
+    /// URL of the wrapped VAST document.
+    pub vast_ad_tag_uri: String,
+    /// Whether the wrapped document may itself be a wrapper
+    /// (`followAdditionalWrappers`).
+    pub follow_additional_wrappers: Option<bool>,
+    /// Whether the wrapped document may hold a pod (`allowMultipleAds`).
+    pub allow_multiple_ads: Option<bool>,
+    /// Whether to fall back to another ad if the wrapped document has none
+    /// (`fallbackOnNoAd`).
+    pub fallback_on_no_ad: Option<bool>,
+    pub impressions: Vec<Impression>,
+    pub errors: Vec<String>,
+    pub creatives: Vec<Creative>,
+    pub ad_verifications: Vec<Verification>,
+    pub extensions: Vec<Extension>,
+}
+
+/// The ad server that returned the ad.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AdSystem {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// An impression tracking URL.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Impression {
+    pub id: Option<String>,
+    pub url: String,
+}
+
+/// A creative of an ad: a linear video or audio, and its companions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Creative {
+    pub id: Option<String>,
+    pub sequence: Option<u32>,
+    /// ID of the ad the creative belongs to, in the ad server (`adId`).
+    pub ad_id: Option<String>,
+    /// API framework the creative needs, such as `VPAID` (`apiFramework`).
+    pub api_framework: Option<String>,
+    pub universal_ad_id: Option<UniversalAdId>,
+    pub linear: Option<Linear>,
+    pub companions: Vec<Companion>,
+}
+
+/// Identifier of a creative in a registry, such as Ad-ID (VAST 4).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UniversalAdId {
+    /// The registry, such as `ad-id.org` (`idRegistry`).
+    pub registry: String,
+    pub value: String,
+}
+
+/// A linear creative, played before, between or after the content. In a
+/// wrapper, it only holds trackers.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Linear {
+    /// Time or percentage after which the ad can be skipped (`skipoffset`).
+    pub skip_offset: Option<String>,
+    pub duration: Option<Duration>,
+    /// Data passed to an interactive creative.
+    pub ad_parameters: Option<String>,
+    pub media_files: Vec<MediaFile>,
+    pub tracking_events: Vec<Tracking>,
+    pub video_clicks: Option<VideoClicks>,
+}
+
+/// A file of a linear creative, in one encoding.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MediaFile {
+    pub url: String,
+    pub id: Option<String>,
+    /// `progressive` or `streaming`.
+    pub delivery: String,
+    /// MIME type (`type`), such as `video/mp4`.
+    pub mime_type: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Bitrate in Kbps, for progressive files.
+    pub bitrate: Option<u32>,
+    pub min_bitrate: Option<u32>,
+    pub max_bitrate: Option<u32>,
+    pub codec: Option<String>,
+    pub scalable: Option<bool>,
+    pub maintain_aspect_ratio: Option<bool>,
+    pub api_framework: Option<String>,
+}
+
+/// A tracking URL for an event, such as `start` or `firstQuartile`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Tracking {
+    pub event: String,
+    /// Time or percentage of the `progress` event.
+    pub offset: Option<String>,
+    pub url: String,
+}
+
+/// Clicks on a linear creative.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VideoClicks {
+    /// The landing page.
+    pub click_through: Option<String>,
+    pub click_tracking: Vec<String>,
+    pub custom_click: Vec<String>,
+}
+
+/// A companion banner shown with a linear creative.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Companion {
+    pub id: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// ID of the slot of the page the companion is for (`adSlotID`).
+    pub ad_slot_id: Option<String>,
+    pub api_framework: Option<String>,
+    pub static_resource: Option<StaticResource>,
+    /// URL of a page to show in an iframe.
+    pub iframe_resource: Option<String>,
+    /// HTML to insert in the page.
+    pub html_resource: Option<String>,
+    pub alt_text: Option<String>,
+    pub click_through: Option<String>,
+    pub click_tracking: Vec<String>,
+    pub tracking_events: Vec<Tracking>,
+}
+
+/// An image or other file of a companion.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StaticResource {
+    /// MIME type of the file (`creativeType`).
+    pub creative_type: String,
+    pub url: String,
+}
+
+/// A verification script to run with the ad, such as Open Measurement.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Verification {
+    pub vendor: Option<String>,
+    pub javascript_resources: Vec<JavaScriptResource>,
+    /// Data passed to the script.
+    pub verification_parameters: Option<String>,
+    /// Trackers, such as `verificationNotExecuted`.
+    pub tracking_events: Vec<Tracking>,
+}
+
+/// URL of a verification script.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JavaScriptResource {
+    /// The API framework, `omid` for Open Measurement.
+    pub api_framework: Option<String>,
+    /// Whether the script can run without a browser (`browserOptional`).
+    pub browser_optional: Option<bool>,
+    pub url: String,
+}
+
+/// A custom extension of an ad, kept as XML.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Extension {
+    pub extension_type: Option<String>,
+    /// The content of the `Extension` element.
+    pub xml: String,
+}
+
+impl Vast {
+    /// Parses a VAST document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the markup is not well-formed XML, or not a valid VAST
+    /// document.
+    pub fn parse(markup: &str) -> Result<Vast, VastError> {
+        Vast::read(&xml::parse(markup)?)
+    }
+
+    /// Writes the document as XML.
+    #[must_use]
+    pub fn to_xml(&self) -> String {
+        let mut out = String::from(PROLOG);
+        self.element().write(&mut out);
+        out
+    }
+
+    /// Reads a document from its root element.
+    pub(crate) fn read(root: &Element) -> Result<Vast, VastError> {
+        if root.name != "VAST" {
+            return Err(VastError::NotVast {
+                root: root.name.clone(),
+            });
+        }
+        Ok(Vast {
+            version: root.attr("version").unwrap_or_default().to_string(),
+            ads: root
+                .children("Ad")
+                .map(Ad::read)
+                .collect::<Result<_, _>>()?,
+            errors: texts(root, "Error"),
+        })
+    }
+
+    /// Returns the root element of the document.
+    pub(crate) fn element(&self) -> Element {
+        Element::new("VAST")
+            .with_attr("version", &self.version)
+            .with_children(self.ads.iter().map(Ad::element))
+            .with_children(text_elements("Error", &self.errors))
+    }
+
+    /// Returns the protocol of the document (`Bid.protocol`), a wrapper one
+    /// if any ad is a wrapper, or `None` for an unknown version.
+    #[must_use]
+    pub fn protocol(&self) -> Option<Protocol> {
+        let wrapper = self
+            .ads
+            .iter()
+            .any(|ad| matches!(ad.kind, AdKind::Wrapper(_)));
+        Protocol::vast(self.version.trim(), wrapper)
+    }
+
+    /// Returns the total duration of the linear creatives of the ads, to
+    /// compare with `Bid.dur`, or `None` if no ad has one.
+    pub fn duration(&self) -> Option<Duration> {
+        self.ads
+            .iter()
+            .filter_map(Ad::duration)
+            .reduce(Duration::saturating_add)
+    }
+
+    /// Returns the media files of every linear creative.
+    pub fn media_files(&self) -> impl Iterator<Item = &MediaFile> {
+        self.ads
+            .iter()
+            .flat_map(Ad::creatives)
+            .filter_map(|creative| creative.linear.as_ref())
+            .flat_map(|linear| &linear.media_files)
+    }
+}
+
+impl Ad {
+    /// Returns the creatives of the ad.
+    #[must_use]
+    pub fn creatives(&self) -> &[Creative] {
+        match &self.kind {
+            AdKind::InLine(inline) => &inline.creatives,
+            AdKind::Wrapper(wrapper) => &wrapper.creatives,
+        }
+    }
+
+    /// Returns the duration of the first linear creative with one.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.creatives()
+            .iter()
+            .find_map(|creative| creative.linear.as_ref()?.duration)
+    }
+
+    fn read(element: &Element) -> Result<Ad, VastError> {
+        let kind = if let Some(inline) = element.child("InLine") {
+            AdKind::InLine(InLine::read(inline))
+        } else if let Some(wrapper) = element.child("Wrapper") {
+            AdKind::Wrapper(Wrapper::read(wrapper)?)
+        } else {
+            return Err(VastError::MissingElement {
+                parent: "Ad",
+                name: "InLine",
+            });
+        };
+        Ok(Ad {
+            id: attr(element, "id"),
+            sequence: number(element, "sequence"),
+            conditional_ad: flag(element, "conditionalAd"),
+            ad_type: attr(element, "adType"),
+            kind,
+        })
+    }
+
+    fn element(&self) -> Element {
+        let kind = match &self.kind {
+            AdKind::InLine(inline) => inline.element(),
+            AdKind::Wrapper(wrapper) => wrapper.element(),
+        };
+        Element::new("Ad")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_optional_attr("sequence", self.sequence)
+            .with_optional_attr("conditionalAd", self.conditional_ad)
+            .with_optional_attr("adType", self.ad_type.as_ref())
+            .with_child(kind)
+    }
+}
+
+impl InLine {
+    fn read(element: &Element) -> InLine {
+        InLine {
+            ad_system: AdSystem::read(element),
+            ad_title: element.child_text("AdTitle").unwrap_or_default(),
+            ad_serving_id: element.child_text("AdServingId"),
+            description: element.child_text("Description"),
+            advertiser: element.child_text("Advertiser"),
+            impressions: Impression::read_all(element),
+            errors: texts(element, "Error"),
+            creatives: Creative::read_all(element),
+            ad_verifications: Verification::read_all(element),
+            extensions: Extension::read_all(element),
+        }
+    }
+
+    fn element(&self) -> Element {
+        Element::new("InLine")
+            .with_child(self.ad_system.element())
+            .with_child(Element::new("AdTitle").with_text(&self.ad_title))
+            .with_children(optional_text("AdServingId", self.ad_serving_id.as_deref()))
+            .with_children(optional_text("Description", self.description.as_deref()))
+            .with_children(optional_text("Advertiser", self.advertiser.as_deref()))
+            .with_children(text_elements("Error", &self.errors))
+            .with_children(self.impressions.iter().map(Impression::element))
+            .with_children(list(
+                "AdVerifications",
+                &self.ad_verifications,
+                Verification::element,
+            ))
+            .with_children(list("Creatives", &self.creatives, Creative::element))
+            .with_children(list("Extensions", &self.extensions, Extension::element))
+    }
+}
+
+impl Wrapper {
+    fn read(element: &Element) -> Result<Wrapper, VastError> {
+        let vast_ad_tag_uri =
+            element
+                .child_text("VASTAdTagURI")
+                .ok_or(VastError::MissingElement {
+                    parent: "Wrapper",
+                    name: "VASTAdTagURI",
+                })?;
+        Ok(Wrapper {
+            ad_system: AdSystem::read(element),
+            vast_ad_tag_uri,
+            follow_additional_wrappers: flag(element, "followAdditionalWrappers"),
+            allow_multiple_ads: flag(element, "allowMultipleAds"),
+            fallback_on_no_ad: flag(element, "fallbackOnNoAd"),
+            impressions: Impression::read_all(element),
+            errors: texts(element, "Error"),
+            creatives: Creative::read_all(element),
+            ad_verifications: Verification::read_all(element),
+            extensions: Extension::read_all(element),
+        })
+    }
+
+    fn element(&self) -> Element {
+        Element::new("Wrapper")
+            .with_optional_attr("followAdditionalWrappers", self.follow_additional_wrappers)
+            .with_optional_attr("allowMultipleAds", self.allow_multiple_ads)
+            .with_optional_attr("fallbackOnNoAd", self.fallback_on_no_ad)
+            .with_child(self.ad_system.element())
+            .with_child(Element::new("VASTAdTagURI").with_text(&self.vast_ad_tag_uri))
+            .with_children(text_elements("Error", &self.errors))
+            .with_children(self.impressions.iter().map(Impression::element))
+            .with_children(list(
+                "AdVerifications",
+                &self.ad_verifications,
+                Verification::element,
+            ))
+            .with_children(list("Creatives", &self.creatives, Creative::element))
+            .with_children(list("Extensions", &self.extensions, Extension::element))
+    }
+}
+
+impl AdSystem {
+    fn read(parent: &Element) -> AdSystem {
+        let Some(element) = parent.child("AdSystem") else {
+            return AdSystem::default();
+        };
+        AdSystem {
+            name: element.text(),
+            version: attr(element, "version"),
+        }
+    }
+
+    fn element(&self) -> Element {
+        Element::new("AdSystem")
+            .with_optional_attr("version", self.version.as_ref())
+            .with_text(&self.name)
+    }
+}
+
+impl Impression {
+    fn read_all(parent: &Element) -> Vec<Impression> {
+        parent
+            .children("Impression")
+            .map(|element| Impression {
+                id: attr(element, "id"),
+                url: element.text(),
+            })
+            .filter(|impression| !impression.url.is_empty())
+            .collect()
+    }
+
+    fn element(&self) -> Element {
+        Element::new("Impression")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_text(&self.url)
+    }
+}
+
+impl Creative {
+    fn read_all(parent: &Element) -> Vec<Creative> {
+        let Some(creatives) = parent.child("Creatives") else {
+            return Vec::new();
+        };
+        creatives
+            .children("Creative")
+            .map(|element| Creative {
+                id: attr(element, "id"),
+                sequence: number(element, "sequence"),
+                ad_id: attr(element, "adId").or_else(|| attr(element, "AdID")),
+                api_framework: attr(element, "apiFramework"),
+                universal_ad_id: element.child("UniversalAdId").map(|id| UniversalAdId {
+                    registry: attr(id, "idRegistry").unwrap_or_default(),
+                    // VAST 4.0 has the value in an attribute.
+                    value: attr(id, "idValue").unwrap_or_else(|| id.text()),
+                }),
+                linear: element.child("Linear").map(Linear::read),
+                companions: element
+                    .child("CompanionAds")
+                    .map(|ads| ads.children("Companion").map(Companion::read).collect())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn element(&self) -> Element {
+        let universal_ad_id = self.universal_ad_id.as_ref().map(|id| {
+            Element::new("UniversalAdId")
+                .with_attr("idRegistry", &id.registry)
+                .with_text(&id.value)
+        });
+        Element::new("Creative")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_optional_attr("sequence", self.sequence)
+            .with_optional_attr("adId", self.ad_id.as_ref())
+            .with_optional_attr("apiFramework", self.api_framework.as_ref())
+            .with_children(universal_ad_id)
+            .with_children(self.linear.as_ref().map(Linear::element))
+            .with_children(list("CompanionAds", &self.companions, Companion::element))
+    }
+}
+
+impl Linear {
+    fn read(element: &Element) -> Linear {
+        Linear {
+            skip_offset: attr(element, "skipoffset"),
+            duration: element
+                .child_text("Duration")
+                .and_then(|text| parse_duration(&text)),
+            ad_parameters: element.child_text("AdParameters"),
+            media_files: element
+                .child("MediaFiles")
+                .map(|files| files.children("MediaFile").map(MediaFile::read).collect())
+                .unwrap_or_default(),
+            tracking_events: Tracking::read_all(element),
+            video_clicks: element.child("VideoClicks").map(|clicks| VideoClicks {
+                click_through: clicks.child_text("ClickThrough"),
+                click_tracking: texts(clicks, "ClickTracking"),
+                custom_click: texts(clicks, "CustomClick"),
+            }),
+        }
+    }
+
+    fn element(&self) -> Element {
+        let duration = self
+            .duration
+            .map(|duration| Element::new("Duration").with_text(format_duration(duration)));
+        let video_clicks = self.video_clicks.as_ref().map(|clicks| {
+            Element::new("VideoClicks")
+                .with_children(optional_text(
+                    "ClickThrough",
+                    clicks.click_through.as_deref(),
+                ))
+                .with_children(text_elements("ClickTracking", &clicks.click_tracking))
+                .with_children(text_elements("CustomClick", &clicks.custom_click))
+        });
+        Element::new("Linear")
+            .with_optional_attr("skipoffset", self.skip_offset.as_ref())
+            .with_children(duration)
+            .with_children(optional_text("AdParameters", self.ad_parameters.as_deref()))
+            .with_children(list("MediaFiles", &self.media_files, MediaFile::element))
+            .with_children(list(
+                "TrackingEvents",
+                &self.tracking_events,
+                Tracking::element,
+            ))
+            .with_children(video_clicks)
+    }
+}
+
+impl MediaFile {
+    fn read(element: &Element) -> MediaFile {
+        MediaFile {
+            url: element.text(),
+            id: attr(element, "id"),
+            delivery: attr(element, "delivery").unwrap_or_default(),
+            mime_type: attr(element, "type").unwrap_or_default(),
+            width: number(element, "width"),
+            height: number(element, "height"),
+            bitrate: number(element, "bitrate"),
+            min_bitrate: number(element, "minBitrate"),
+            max_bitrate: number(element, "maxBitrate"),
+            codec: attr(element, "codec"),
+            scalable: flag(element, "scalable"),
+            maintain_aspect_ratio: flag(element, "maintainAspectRatio"),
+            api_framework: attr(element, "apiFramework"),
+        }
+    }
+
+    fn element(&self) -> Element {
+        Element::new("MediaFile")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_attr("delivery", &self.delivery)
+            .with_attr("type", &self.mime_type)
+            .with_optional_attr("width", self.width)
+            .with_optional_attr("height", self.height)
+            .with_optional_attr("bitrate", self.bitrate)
+            .with_optional_attr("minBitrate", self.min_bitrate)
+            .with_optional_attr("maxBitrate", self.max_bitrate)
+            .with_optional_attr("codec", self.codec.as_ref())
+            .with_optional_attr("scalable", self.scalable)
+            .with_optional_attr("maintainAspectRatio", self.maintain_aspect_ratio)
+            .with_optional_attr("apiFramework", self.api_framework.as_ref())
+            .with_text(&self.url)
+    }
+}
+
+impl Tracking {
+    fn read_all(parent: &Element) -> Vec<Tracking> {
+        let Some(events) = parent.child("TrackingEvents") else {
+            return Vec::new();
+        };
+        events
+            .children("Tracking")
+            .map(|element| Tracking {
+                event: attr(element, "event").unwrap_or_default(),
+                offset: attr(element, "offset"),
+                url: element.text(),
+            })
+            .filter(|tracking| !tracking.url.is_empty())
+            .collect()
+    }
+
+    fn element(&self) -> Element {
+        Element::new("Tracking")
+            .with_attr("event", &self.event)
+            .with_optional_attr("offset", self.offset.as_ref())
+            .with_text(&self.url)
+    }
+}
+
+impl Companion {
+    fn read(element: &Element) -> Companion {
+        Companion {
+            id: attr(element, "id"),
+            width: number(element, "width"),
+            height: number(element, "height"),
+            ad_slot_id: attr(element, "adSlotID").or_else(|| attr(element, "adSlotId")),
+            api_framework: attr(element, "apiFramework"),
+            static_resource: element
+                .child("StaticResource")
+                .map(|resource| StaticResource {
+                    creative_type: attr(resource, "creativeType").unwrap_or_default(),
+                    url: resource.text(),
+                }),
+            iframe_resource: element.child_text("IFrameResource"),
+            html_resource: element.child_text("HTMLResource"),
+            alt_text: element.child_text("AltText"),
+            click_through: element.child_text("CompanionClickThrough"),
+            click_tracking: texts(element, "CompanionClickTracking"),
+            tracking_events: Tracking::read_all(element),
+        }
+    }
+
+    fn element(&self) -> Element {
+        let static_resource = self.static_resource.as_ref().map(|resource| {
+            Element::new("StaticResource")
+                .with_attr("creativeType", &resource.creative_type)
+                .with_text(&resource.url)
+        });
+        Element::new("Companion")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_optional_attr("width", self.width)
+            .with_optional_attr("height", self.height)
+            .with_optional_attr("adSlotID", self.ad_slot_id.as_ref())
+            .with_optional_attr("apiFramework", self.api_framework.as_ref())
+            .with_children(static_resource)
+            .with_children(optional_text(
+                "IFrameResource",
+                self.iframe_resource.as_deref(),
+            ))
+            .with_children(optional_text("HTMLResource", self.html_resource.as_deref()))
+            .with_children(optional_text("AltText", self.alt_text.as_deref()))
+            .with_children(optional_text(
+                "CompanionClickThrough",
+                self.click_through.as_deref(),
+            ))
+            .with_children(text_elements(
+                "CompanionClickTracking",
+                &self.click_tracking,
+            ))
+            .with_children(list(
+                "TrackingEvents",
+                &self.tracking_events,
+                Tracking::element,
+            ))
+    }
+}
+
+impl Verification {
+    fn read_all(parent: &Element) -> Vec<Verification> {
+        let Some(verifications) = parent.child("AdVerifications") else {
+            return Vec::new();
+        };
+        verifications
+            .children("Verification")
+            .map(|element| Verification {
+                vendor: attr(element, "vendor"),
+                javascript_resources: element
+                    .children("JavaScriptResource")
+                    .map(|resource| JavaScriptResource {
+                        api_framework: attr(resource, "apiFramework"),
+                        browser_optional: flag(resource, "browserOptional"),
+                        url: resource.text(),
+                    })
+                    .collect(),
+                verification_parameters: element.child_text("VerificationParameters"),
+                tracking_events: Tracking::read_all(element),
+            })
+            .collect()
+    }
+
+    fn element(&self) -> Element {
+        let resources = self.javascript_resources.iter().map(|resource| {
+            Element::new("JavaScriptResource")
+                .with_optional_attr("apiFramework", resource.api_framework.as_ref())
+                .with_optional_attr("browserOptional", resource.browser_optional)
+                .with_text(&resource.url)
+        });
+        Element::new("Verification")
+            .with_optional_attr("vendor", self.vendor.as_ref())
+            .with_children(resources)
+            .with_children(optional_text(
+                "VerificationParameters",
+                self.verification_parameters.as_deref(),
+            ))
+            .with_children(list(
+                "TrackingEvents",
+                &self.tracking_events,
+                Tracking::element,
+            ))
+    }
+}
+
+impl Extension {
+    fn read_all(parent: &Element) -> Vec<Extension> {
+        let Some(extensions) = parent.child("Extensions") else {
+            return Vec::new();
+        };
+        extensions
+            .children("Extension")
+            .map(|element| {
+                let mut xml = String::new();
+                element.write_content(&mut xml);
+                Extension {
+                    extension_type: attr(element, "type"),
+                    xml: xml.trim().to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn element(&self) -> Element {
+        Element::new("Extension")
+            .with_optional_attr("type", self.extension_type.as_ref())
+            .with_raw(&self.xml)
+    }
+}
+
+/// Parses a duration written `HH:MM:SS` or `HH:MM:SS.mmm`, or returns `None`
+/// if it is invalid or too long for a `Duration`.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration> {
+    let (time, millis) = match text.split_once('.') {
+        Some((time, millis)) => (time, millis),
+        None => (text, ""),
+    };
+    let mut parts = time.split(':').map(|part| part.trim().parse::<u64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    let millis = match millis {
+        "" => 0,
+        millis => format!("{millis:0<3}").get(..3)?.parse().ok()?,
+    };
+    let seconds = hours
+        .checked_mul(3600)?
+        .checked_add(minutes * 60 + seconds)?;
+    Duration::from_secs(seconds).checked_add(Duration::from_millis(millis))
+}
+
+/// Writes a duration as `HH:MM:SS`, with milliseconds if it has any.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match duration.subsec_millis() {
+        0 => time,
+        millis => format!("{time}.{millis:03}"),
+    }
+}
+
+pub(crate) fn attr(element: &Element, name: &str) -> Option<String> {
+    element.attr(name).map(|value| value.trim().to_string())
+}
+
+fn number(element: &Element, name: &str) -> Option<u32> {
+    element.attr(name)?.trim().parse().ok()
+}
+
+pub(crate) fn flag(element: &Element, name: &str) -> Option<bool> {
+    match element.attr(name)?.trim() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the non-empty texts of the child elements with a name.
+fn texts(parent: &Element, name: &str) -> Vec<String> {
+    parent
+        .children(name)
+        .map(Element::text)
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+fn text_elements<'a>(name: &'a str, texts: &'a [String]) -> impl Iterator<Item = Element> + 'a {
+    texts
+        .iter()
+        .map(move |text| Element::new(name).with_text(text))
+}
+
+fn optional_text(name: &str, text: Option<&str>) -> Option<Element> {
+    text.map(|text| Element::new(name).with_text(text))
+}
+
+/// Returns a container element for the items, or none if there are none.
+pub(crate) fn list<T>(name: &str, items: &[T], element: fn(&T) -> Element) -> Option<Element> {
+    (!items.is_empty()).then(|| Element::new(name).with_children(items.iter().map(element)))
+}
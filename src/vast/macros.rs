@@ -0,0 +1,232 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::encoding::percent_encode;
+use crate::openrtb2::{Bid, BidRequest};
+
+/// Value of an identifier macro that may not be shared, from VAST 4.1.
+const RESTRICTED: &str = "-2";
+
+/// Values of the IAB VAST 4.x macros, substituted in the tracking URLs of
+/// VAST markup, such as `[TIMESTAMP]` or `[IFA]`.
+///
+/// Values are percent-encoded, so they are safe in a URL and in XML.
+/// Macros without a value are left untouched, for the player to fill in, as
+/// are unknown macros. When the user limits ad tracking (`Device.lmt` or
+/// `Device.dnt`), the identifier macros `[IFA]`, `[DEVICEIP]` and
+/// `[LATLONG]` are replaced with `-2`, the value VAST uses for data that may
+/// not be shared.
+///
+/// ```
+/// use iab::openrtb2::*;
+/// use iab::vast::*;
+///
+/// let request = BidRequest {
+///     device: Some(Device {
+///         ifa: Some("ifa-1".to_string()),
+///         ua: Some("Mozilla/5.0 (CTV)".to_string()),
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// };
+///
+/// let macros = VastMacros::from_request(&request).error_code(303);
+/// assert_eq!(
+///     macros.substitute("https://dsp.com/e?c=[ERRORCODE]&ifa=[IFA]&ua=[DEVICEUA]&t=[TIMESTAMP]"),
+///     "https://dsp.com/e?c=303&ifa=ifa-1&ua=Mozilla%2F5.0%20%28CTV%29&t=[TIMESTAMP]"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VastMacros {
+    /// `[TIMESTAMP]`: time of the call, written in ISO 8601 with
+    /// milliseconds, in UTC.
+    pub timestamp: Option<SystemTime>,
+    /// `[CACHEBUSTING]`: a random number, written with 8 digits.
+    pub cache_busting: Option<u32>,
+    /// `[ERRORCODE]`: VAST error code, such as 303 for no ad.
+    pub error_code: Option<i64>,
+    /// `[CONTENTPLAYHEAD]`: position in the content, written `HH:MM:SS.mmm`.
+    pub content_playhead: Option<Duration>,
+    /// `[IFA]`: advertising ID of the device (`Device.ifa`).
+    pub ifa: Option<String>,
+    /// `[DEVICEUA]`: user agent of the device (`Device.ua`).
+    pub device_ua: Option<String>,
+    /// `[DEVICEIP]`: IP address of the device (`Device.ip` or `Device.ipv6`).
+    pub device_ip: Option<String>,
+    /// `[LATLONG]`: location of the device (`Device.geo`).
+    pub lat_long: Option<(f64, f64)>,
+    /// `[GDPRCONSENT]`: TCF consent string (`User.consent`).
+    pub gdpr_consent: Option<String>,
+    /// `[APPBUNDLE]`: bundle or store ID of the app (`App.bundle`).
+    pub app_bundle: Option<String>,
+    /// `[DOMAIN]`: domain of the site or app (`Site.domain` or `App.domain`).
+    pub domain: Option<String>,
+    /// `[PAGEURL]`: URL of the page (`Site.page`).
+    pub page_url: Option<String>,
+    /// `[LIMITADTRACKING]`: whether the user limits ad tracking. Identifier
+    /// macros are restricted when set.
+    pub limit_ad_tracking: Option<bool>,
+}
+
+impl VastMacros {
+    /// Creates the values known from a bid request.
+    #[must_use]
+    pub fn from_request(request: &BidRequest) -> Self {
+        let device = request.device.as_ref();
+        let site = request.site.as_ref();
+        let app = request.app.as_ref();
+        let limited = device.map(|device| device.lmt == Some(1) || device.dnt == Some(1));
+        VastMacros {
+            ifa: device.and_then(|device| device.ifa.clone()),
+            device_ua: device.and_then(|device| device.ua.clone()),
+            device_ip: device.and_then(|device| device.ip.clone().or(device.ipv6.clone())),
+            lat_long: device
+                .and_then(|device| device.geo.as_ref())
+                .and_then(|geo| Some((geo.lat?, geo.lon?))),
+            gdpr_consent: request.user.as_ref().and_then(|user| user.consent.clone()),
+            app_bundle: app.and_then(|app| app.bundle.clone()),
+            domain: site
+                .and_then(|site| site.domain.clone())
+                .or_else(|| app.and_then(|app| app.domain.clone())),
+            page_url: site.and_then(|site| site.page.clone()),
+            limit_ad_tracking: limited,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the time of the call, `[TIMESTAMP]`.
+    #[must_use]
+    pub fn timestamp(mut self, time: SystemTime) -> Self {
+        self.timestamp = Some(time);
+        self
+    }
+
+    /// Sets the random number of `[CACHEBUSTING]`.
+    #[must_use]
+    pub fn cache_busting(mut self, value: u32) -> Self {
+        self.cache_busting = Some(value);
+        self
+    }
+
+    /// Sets the VAST error code, `[ERRORCODE]`.
+    #[must_use]
+    pub fn error_code(mut self, code: i64) -> Self {
+        self.error_code = Some(code);
+        self
+    }
+
+    /// Sets the position in the content, `[CONTENTPLAYHEAD]`.
+    #[must_use]
+    pub fn content_playhead(mut self, position: Duration) -> Self {
+        self.content_playhead = Some(position);
+        self
+    }
+
+    /// Returns the text with every macro that has a value substituted.
+    #[must_use]
+    pub fn substitute(&self, text: &str) -> String {
+        let mut substituted = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('[') {
+            substituted.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let value = after
+                .find([']', '['])
+                .filter(|end| after[*end..].starts_with(']'))
+                .and_then(|end| Some((self.value(&after[..end])?, end)));
+            if let Some((value, end)) = value {
+                substituted.push_str(&value);
+                rest = &after[end + 1..];
+            } else {
+                substituted.push('[');
+                rest = after;
+            }
+        }
+        substituted.push_str(rest);
+        substituted
+    }
+
+    /// Substitutes the macros of the VAST markup of a bid (`Bid.adm`).
+    pub fn apply(&self, bid: &mut Bid) {
+        if let Some(adm) = &mut bid.adm {
+            *adm = self.substitute(adm);
+        }
+    }
+
+    /// Returns the encoded value of a macro, or `None` if the macro is
+    /// unknown or has no value.
+    fn value(&self, name: &str) -> Option<String> {
+        let restricted = self.limit_ad_tracking == Some(true);
+        let identifier = |value: Option<String>| {
+            if restricted {
+                Some(RESTRICTED.to_string())
+            } else {
+                value
+            }
+        };
+        let value = match name {
+            "TIMESTAMP" => self.timestamp.map(format_timestamp),
+            "CACHEBUSTING" => self.cache_busting.map(|value| format!("{value:08}")),
+            "ERRORCODE" => self.error_code.map(|code| code.to_string()),
+            "CONTENTPLAYHEAD" => self.content_playhead.map(format_playhead),
+            "IFA" => identifier(self.ifa.clone()),
+            "DEVICEIP" => identifier(self.device_ip.clone()),
+            "LATLONG" => identifier(self.lat_long.map(|(lat, lon)| format!("{lat:.4},{lon:.4}"))),
+            "DEVICEUA" => self.device_ua.clone(),
+            "GDPRCONSENT" => self.gdpr_consent.clone(),
+            "APPBUNDLE" => self.app_bundle.clone(),
+            "DOMAIN" => self.domain.clone(),
+            "PAGEURL" => self.page_url.clone(),
+            "LIMITADTRACKING" => self
+                .limit_ad_tracking
+                .map(|limited| u8::from(limited).to_string()),
+            _ => None,
+        }?;
+        Some(percent_encode(&value))
+    }
+}
+
+/// Writes a time in ISO 8601 with milliseconds, in UTC, as in
+/// `2016-01-17T08:15:07.127Z`.
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_date(seconds / 86_400);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Writes a position as `HH:MM:SS.mmm`.
+fn format_playhead(position: Duration) -> String {
+    let seconds = position.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        position.subsec_millis()
+    )
+}
+
+/// Returns the year, month and day of a number of days since 1970-01-01.
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
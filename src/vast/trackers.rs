@@ -0,0 +1,288 @@
+use std::fmt;
+
+use super::{Ad, AdKind, AdSystem, Creative, Impression, Linear, Tracking, Vast, VastError};
+use super::{Wrapper, PROLOG};
+use crate::openrtb2::codes::Protocol;
+use crate::openrtb2::{Bid, Imp};
+use crate::xml::{self, Element};
+
+/// Errors raised when wrapping the creative of a bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WrapError {
+    /// The bid has no win notice URL (`Bid.nurl`) to serve the creative.
+    MissingNurl,
+    /// There is no impression tracker, required in a wrapper.
+    MissingImpression,
+    /// The impression lists no VAST wrapper protocol (`Video.protocols` or
+    /// `Audio.protocols`).
+    UnsupportedProtocols,
+}
+
+impl fmt::Display for WrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WrapError::MissingNurl => write!(f, "bid has no win notice URL"),
+            WrapError::MissingImpression => write!(f, "wrapper needs an impression tracker"),
+            WrapError::UnsupportedProtocols => {
+                write!(f, "impression supports no VAST wrapper protocol")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WrapError {}
+
+/// The trackers of an exchange, added to the VAST ads it serves.
+///
+/// [`inject`](Trackers::inject) adds them to the ads of a VAST document and
+/// [`wrap`](Trackers::wrap) creates a wrapper holding them, for creatives
+/// served by the win notice.
+///
+/// ```
+/// use iab::vast::*;
+///
+/// let trackers = Trackers::new()
+///     .impression("https://exchange.com/imp")
+///     .event("firstQuartile", "https://exchange.com/q1");
+///
+/// let vast = r#"<VAST version="3.0"><Ad><InLine><AdSystem>DSP</AdSystem><AdTitle>Ad</AdTitle>
+///   <Impression>https://dsp.com/imp</Impression>
+///   <Creatives><Creative><Linear><Duration>00:00:15</Duration></Linear></Creative></Creatives>
+/// </InLine></Ad></VAST>"#;
+///
+/// let injected = trackers.inject(vast).unwrap();
+/// assert_eq!(trackers.inject(&injected).unwrap(), injected);
+///
+/// let AdKind::InLine(inline) = &Vast::parse(&injected).unwrap().ads[0].kind else { panic!() };
+/// assert_eq!(inline.impressions.len(), 2);
+/// assert_eq!(inline.creatives[0].linear.as_ref().unwrap().tracking_events[0].event, "firstQuartile");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trackers {
+    /// Impression tracking URLs.
+    pub impressions: Vec<String>,
+    /// Error URLs.
+    pub errors: Vec<String>,
+    /// Trackers of linear creative events, such as `start` or `complete`.
+    pub events: Vec<Tracking>,
+}
+
+impl Trackers {
+    /// Creates an empty set of trackers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an impression tracking URL.
+    #[must_use]
+    pub fn impression(mut self, url: impl Into<String>) -> Self {
+        self.impressions.push(url.into());
+        self
+    }
+
+    /// Adds an error URL.
+    #[must_use]
+    pub fn error(mut self, url: impl Into<String>) -> Self {
+        self.errors.push(url.into());
+        self
+    }
+
+    /// Adds a tracker for an event of linear creatives.
+    #[must_use]
+    pub fn event(mut self, event: impl Into<String>, url: impl Into<String>) -> Self {
+        self.events.push(Tracking {
+            event: event.into(),
+            offset: None,
+            url: url.into(),
+        });
+        self
+    }
+
+    /// Adds the trackers to every ad of a VAST document, and the error URLs
+    /// to the document itself if it has no ads. Trackers already present
+    /// are not added again, so injecting twice changes nothing.
+    ///
+    /// Event trackers go to the linear creatives; a wrapper without any gets
+    /// a creative holding them. The rest of the document is kept as is.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the markup is not well-formed XML with a `VAST` root.
+    pub fn inject(&self, markup: &str) -> Result<String, VastError> {
+        let mut root = xml::parse(markup)?;
+        if root.name != "VAST" {
+            return Err(VastError::NotVast { root: root.name });
+        }
+
+        let mut ads = 0;
+        for ad in root.children_mut("Ad") {
+            ads += 1;
+            let wrapper = ad.child("InLine").is_none();
+            let name = if wrapper { "Wrapper" } else { "InLine" };
+            if let Some(body) = ad.child_mut(name) {
+                self.inject_ad(body, wrapper);
+            }
+        }
+        if ads == 0 {
+            for url in &self.errors {
+                add_text(&mut root, "Error", url);
+            }
+        }
+
+        let mut out = String::from(PROLOG);
+        root.write(&mut out);
+        Ok(out)
+    }
+
+    /// Injects the trackers into the VAST markup of a bid (`Bid.adm`), if
+    /// it has some.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`inject`](Self::inject), leaving the bid untouched.
+    pub fn apply(&self, bid: &mut Bid) -> Result<(), VastError> {
+        if let Some(adm) = &mut bid.adm {
+            *adm = self.inject(adm)?;
+        }
+        Ok(())
+    }
+
+    /// Creates a VAST wrapper of the creative served by the win notice URL
+    /// of a bid (`Bid.nurl`), holding the trackers.
+    ///
+    /// The version is the highest one the impression supports for wrappers
+    /// in its `Video.protocols`, or `Audio.protocols`, from VAST 2.0 on; see
+    /// [`wrapper_protocol`]. The win notice URL must have its macros
+    /// substituted already.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bid has no win notice URL, if there is no impression
+    /// tracker, or if the impression supports no VAST version with wrappers.
+    pub fn wrap(&self, ad_system: &str, bid: &Bid, imp: &Imp) -> Result<Vast, WrapError> {
+        let nurl = bid.nurl.as_ref().ok_or(WrapError::MissingNurl)?;
+        if self.impressions.is_empty() {
+            return Err(WrapError::MissingImpression);
+        }
+        let protocols = imp
+            .video
+            .as_ref()
+            .and_then(|video| video.protocols.as_deref())
+            .or_else(|| imp.audio.as_ref()?.protocols.as_deref())
+            .unwrap_or_default();
+        let protocol = wrapper_protocol(protocols).ok_or(WrapError::UnsupportedProtocols)?;
+
+        let creatives = if self.events.is_empty() {
+            Vec::new()
+        } else {
+            vec![Creative {
+                linear: Some(Linear {
+                    tracking_events: self.events.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]
+        };
+        let wrapper = Wrapper {
+            ad_system: AdSystem {
+                name: ad_system.to_string(),
+                version: None,
+            },
+            vast_ad_tag_uri: nurl.clone(),
+            impressions: self
+                .impressions
+                .iter()
+                .map(|url| Impression {
+                    id: None,
+                    url: url.clone(),
+                })
+                .collect(),
+            errors: self.errors.clone(),
+            creatives,
+            ..Default::default()
+        };
+        Ok(Vast {
+            version: protocol.vast_version().unwrap_or_default().to_string(),
+            ads: vec![Ad {
+                id: Some(bid.id.clone()),
+                sequence: None,
+                conditional_ad: None,
+                ad_type: None,
+                kind: AdKind::Wrapper(wrapper),
+            }],
+            errors: Vec::new(),
+        })
+    }
+
+    fn inject_ad(&self, body: &mut Element, wrapper: bool) {
+        for url in &self.impressions {
+            add_text(body, "Impression", url);
+        }
+        for url in &self.errors {
+            add_text(body, "Error", url);
+        }
+        if self.events.is_empty() {
+            return;
+        }
+
+        let mut linears = 0;
+        if let Some(creatives) = body.child_mut("Creatives") {
+            for creative in creatives.children_mut("Creative") {
+                if let Some(linear) = creative.child_mut("Linear") {
+                    linears += 1;
+                    self.inject_events(linear);
+                }
+            }
+        }
+        if linears == 0 && wrapper {
+            let mut linear = Element::new("Linear");
+            self.inject_events(&mut linear);
+            let creative = Element::new("Creative").with_child(linear);
+            match body.child_mut("Creatives") {
+                Some(creatives) => creatives.insert(creative),
+                None => body.insert(Element::new("Creatives").with_child(creative)),
+            }
+        }
+    }
+
+    fn inject_events(&self, linear: &mut Element) {
+        if linear.child("TrackingEvents").is_none() {
+            linear.insert(Element::new("TrackingEvents"));
+        }
+        let Some(events) = linear.child_mut("TrackingEvents") else {
+            return;
+        };
+        for tracking in &self.events {
+            let present = events.children("Tracking").any(|element| {
+                element.attr("event") == Some(tracking.event.as_str())
+                    && element.attr("offset") == tracking.offset.as_deref()
+                    && element.text() == tracking.url
+            });
+            if !present {
+                events.insert(tracking.element());
+            }
+        }
+    }
+}
+
+/// Returns the protocol of a VAST wrapper for an impression supporting the
+/// given protocols: the highest VAST version, from 2.0 on, listed with its
+/// wrapper protocol.
+#[must_use]
+pub fn wrapper_protocol(protocols: &[i64]) -> Option<Protocol> {
+    protocols
+        .iter()
+        .filter_map(|code| Protocol::from_code(*code))
+        .filter(|protocol| protocol.is_wrapper() && *protocol != Protocol::Vast10Wrapper)
+        .filter_map(|protocol| Some((protocol.vast_version()?, protocol)))
+        .max()
+        .map(|(_, protocol)| protocol)
+}
+
+/// Adds an element with a text, unless one with the same text is present.
+fn add_text(parent: &mut Element, name: &str, text: &str) {
+    if !parent.children(name).any(|element| element.text() == text) {
+        parent.insert(Element::new(name).with_text(text));
+    }
+}
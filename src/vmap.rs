@@ -0,0 +1,689 @@
+//! # VMAP
+//!
+//! The IAB Video Multiple Ad Playlist (VMAP) 1.0 documents in which long-form
+//! and CTV players describe the ad breaks of their content: when each break
+//! plays and where its ads come from.
+//!
+//! [`Vmap::parse`] reads a document, with or without the `vmap` namespace
+//! prefix, and [`Vmap::to_xml`] writes it back; extensions are left out.
+//! [`Vmap::add_pods`] turns the linear breaks into `OpenRTB` ad pod
+//! impressions, and [`Vmap::serve_pods`] builds the document serving the
+//! pods won in the auction.
+//!
+//! ```
+//! use iab::openrtb2::*;
+//! use iab::vmap::*;
+//!
+//! let vmap = Vmap::parse(
+//!     r#"<vmap:VMAP xmlns:vmap="http://www.iab.net/videosuite/vmap" version="1.0">
+//!       <vmap:AdBreak timeOffset="start" breakType="linear" breakId="preroll">
+//!         <vmap:AdSource id="preroll-ads" allowMultipleAds="true">
+//!           <vmap:AdTagURI templateType="vast3"><![CDATA[https://ads.com/vast?pod=1]]></vmap:AdTagURI>
+//!         </vmap:AdSource>
+//!       </vmap:AdBreak>
+//!       <vmap:AdBreak timeOffset="00:10:00.000" breakType="linear" breakId="midroll-1"/>
+//!     </vmap:VMAP>"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(vmap.ad_breaks[1].time_offset, TimeOffset::Time(std::time::Duration::from_secs(600)));
+//! assert_eq!(Vmap::parse(&vmap.to_xml()).unwrap(), vmap);
+//!
+//! let mut request = BidRequest::default();
+//! let template = Video {
+//!     mimes: vec!["video/mp4".to_string()],
+//!     poddur: Some(90),
+//!     maxseq: Some(4),
+//!     ..Default::default()
+//! };
+//! vmap.add_pods(&mut request, &template);
+//!
+//! let video = request.imp[1].video.as_ref().unwrap();
+//! assert_eq!(video.podid.as_deref(), Some("midroll-1"));
+//! assert_eq!((video.podseq, video.startdelay, video.poddur), (Some(-1), Some(600), Some(90)));
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::auction::Pod;
+use crate::openrtb2::{BidRequest, Imp, Video};
+use crate::vast::{self, AdKind, Trackers, Vast, VastError, WrapError, PROLOG};
+use crate::xml::{self, Element, Node, XmlError};
+
+/// Namespace of VMAP elements.
+const NAMESPACE: &str = "http://www.iab.net/videosuite/vmap";
+
+/// Value of `startdelay` for a generic mid-roll, from the `AdCOM` 1.0 List:
+/// Start Delay Modes.
+const GENERIC_MID_ROLL: i64 = -1;
+
+/// Value of `startdelay` for a generic post-roll.
+const GENERIC_POST_ROLL: i64 = -2;
+
+/// Errors raised when parsing a VMAP document or building one from pods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmapError {
+    /// The markup is not well-formed XML.
+    InvalidXml {
+        /// Byte offset of the error in the markup.
+        position: usize,
+        message: &'static str,
+    },
+    /// The root element is not `VMAP`.
+    NotVmap { root: String },
+    /// A required element is missing, such as the ad data of an `AdSource`.
+    MissingElement {
+        parent: &'static str,
+        name: &'static str,
+    },
+    /// The `timeOffset` of a break is missing or not a valid offset.
+    InvalidTimeOffset(String),
+    /// The VAST document of a break, or the markup of a winning bid, is not
+    /// valid VAST.
+    InvalidVast(VastError),
+    /// A winning bid has neither markup (`Bid.adm`) nor win notice URL
+    /// (`Bid.nurl`).
+    MissingMarkup { bid: String },
+    /// The creative served by the win notice URL of a winning bid cannot be
+    /// wrapped.
+    Wrap { bid: String, error: WrapError },
+    /// The impressions of a pod have no `startdelay` giving the time of its
+    /// break, or only a generic mid-roll one.
+    UnknownStart { podid: String },
+}
+
+impl fmt::Display for VmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmapError::InvalidXml { position, message } => {
+                write!(f, "invalid XML: {message} at byte {position}")
+            }
+            VmapError::NotVmap { root } => write!(f, "root element {root} is not VMAP"),
+            VmapError::MissingElement { parent, name } => {
+                write!(f, "missing {name} element in {parent}")
+            }
+            VmapError::InvalidTimeOffset(offset) => write!(f, "invalid time offset {offset:?}"),
+            VmapError::InvalidVast(e) => write!(f, "invalid VAST: {e}"),
+            VmapError::MissingMarkup { bid } => {
+                write!(f, "bid {bid} has neither markup nor win notice URL")
+            }
+            VmapError::UnknownStart { podid } => write!(f, "unknown start of pod {podid}"),
+            VmapError::Wrap { bid, error } => write!(f, "cannot wrap bid {bid}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for VmapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VmapError::InvalidVast(e) => Some(e),
+            VmapError::Wrap { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<XmlError> for VmapError {
+    fn from(error: XmlError) -> Self {
+        VmapError::InvalidXml {
+            position: error.position,
+            message: error.message,
+        }
+    }
+}
+
+impl From<VastError> for VmapError {
+    fn from(error: VastError) -> Self {
+        VmapError::InvalidVast(error)
+    }
+}
+
+/// A VMAP document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vmap {
+    /// Version of the document, `1.0`.
+    pub version: String,
+    /// The breaks, in the order of the document.
+    pub ad_breaks: Vec<AdBreak>,
+}
+
+/// An ad break in the content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdBreak {
+    /// When the break plays (`timeOffset`).
+    pub time_offset: TimeOffset,
+    /// Kinds of ads allowed, comma separated, among `linear`, `nonlinear`
+    /// and `display` (`breakType`).
+    pub break_type: String,
+    /// ID of the break (`breakId`).
+    pub break_id: Option<String>,
+    /// Interval after which the break plays again (`repeatAfter`, VMAP
+    /// 1.0.1).
+    pub repeat_after: Option<Duration>,
+    /// Where the ads of the break come from, absent for a break whose ads
+    /// are requested by the player.
+    pub ad_source: Option<AdSource>,
+    /// Trackers of the `breakStart`, `breakEnd` and `error` events.
+    pub tracking_events: Vec<Tracking>,
+}
+
+/// When a break plays in the content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeOffset {
+    /// Before the content (`start`).
+    Start,
+    /// After the content (`end`).
+    End,
+    /// At a time of the content, written `HH:MM:SS` or `HH:MM:SS.mmm`.
+    Time(Duration),
+    /// At a percentage of the content, written `50%`.
+    Percentage(f64),
+    /// At a cue point of the content, from 1, written `#1`.
+    Position(u32),
+}
+
+/// The source of the ads of a break.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdSource {
+    pub id: Option<String>,
+    /// Whether the source may return a pod (`allowMultipleAds`).
+    pub allow_multiple_ads: Option<bool>,
+    /// Whether the player follows VAST wrappers (`followRedirects`).
+    pub follow_redirects: Option<bool>,
+    pub data: AdData,
+}
+
+/// The ads of a break, or where to get them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdData {
+    /// A VAST document in the VMAP document (`VASTAdData`).
+    Vast(Vast),
+    /// URL of an ad server returning the ads (`AdTagURI`).
+    AdTagUri {
+        /// Format of the response, such as `vast3` (`templateType`).
+        template_type: String,
+        url: String,
+    },
+    /// Ads in another format, kept as XML (`CustomAdData`).
+    Custom { template_type: String, xml: String },
+}
+
+/// A tracking URL for an event of a break.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Tracking {
+    /// `breakStart`, `breakEnd` or `error`.
+    pub event: String,
+    pub url: String,
+}
+
+impl Vmap {
+    /// Parses a VMAP document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the markup is not well-formed XML, or not a valid VMAP
+    /// document.
+    pub fn parse(markup: &str) -> Result<Vmap, VmapError> {
+        let mut root = xml::parse(markup)?;
+        let Some(prefix) = root.name.strip_suffix("VMAP").map(str::to_string) else {
+            return Err(VmapError::NotVmap { root: root.name });
+        };
+        if !prefix.is_empty() && !prefix.ends_with(':') {
+            return Err(VmapError::NotVmap { root: root.name });
+        }
+        strip_prefix(&mut root, &prefix);
+        Ok(Vmap {
+            version: root.attr("version").unwrap_or_default().trim().to_string(),
+            ad_breaks: root
+                .children("AdBreak")
+                .map(AdBreak::read)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Writes the document as XML, with the `vmap` namespace prefix.
+    pub fn to_xml(&self) -> String {
+        let root = Element::new("vmap:VMAP")
+            .with_attr("xmlns:vmap", NAMESPACE)
+            .with_attr("version", &self.version)
+            .with_children(self.ad_breaks.iter().map(AdBreak::element));
+        let mut out = String::from(PROLOG);
+        root.write(&mut out);
+        out
+    }
+
+    /// Adds an ad pod impression to a bid request for each linear break,
+    /// in the order of the document.
+    ///
+    /// Each impression has a copy of the template, with `podid` set to the
+    /// ID of the break, or `break-n` for the n-th break without one,
+    /// `podseq` to its position among the pods (first, last or any) and
+    /// `startdelay` to its time offset. Percentages of the content are
+    /// converted to seconds with `Content.len` of the site or app, if known,
+    /// and are generic mid-rolls otherwise, as are cue points. VMAP has no
+    /// duration for breaks, so `poddur`, `maxseq` and the other pod fields
+    /// come from the template. Impression IDs are numbers following the
+    /// number of impressions already in the request, skipping the IDs
+    /// taken.
+    pub fn add_pods(&self, request: &mut BidRequest, template: &Video) {
+        let content = request
+            .site
+            .as_ref()
+            .and_then(|site| site.content.as_ref())
+            .or_else(|| request.app.as_ref()?.content.as_ref());
+        let length = content.and_then(|content| content.len);
+
+        let breaks: Vec<(String, &AdBreak)> = self.linear_breaks().collect();
+        for (position, (podid, ad_break)) in breaks.iter().enumerate() {
+            let podseq = match position {
+                0 => 1,
+                _ if position + 1 == breaks.len() => -1,
+                _ => 0,
+            };
+            let video = Video {
+                podid: Some(podid.clone()),
+                podseq: Some(podseq),
+                startdelay: Some(ad_break.time_offset.start_delay(length)),
+                ..template.clone()
+            };
+            let mut number = request.imp.len() + 1;
+            while request.imp.iter().any(|imp| imp.id == number.to_string()) {
+                number += 1;
+            }
+            request.imp.push(Imp {
+                id: number.to_string(),
+                video: Some(video),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Creates the document serving the pods won in the auction of a request
+    /// made with [`add_pods`](Vmap::add_pods), with a linear break for each
+    /// pod that has ads.
+    ///
+    /// The breaks keep the time offset, ID, repetition and tracking events of
+    /// the break of this document the pod was made for, so percentages and cue points
+    /// are served as scheduled. Other pods are placed at their `startdelay`,
+    /// as in [`from_pods`](Vmap::from_pods), which gives the content of the
+    /// breaks.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`from_pods`](Vmap::from_pods), for the pods that are not
+    /// part of this document.
+    pub fn serve_pods(
+        &self,
+        trackers: &Trackers,
+        ad_system: &str,
+        request: &BidRequest,
+        pods: &[Pod],
+    ) -> Result<Vmap, VmapError> {
+        let breaks: Vec<(String, &AdBreak)> = self.linear_breaks().collect();
+        let mut ad_breaks = Vec::new();
+        for pod in pods.iter().filter(|pod| !pod.ads.is_empty()) {
+            let scheduled = breaks.iter().find(|(podid, _)| *podid == pod.podid);
+            let ad_break = if let Some((_, scheduled)) = scheduled {
+                AdBreak {
+                    break_id: scheduled.break_id.clone(),
+                    repeat_after: scheduled.repeat_after,
+                    tracking_events: scheduled.tracking_events.clone(),
+                    ..pod_break(trackers, ad_system, request, pod, scheduled.time_offset)?
+                }
+            } else {
+                let time_offset = start_offset(request, pod)?;
+                pod_break(trackers, ad_system, request, pod, time_offset)?
+            };
+            ad_breaks.push(ad_break);
+        }
+        Ok(Vmap {
+            version: "1.0".to_string(),
+            ad_breaks,
+        })
+    }
+
+    /// Creates the document serving pods won in the auction of a request,
+    /// with a linear break for each pod that has ads.
+    ///
+    /// The break of a pod is at the `startdelay` of its impressions, and
+    /// holds a VAST document with the ads of the pod in playback order: the
+    /// ads of the markup of each bid (`Bid.adm`), which keep the error URLs
+    /// of their document, or a wrapper of the creative served by its win
+    /// notice URL (`Bid.nurl`) from the given ad system; see
+    /// [`Trackers::wrap`]. Either way, the ads hold the trackers of the
+    /// exchange. The markup must have its macros substituted already.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the markup of a bid is not valid VAST, if a bid without
+    /// markup cannot be wrapped, or on pods at a generic mid-roll
+    /// `startdelay`, whose time is unknown; [`serve_pods`](Vmap::serve_pods)
+    /// places the pods of a schedule at their breaks instead.
+    pub fn from_pods(
+        trackers: &Trackers,
+        ad_system: &str,
+        request: &BidRequest,
+        pods: &[Pod],
+    ) -> Result<Vmap, VmapError> {
+        let mut ad_breaks = Vec::new();
+        for pod in pods.iter().filter(|pod| !pod.ads.is_empty()) {
+            let time_offset = start_offset(request, pod)?;
+            ad_breaks.push(pod_break(trackers, ad_system, request, pod, time_offset)?);
+        }
+        Ok(Vmap {
+            version: "1.0".to_string(),
+            ad_breaks,
+        })
+    }
+
+    /// Returns the linear breaks with the ID of their pod: the ID of the
+    /// break, or `break-n` for the n-th break without one.
+    fn linear_breaks(&self) -> impl Iterator<Item = (String, &AdBreak)> {
+        self.ad_breaks
+            .iter()
+            .enumerate()
+            .filter(|(_, ad_break)| ad_break.is_linear())
+            .map(|(index, ad_break)| {
+                let podid = ad_break
+                    .break_id
+                    .clone()
+                    .unwrap_or_else(|| format!("break-{}", index + 1));
+                (podid, ad_break)
+            })
+    }
+}
+
+/// Returns the time offset of the `startdelay` of the impressions of a pod.
+fn start_offset(request: &BidRequest, pod: &Pod) -> Result<TimeOffset, VmapError> {
+    let start_delay = request
+        .imp
+        .iter()
+        .find_map(|imp| match (&imp.video, &imp.audio) {
+            (Some(video), _) if video.podid.as_deref() == Some(&pod.podid) => video.startdelay,
+            (_, Some(audio)) if audio.podid.as_deref() == Some(&pod.podid) => audio.startdelay,
+            _ => None,
+        });
+    match start_delay {
+        Some(0) => Ok(TimeOffset::Start),
+        Some(GENERIC_POST_ROLL) => Ok(TimeOffset::End),
+        Some(seconds) if seconds > 0 => Ok(TimeOffset::Time(Duration::from_secs(
+            seconds.unsigned_abs(),
+        ))),
+        _ => Err(VmapError::UnknownStart {
+            podid: pod.podid.clone(),
+        }),
+    }
+}
+
+/// Creates the break serving the ads of a pod at a time offset.
+fn pod_break(
+    trackers: &Trackers,
+    ad_system: &str,
+    request: &BidRequest,
+    pod: &Pod,
+    time_offset: TimeOffset,
+) -> Result<AdBreak, VmapError> {
+    let mut vast = Vast::default();
+    for pod_ad in &pod.ads {
+        let bid = &pod_ad.candidate.bid;
+        let document = match (&bid.adm, &bid.nurl) {
+            (Some(adm), _) if !adm.trim().is_empty() => {
+                let mut document = Vast::parse(&trackers.inject(adm)?)?;
+                // The errors of the document would otherwise be lost in the
+                // pod; they apply to each of its ads.
+                for ad in &mut document.ads {
+                    let errors = match &mut ad.kind {
+                        AdKind::InLine(inline) => &mut inline.errors,
+                        AdKind::Wrapper(wrapper) => &mut wrapper.errors,
+                    };
+                    for url in &document.errors {
+                        if !errors.contains(url) {
+                            errors.push(url.clone());
+                        }
+                    }
+                }
+                document
+            }
+            (_, Some(_)) => {
+                // Pods are assembled from the impressions of the request;
+                // any other one offers no protocol.
+                let imp = request.imp.iter().find(|imp| imp.id == pod_ad.imp_id);
+                trackers
+                    .wrap(ad_system, bid, imp.unwrap_or(&Imp::default()))
+                    .map_err(|error| VmapError::Wrap {
+                        bid: bid.id.clone(),
+                        error,
+                    })?
+            }
+            _ => {
+                return Err(VmapError::MissingMarkup {
+                    bid: bid.id.clone(),
+                })
+            }
+        };
+        vast.version = vast.version.max(document.version);
+        vast.ads.extend(document.ads);
+    }
+    for (sequence, ad) in vast.ads.iter_mut().enumerate() {
+        ad.sequence = u32::try_from(sequence + 1).ok();
+    }
+    if vast.version.is_empty() {
+        // Ad pods appeared in VAST 3.0.
+        vast.version = "3.0".to_string();
+    }
+
+    Ok(AdBreak {
+        time_offset,
+        break_type: "linear".to_string(),
+        break_id: Some(pod.podid.clone()),
+        repeat_after: None,
+        ad_source: Some(AdSource {
+            id: Some(pod.podid.clone()),
+            allow_multiple_ads: Some(true),
+            follow_redirects: Some(true),
+            data: AdData::Vast(vast),
+        }),
+        tracking_events: Vec::new(),
+    })
+}
+
+impl AdBreak {
+    /// Returns whether the break allows linear ads.
+    #[must_use]
+    pub fn is_linear(&self) -> bool {
+        self.break_type
+            .split(',')
+            .any(|kind| kind.trim().eq_ignore_ascii_case("linear"))
+    }
+
+    fn read(element: &Element) -> Result<AdBreak, VmapError> {
+        let offset = element.attr("timeOffset").unwrap_or_default();
+        let time_offset = TimeOffset::parse(offset)
+            .ok_or_else(|| VmapError::InvalidTimeOffset(offset.to_string()))?;
+        let ad_source = match element.child("AdSource") {
+            Some(source) => Some(AdSource::read(source)?),
+            None => None,
+        };
+        let tracking_events = element
+            .child("TrackingEvents")
+            .into_iter()
+            .flat_map(|events| events.children("Tracking"))
+            .map(|tracking| Tracking {
+                event: vast::attr(tracking, "event").unwrap_or_default(),
+                url: tracking.text(),
+            })
+            .filter(|tracking| !tracking.url.is_empty())
+            .collect();
+        Ok(AdBreak {
+            time_offset,
+            break_type: vast::attr(element, "breakType").unwrap_or_default(),
+            break_id: vast::attr(element, "breakId"),
+            repeat_after: element
+                .attr("repeatAfter")
+                .and_then(|text| vast::parse_duration(text.trim())),
+            ad_source,
+            tracking_events,
+        })
+    }
+
+    fn element(&self) -> Element {
+        Element::new("vmap:AdBreak")
+            .with_attr("timeOffset", &self.time_offset)
+            .with_attr("breakType", &self.break_type)
+            .with_optional_attr("breakId", self.break_id.as_ref())
+            .with_optional_attr("repeatAfter", self.repeat_after.map(vast::format_duration))
+            .with_children(self.ad_source.as_ref().map(AdSource::element))
+            .with_children(vast::list(
+                "vmap:TrackingEvents",
+                &self.tracking_events,
+                Tracking::element,
+            ))
+    }
+}
+
+impl AdSource {
+    fn read(element: &Element) -> Result<AdSource, VmapError> {
+        let data = if let Some(data) = element.child("VASTAdData") {
+            let root = data.child("VAST").ok_or(VmapError::MissingElement {
+                parent: "VASTAdData",
+                name: "VAST",
+            })?;
+            AdData::Vast(Vast::read(root)?)
+        } else if let Some(uri) = element.child("AdTagURI") {
+            AdData::AdTagUri {
+                template_type: vast::attr(uri, "templateType").unwrap_or_default(),
+                url: uri.text(),
+            }
+        } else if let Some(custom) = element.child("CustomAdData") {
+            let mut xml = String::new();
+            custom.write_content(&mut xml);
+            AdData::Custom {
+                template_type: vast::attr(custom, "templateType").unwrap_or_default(),
+                xml: xml.trim().to_string(),
+            }
+        } else {
+            return Err(VmapError::MissingElement {
+                parent: "AdSource",
+                name: "AdTagURI",
+            });
+        };
+        Ok(AdSource {
+            id: vast::attr(element, "id"),
+            allow_multiple_ads: vast::flag(element, "allowMultipleAds"),
+            follow_redirects: vast::flag(element, "followRedirects"),
+            data,
+        })
+    }
+
+    fn element(&self) -> Element {
+        let data = match &self.data {
+            AdData::Vast(vast) => Element::new("vmap:VASTAdData").with_child(vast.element()),
+            AdData::AdTagUri { template_type, url } => Element::new("vmap:AdTagURI")
+                .with_attr("templateType", template_type)
+                .with_text(url),
+            AdData::Custom { template_type, xml } => Element::new("vmap:CustomAdData")
+                .with_attr("templateType", template_type)
+                .with_raw(xml),
+        };
+        Element::new("vmap:AdSource")
+            .with_optional_attr("id", self.id.as_ref())
+            .with_optional_attr("allowMultipleAds", self.allow_multiple_ads)
+            .with_optional_attr("followRedirects", self.follow_redirects)
+            .with_child(data)
+    }
+}
+
+impl Tracking {
+    fn element(&self) -> Element {
+        Element::new("vmap:Tracking")
+            .with_attr("event", &self.event)
+            .with_text(&self.url)
+    }
+}
+
+impl TimeOffset {
+    /// Parses a time offset, as in `start`, `00:10:00`, `50%` or `#1`.
+    pub fn parse(text: &str) -> Option<TimeOffset> {
+        let text = text.trim();
+        match text {
+            "start" => Some(TimeOffset::Start),
+            "end" => Some(TimeOffset::End),
+            _ => {
+                if let Some(percentage) = text.strip_suffix('%') {
+                    let percentage: f64 = percentage.trim().parse().ok()?;
+                    (0.0..=100.0)
+                        .contains(&percentage)
+                        .then_some(TimeOffset::Percentage(percentage))
+                } else if let Some(position) = text.strip_prefix('#') {
+                    position.trim().parse().ok().map(TimeOffset::Position)
+                } else {
+                    vast::parse_duration(text).map(TimeOffset::Time)
+                }
+            }
+        }
+    }
+
+    /// Returns the `startdelay` of a pod playing at the offset, given the
+    /// length of the content in seconds if known.
+    // Content lengths are far below 2^52 seconds, where `f64` stops being
+    // exact, and the rounded offset is below the length.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn start_delay(&self, content_length: Option<i64>) -> i64 {
+        match *self {
+            TimeOffset::Start => 0,
+            TimeOffset::End => GENERIC_POST_ROLL,
+            TimeOffset::Time(time) if time.is_zero() => 0,
+            TimeOffset::Time(time) => i64::try_from(time.as_secs().max(1)).unwrap_or(i64::MAX),
+            TimeOffset::Percentage(percentage) if percentage <= 0.0 => 0,
+            TimeOffset::Percentage(percentage) if percentage >= 100.0 => GENERIC_POST_ROLL,
+            TimeOffset::Percentage(percentage) => match content_length {
+                Some(length) if length > 0 => {
+                    ((length as f64 * percentage / 100.0).round() as i64).max(1)
+                }
+                _ => GENERIC_MID_ROLL,
+            },
+            TimeOffset::Position(_) => GENERIC_MID_ROLL,
+        }
+    }
+}
+
+impl fmt::Display for TimeOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeOffset::Start => write!(f, "start"),
+            TimeOffset::End => write!(f, "end"),
+            TimeOffset::Time(time) => {
+                // VMAP writes the milliseconds of times.
+                let seconds = time.as_secs();
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}.{:03}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60,
+                    time.subsec_millis()
+                )
+            }
+            TimeOffset::Percentage(percentage) => write!(f, "{percentage}%"),
+            TimeOffset::Position(position) => write!(f, "#{position}"),
+        }
+    }
+}
+
+/// Removes the namespace prefix of the VMAP elements, leaving the ads of
+/// `VASTAdData` and `CustomAdData` as they are.
+fn strip_prefix(element: &mut Element, prefix: &str) {
+    if let Some(name) = element.name.strip_prefix(prefix) {
+        element.name = name.to_string();
+    }
+    if element.name == "VASTAdData" || element.name == "CustomAdData" {
+        return;
+    }
+    for node in &mut element.children {
+        if let Node::Element(child) = node {
+            strip_prefix(child, prefix);
+        }
+    }
+}
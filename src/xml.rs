@@ -0,0 +1,479 @@
+//! A small, lenient XML reader and a writer for ad markup such as VAST.
+//!
+//! Markup found in bid responses is often hand written, so spaces are
+//! accepted around tag names and attribute values (`< /Linear >`), and any
+//! `<![...[` section ending with `]]>` is read as character data. Comments,
+//! processing instructions and doctypes are skipped, and namespaces are kept
+//! as part of the names.
+//!
+//! Elements may be nested [`MAX_DEPTH`] deep, far more than any ad format
+//! needs, so that hostile markup cannot exhaust the stack.
+//!
+//! Elements are written without added whitespace, with text that needs
+//! escaping written as character data, as is usual for URLs in VAST.
+
+use std::fmt;
+
+/// Maximum nesting depth of elements, the root being at depth 1.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// An XML element with its attributes and children.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+/// A child of an element: another element, or text and character data.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+    /// Markup written as is, never read.
+    Raw(String),
+}
+
+/// Markup that cannot be read as XML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct XmlError {
+    /// Byte offset of the error in the markup.
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+impl Element {
+    /// Returns the value of an attribute.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) | Node::Raw(_) => None,
+        })
+    }
+
+    /// Returns the first child element with a name.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Returns the child elements with a name.
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    /// Returns the first child element with a name, mutably.
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut Element> {
+        self.children.iter_mut().find_map(|node| match node {
+            Node::Element(element) if element.name == name => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Returns the child elements with a name, mutably.
+    pub fn children_mut<'a>(&'a mut self, name: &'a str) -> impl Iterator<Item = &'a mut Element> {
+        self.children.iter_mut().filter_map(move |node| match node {
+            Node::Element(element) if element.name == name => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Inserts a child element after the last one with the same name, or
+    /// last if there is none.
+    pub fn insert(&mut self, child: Element) {
+        let position = self
+            .children
+            .iter()
+            .rposition(|node| matches!(node, Node::Element(element) if element.name == child.name))
+            .map_or(self.children.len(), |position| position + 1);
+        self.children.insert(position, Node::Element(child));
+    }
+
+    /// Returns the text of the element, without surrounding whitespace.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            if let Node::Text(part) = node {
+                text.push_str(part);
+            }
+        }
+        text.trim().to_string()
+    }
+
+    /// Returns the trimmed text of the first child element with a name.
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name).map(Element::text)
+    }
+
+    /// Creates an element without attributes or children.
+    pub fn new(name: impl Into<String>) -> Element {
+        Element {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds an attribute.
+    pub fn with_attr(mut self, name: &str, value: &(impl ToString + ?Sized)) -> Element {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds an attribute if it has a value.
+    pub fn with_optional_attr(self, name: &str, value: Option<impl ToString>) -> Element {
+        match value {
+            Some(value) => self.with_attr(name, &value),
+            None => self,
+        }
+    }
+
+    /// Adds a child element.
+    pub fn with_child(mut self, child: Element) -> Element {
+        self.children.push(Node::Element(child));
+        self
+    }
+
+    /// Adds child elements.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = Element>) -> Element {
+        self.children
+            .extend(children.into_iter().map(Node::Element));
+        self
+    }
+
+    /// Adds text.
+    pub fn with_text(mut self, text: impl Into<String>) -> Element {
+        self.children.push(Node::Text(text.into()));
+        self
+    }
+
+    /// Adds markup, written as is.
+    pub fn with_raw(mut self, markup: impl Into<String>) -> Element {
+        self.children.push(Node::Raw(markup.into()));
+        self
+    }
+
+    /// Writes the element with its children.
+    pub fn write(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.name);
+        for (name, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(name);
+            out.push_str("=\"");
+            out.push_str(&escape(value));
+            out.push('"');
+        }
+        if self.children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+        self.write_content(out);
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push('>');
+    }
+
+    /// Writes the children of the element.
+    pub fn write_content(&self, out: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(element) => element.write(out),
+                Node::Text(text) if needs_escaping(text) && !text.contains("]]>") => {
+                    out.push_str("<![CDATA[");
+                    out.push_str(text);
+                    out.push_str("]]>");
+                }
+                Node::Text(text) => out.push_str(&escape(text)),
+                Node::Raw(markup) => out.push_str(markup),
+            }
+        }
+    }
+}
+
+/// Parses a document into its root element.
+pub(crate) fn parse(markup: &str) -> Result<Element, XmlError> {
+    let mut reader = Reader {
+        markup,
+        position: 0,
+        depth: 0,
+    };
+    reader.skip_misc()?;
+    if reader.rest().is_empty() {
+        return Err(reader.error("missing root element"));
+    }
+    let root = reader.element()?;
+    reader.skip_misc()?;
+    if !reader.rest().is_empty() {
+        return Err(reader.error("content after the root element"));
+    }
+    Ok(root)
+}
+
+struct Reader<'a> {
+    markup: &'a str,
+    position: usize,
+    /// Number of elements open at the position.
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.markup[self.position..]
+    }
+
+    fn error(&self, message: &'static str) -> XmlError {
+        XmlError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips a byte order mark, whitespace, comments, processing
+    /// instructions and doctypes.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        if self.rest().starts_with('\u{feff}') {
+            self.position += '\u{feff}'.len_utf8();
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.skip_ignored()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips a comment, processing instruction or doctype at the position,
+    /// returning whether there was one.
+    fn skip_ignored(&mut self) -> Result<bool, XmlError> {
+        let end = if self.rest().starts_with("<!--") {
+            "-->"
+        } else if self.rest().starts_with("<?") {
+            "?>"
+        } else if self.rest().starts_with("<!") && !self.rest().starts_with("<![") {
+            ">"
+        } else {
+            return Ok(false);
+        };
+        let length = self
+            .rest()
+            .find(end)
+            .ok_or_else(|| self.error("unterminated declaration"))?;
+        self.position += length + end.len();
+        Ok(true)
+    }
+
+    fn name(&mut self) -> Result<String, XmlError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.position += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn expect(&mut self, token: char) -> Result<(), XmlError> {
+        self.skip_whitespace();
+        if !self.rest().starts_with(token) {
+            return Err(self.error(match token {
+                '>' => "expected '>'",
+                '=' => "expected '='",
+                _ => "unexpected character",
+            }));
+        }
+        self.position += token.len_utf8();
+        Ok(())
+    }
+
+    /// Reads an element starting at `<`, with its children.
+    fn element(&mut self) -> Result<Element, XmlError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("elements nested too deep"));
+        }
+        self.depth += 1;
+        let element = self.element_content();
+        self.depth -= 1;
+        element
+    }
+
+    fn element_content(&mut self) -> Result<Element, XmlError> {
+        self.expect('<')?;
+        self.skip_whitespace();
+        let mut element = Element {
+            name: self.name()?,
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with('/') {
+                self.position += 1;
+                self.expect('>')?;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let name = self.name()?;
+            self.expect('=')?;
+            self.skip_whitespace();
+            let value = self.quoted()?;
+            element.attributes.push((name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            let Some(start) = rest.find('<') else {
+                return Err(self.error("unterminated element"));
+            };
+            if start > 0 {
+                let text = unescape(&rest[..start]);
+                self.position += start;
+                push_text(&mut element, text);
+            }
+
+            if self.rest().starts_with("<![") {
+                let text = self.character_data()?;
+                push_text(&mut element, text);
+            } else if self.skip_ignored()? {
+                // Comments and processing instructions hold no content.
+            } else if self.closing_tag() {
+                self.position += 1;
+                self.skip_whitespace();
+                self.position += 1;
+                self.skip_whitespace();
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error("mismatched closing tag"));
+                }
+                self.expect('>')?;
+                return Ok(element);
+            } else {
+                let child = self.element()?;
+                element.children.push(Node::Element(child));
+            }
+        }
+    }
+
+    /// Returns whether the position is at a closing tag, `</` or `< /`.
+    fn closing_tag(&self) -> bool {
+        self.rest()[1..].trim_start().starts_with('/')
+    }
+
+    /// Reads a `<![CDATA[...]]>` section, or any `<![...[...]]>` one.
+    fn character_data(&mut self) -> Result<String, XmlError> {
+        let rest = self.rest();
+        let start = rest[3..]
+            .find('[')
+            .map(|start| start + 4)
+            .ok_or_else(|| self.error("unterminated character data"))?;
+        let length = rest[start..]
+            .find("]]>")
+            .ok_or_else(|| self.error("unterminated character data"))?;
+        self.position += start + length + 3;
+        Ok(rest[start..start + length].to_string())
+    }
+
+    fn quoted(&mut self) -> Result<String, XmlError> {
+        let Some(quote @ ('"' | '\'')) = self.rest().chars().next() else {
+            return Err(self.error("expected a quoted value"));
+        };
+        self.position += 1;
+        let length = self
+            .rest()
+            .find(quote)
+            .ok_or_else(|| self.error("unterminated attribute value"))?;
+        let value = unescape(&self.rest()[..length]);
+        self.position += length + 1;
+        Ok(value)
+    }
+}
+
+/// Appends text to an element, merged with the text before it.
+fn push_text(element: &mut Element, text: String) {
+    match element.children.last_mut() {
+        Some(Node::Text(last)) => last.push_str(&text),
+        _ => element.children.push(Node::Text(text)),
+    }
+}
+
+/// Replaces the predefined entities and character references. Unknown or
+/// malformed references are kept as they are.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest.find(';').and_then(|end| {
+            let character = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                code => {
+                    let code = match code.strip_prefix("#x").or(code.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((character, end))
+        });
+        if let Some((character, end)) = reference {
+            unescaped.push(character);
+            rest = &rest[end + 1..];
+        } else {
+            unescaped.push('&');
+            rest = &rest[1..];
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn needs_escaping(text: &str) -> bool {
+    text.contains(['<', '>', '&', '"'])
+}
+
+/// Replaces the characters that cannot appear in text or attribute values.
+fn escape(text: &str) -> String {
+    if !needs_escaping(text) {
+        return text.to_string();
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,6 @@
+use iab::openrtb2::{Price, PriceExt};
+
+/// Creates a price, with or without the `decimal` feature.
+pub fn price(value: f64) -> Price {
+    Price::from_f64(value)
+}
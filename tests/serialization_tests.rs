@@ -9,7 +9,7 @@ fn test_serializ() {
         cur: Some(vec!["USD".to_string()]),
         imp: vec![Imp {
             id: "1".to_string(),
-            bidfloor: Some(0.03),
+            bidfloor: Some(Price::from_f64(0.03)),
             banner: Some(Banner {
                 h: Some(250),
                 w: Some(300),
@@ -0,0 +1,305 @@
+use iab::auction::*;
+use iab::currency::RateTable;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+use std::fs;
+use std::path::Path;
+
+fn load_request(filename: &str) -> BidRequest {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/request")
+        .join(filename);
+    let content =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read file {filename}: {e}"));
+    serde_json::from_str(&content).unwrap_or_else(|e| panic!("Failed to parse {filename}: {e}"))
+}
+
+fn banner_request(at: i64) -> BidRequest {
+    BidRequest::builder()
+        .id("auction-1")
+        .at(at)
+        .imp(Imp::banner(300, 250).floor(1.0, "USD"))
+        .imp(Imp::banner(728, 90))
+        .build()
+        .unwrap()
+}
+
+fn response(seat: &str, bids: Vec<BidBuilder>) -> BidResponse {
+    bids.into_iter()
+        .fold(BidResponse::builder("auction-1"), |response, bid| {
+            response.bid(seat, bid)
+        })
+        .build()
+        .unwrap()
+}
+
+fn reasons(outcome: &ImpOutcome) -> Vec<(&str, LossReason)> {
+    outcome
+        .losers
+        .iter()
+        .map(|l| (l.candidate.bid.id.as_str(), l.reason))
+        .collect()
+}
+
+#[test]
+fn test_second_price_plus() {
+    let request = banner_request(2);
+    let responses = [
+        response(
+            "a",
+            vec![Bid::builder("a1", "1", 3.0), Bid::builder("a2", "2", 0.5)],
+        ),
+        response("b", vec![Bid::builder("b1", "1", 2.0)]),
+        response("c", vec![Bid::builder("c1", "1", 0.5)]),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    assert_eq!(outcome.auction_id, "auction-1");
+    assert_eq!(outcome.currency, *"USD");
+
+    let first = &outcome.imps[0];
+    let winner = first.winner.as_ref().unwrap();
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.candidate.seat.as_deref(), Some("a"));
+    assert_eq!(winner.auction_type, AuctionType::SecondPricePlus);
+    assert_eq!(winner.clearing_price, 2.01);
+    assert_eq!(winner.floor.price, 1.0);
+    assert_eq!(
+        reasons(first),
+        [
+            ("b1", LossReason::LostToHigherBid),
+            ("c1", LossReason::BelowAuctionFloor)
+        ]
+    );
+
+    // Without competition or floor, the winner pays the increment.
+    let second = outcome.imp("2").unwrap();
+    let winner = second.winner.as_ref().unwrap();
+    assert_eq!(winner.candidate.bid.id, "a2");
+    assert_eq!(winner.clearing_price, 0.01);
+
+    let outcome = Auction::new()
+        .increment(0.5)
+        .run(&request, &responses[..1])
+        .unwrap();
+    assert_eq!(outcome.imps[0].winner.as_ref().unwrap().clearing_price, 1.5);
+}
+
+#[test]
+fn test_first_price() {
+    let request = banner_request(1);
+    let responses = [
+        response("a", vec![Bid::builder("a1", "1", 2.0)]),
+        response("b", vec![Bid::builder("b1", "1", 2.0)]),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+
+    // Ties go to the bid received first.
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.candidate.response, 0);
+    assert_eq!(winner.auction_type, AuctionType::FirstPrice);
+    assert_eq!(winner.clearing_price, 2.0);
+    assert!(outcome.imps[1].winner.is_none());
+    assert!(outcome.imps[1].losers.is_empty());
+}
+
+#[test]
+fn test_exchange_specific_auction_type() {
+    let request = banner_request(501);
+    let responses = [
+        response("a", vec![Bid::builder("a1", "1", 3.0)]),
+        response("b", vec![Bid::builder("b1", "1", 2.0)]),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+    assert_eq!(winner.auction_type, AuctionType::SecondPricePlus);
+
+    let outcome = Auction::new()
+        .exchange_type(501, AuctionType::FirstPrice)
+        .run(&request, &responses)
+        .unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+    assert_eq!(winner.auction_type, AuctionType::FirstPrice);
+    assert_eq!(winner.clearing_price, 3.0);
+}
+
+#[test]
+fn test_private_auction_deals() {
+    let request = load_request("pmp-with-direct-deal.json");
+    let id = request.id.clone();
+    let response = |seat: &str, bid: BidBuilder| {
+        BidResponse::builder(id.clone())
+            .bid(seat, bid)
+            .build()
+            .unwrap()
+    };
+    let responses = [
+        response(
+            "Agency1",
+            Bid::builder("a1", "1", 3.0).dealid("AB-Agency1-0001"),
+        ),
+        response(
+            "Agency2",
+            Bid::builder("b1", "1", 2.8).dealid("XY-Agency2-0001"),
+        ),
+        response("Agency3", Bid::builder("c1", "1", 10.0)),
+        response(
+            "Agency2",
+            Bid::builder("b2", "1", 4.0).dealid("AB-Agency1-0001"),
+        ),
+        response("Agency1", Bid::builder("a2", "1", 4.0).dealid("ZZ-Unknown")),
+        response(
+            "Agency1",
+            Bid::builder("a3", "1", 2.4).dealid("AB-Agency1-0001"),
+        ),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let imp = &outcome.imps[0];
+    let winner = imp.winner.as_ref().unwrap();
+
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.auction_type, AuctionType::FirstPrice);
+    assert_eq!(winner.clearing_price, 3.0);
+    assert_eq!(winner.floor.price, 2.5);
+    assert_eq!(winner.floor.source, FloorSource::Deal);
+    assert_eq!(
+        reasons(imp),
+        [
+            ("b1", LossReason::LostToHigherBid),
+            ("c1", LossReason::LostToPmpDeal),
+            ("b2", LossReason::BuyerSeatBlocked),
+            ("a2", LossReason::InvalidDealId),
+            ("a3", LossReason::BelowDealFloor),
+        ]
+    );
+}
+
+#[test]
+fn test_deal_auction_type_overrides_request() {
+    let mut request = load_request("pmp-with-direct-deal.json");
+    let deals = request.imp[0].pmp.as_mut().unwrap().deals.as_mut().unwrap();
+    deals[0].at = Some(3);
+    let responses = [BidResponse::builder(request.id.clone())
+        .bid(
+            "Agency1",
+            Bid::builder("a1", "1", 3.0).dealid("AB-Agency1-0001"),
+        )
+        .bid(
+            "Agency2",
+            Bid::builder("b1", "1", 2.8).dealid("XY-Agency2-0001"),
+        )
+        .build()
+        .unwrap()];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+
+    assert_eq!(winner.auction_type, AuctionType::DealPrice);
+    assert_eq!(winner.clearing_price, 2.5);
+}
+
+#[test]
+fn test_invalid_bids() {
+    let request = BidRequest {
+        bseat: Some(vec!["blocked".to_string()]),
+        cur: Some(vec!["USD".to_string()]),
+        ..banner_request(2)
+    };
+    let responses = [
+        BidResponse::builder("other-auction")
+            .bid("a", Bid::builder("a1", "1", 5.0))
+            .build()
+            .unwrap(),
+        response("b", vec![Bid::builder("b1", "3", 5.0)]),
+        response("blocked", vec![Bid::builder("c1", "1", 5.0)]),
+        BidResponse::builder("auction-1")
+            .cur("JPY")
+            .bid("d", Bid::builder("d1", "1", 500.0))
+            .build()
+            .unwrap(),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+
+    assert!(outcome.imps[0].winner.is_none());
+    assert_eq!(
+        reasons(&outcome.imps[0]),
+        [
+            ("a1", LossReason::InvalidAuctionId),
+            ("c1", LossReason::BuyerSeatBlocked),
+            ("d1", LossReason::InvalidBidResponse),
+        ]
+    );
+    assert_eq!(outcome.rejected.len(), 1);
+    assert_eq!(outcome.rejected[0].candidate.bid.id, "b1");
+    assert_eq!(outcome.rejected[0].reason, LossReason::InvalidBidResponse);
+    assert_eq!(outcome.losers().count(), 4);
+    assert_eq!(outcome.winners().count(), 0);
+}
+
+#[test]
+fn test_currency_conversion() {
+    let request = BidRequest {
+        cur: Some(vec!["USD".to_string(), "EUR".to_string()]),
+        ..banner_request(2)
+    };
+    let responses = [
+        BidResponse::builder("auction-1")
+            .cur("EUR")
+            .bid("a", Bid::builder("a1", "1", 2.0))
+            .build()
+            .unwrap(),
+        response("b", vec![Bid::builder("b1", "1", 2.0)]),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+
+    assert_eq!(winner.candidate.bid.id, "b1");
+    assert_eq!(
+        reasons(&outcome.imps[0]),
+        [("a1", LossReason::InvalidBidResponse)]
+    );
+
+    let rates = RateTable::from_csv_str("EUR,USD,1.25").unwrap();
+    let outcome = Auction::new()
+        .converter(&rates)
+        .run(&request, &responses)
+        .unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.candidate.price, 2.5);
+    assert_eq!(winner.clearing_price, 2.01);
+}
+
+#[test]
+fn test_unconvertible_bid() {
+    let request = banner_request(2);
+    let responses = [
+        response("a", vec![Bid::builder("a1", "1", 3.0)]),
+        BidResponse::builder("auction-1")
+            .cur("EUR")
+            .bid("b", Bid::builder("b1", "1", 4.0))
+            .build()
+            .unwrap(),
+        response("c", vec![Bid::builder("c1", "1", 2.0)]),
+    ];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.clearing_price, 2.01);
+    assert_eq!(
+        reasons(&outcome.imps[0]),
+        [
+            ("c1", LossReason::LostToHigherBid),
+            ("b1", LossReason::InvalidBidResponse),
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "avro")]
+
+use apache_avro::{Schema, Writer};
+use iab::avro;
+use iab::openrtb2::*;
+use std::fs;
+use std::path::Path;
+
+fn load<T: serde::de::DeserializeOwned>(dir: &str, filename: &str) -> T {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(dir)
+        .join(filename);
+
+    let content =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read file {filename}: {e}"));
+
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("Failed to deserialize {filename}: {e:?}"))
+}
+
+#[test]
+fn test_request_round_trip() {
+    let requests: Vec<BidRequest> = [
+        "simple-banner.json",
+        "expandable-creative.json",
+        "mobile.json",
+        "multiple-imp.json",
+        "pmp-with-direct-deal.json",
+        "video.json",
+    ]
+    .iter()
+    .map(|f| load("request", f))
+    .collect();
+
+    let file = avro::write_container(
+        &requests,
+        Vec::new(),
+        avro::Codec::Deflate(Default::default()),
+    )
+    .expect("Failed to write requests");
+    let decoded: Vec<BidRequest> =
+        avro::read_container(&file[..]).expect("Failed to read requests");
+
+    assert_eq!(decoded, requests);
+}
+
+#[test]
+fn test_response_round_trip() {
+    let responses: Vec<BidResponse> = [
+        "ad-served-on-win-notice.json",
+        "direct-deal-ad-served-on-win-notice.json",
+        "multiple-imp.json",
+        "native-markup-returned-inline.json",
+        "vast-xml-document-returned-inline.json",
+    ]
+    .iter()
+    .map(|f| load("response", f))
+    .collect();
+
+    let file = avro::write_container(&responses, Vec::new(), avro::Codec::Null)
+        .expect("Failed to write responses");
+    let decoded: Vec<BidResponse> =
+        avro::read_container(&file[..]).expect("Failed to read responses");
+
+    assert_eq!(decoded, responses);
+}
+
+#[test]
+fn test_ext_round_trip() {
+    let request = BidRequest {
+        id: "1".to_string(),
+        imp: vec![Imp {
+            id: "1".to_string(),
+            ext: Some(serde_json::json!({"prebid": {"bidder": {"a": 1}}, "ext": "nested"})),
+            ..Default::default()
+        }],
+        ext: Some(serde_json::json!("plain string")),
+        ..Default::default()
+    };
+
+    let file = avro::write_container(
+        std::slice::from_ref(&request),
+        Vec::new(),
+        avro::Codec::Null,
+    )
+    .unwrap();
+    let decoded: Vec<BidRequest> = avro::read_container(&file[..]).unwrap();
+
+    assert_eq!(decoded, vec![request]);
+}
+
+#[test]
+fn test_schema_documentation_and_defaults() {
+    let schema = serde_json::to_value(avro::schema::<BidRequest>()).unwrap();
+
+    assert_eq!(schema["type"], "record");
+    assert_eq!(schema["name"], "BidRequest");
+    assert_eq!(schema["doc"], "Top-level bid request object.");
+
+    let fields = schema["fields"].as_array().unwrap();
+    let id = fields.iter().find(|f| f["name"] == "id").unwrap();
+    assert_eq!(id["type"], "string");
+    assert!(id.get("default").is_none());
+
+    let tmax = fields.iter().find(|f| f["name"] == "tmax").unwrap();
+    assert_eq!(tmax["type"], serde_json::json!(["null", "long"]));
+    assert_eq!(tmax["default"], serde_json::Value::Null);
+
+    let ext = fields.iter().find(|f| f["name"] == "ext").unwrap();
+    assert_eq!(ext["type"], serde_json::json!(["null", "string"]));
+}
+
+#[test]
+fn test_write_schema() {
+    let path = std::env::temp_dir().join("iab-bid-response.avsc");
+    avro::write_schema::<BidResponse>(&path).expect("Failed to write schema");
+
+    let written = Schema::parse_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written, avro::schema::<BidResponse>());
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_reads_files_from_older_schema() {
+    // Simulate a file written before Bid.dur and Bid.mtype were added.
+    let mut older = serde_json::to_value(avro::schema::<BidResponse>()).unwrap();
+    let bid = &mut older["fields"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|f| f["name"] == "seatbid")
+        .unwrap()["type"][1]["items"]["fields"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|f| f["name"] == "bid")
+        .unwrap()["type"]["items"]["fields"];
+    bid.as_array_mut()
+        .unwrap()
+        .retain(|f| f["name"] != "dur" && f["name"] != "mtype");
+    let older = Schema::parse(&older).unwrap();
+
+    let mut writer = Writer::new(&older, Vec::new()).unwrap();
+    let value = apache_avro::types::Value::try_from(serde_json::json!({
+        "id": "1",
+        "seatbid": [{"bid": [{"id": "a", "impid": "1", "price": 1.5}]}],
+    }))
+    .unwrap()
+    .resolve(&older)
+    .unwrap();
+    writer.append_value(value).unwrap();
+    let file = writer.into_inner().unwrap();
+
+    let decoded: Vec<BidResponse> = avro::read_container(&file[..]).unwrap();
+    let bid = &decoded[0].seatbid.as_ref().unwrap()[0].bid[0];
+
+    assert_eq!(bid.id, "a");
+    assert_eq!(bid.price, 1.5);
+    assert_eq!(bid.dur, None);
+    assert_eq!(bid.mtype, None);
+}
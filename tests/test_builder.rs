@@ -0,0 +1,254 @@
+use iab::openrtb2::*;
+use std::fs;
+use std::path::Path;
+
+fn load<T: serde::de::DeserializeOwned>(dir: &str, filename: &str) -> T {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(dir)
+        .join(filename);
+
+    let content =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read file {filename}: {e}"));
+
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("Failed to deserialize {filename}: {e:?}"))
+}
+
+fn video_request(video: Video) -> BidRequest {
+    BidRequest {
+        id: "1".to_string(),
+        imp: vec![Imp {
+            id: "1".to_string(),
+            video: Some(video),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_builder_matches_simple_banner() {
+    let mut banner: Imp = Imp::banner(300, 250).floor(0.03, "USD").into();
+    banner.banner.as_mut().unwrap().pos = Some(0);
+    banner.bidfloorcur = None;
+
+    let request = BidRequest::builder()
+        .id("80ce30c53c16e6ede735f123ef6e32361bfc7b22")
+        .at(1)
+        .cur(["USD"])
+        .imp(banner)
+        .site(Site {
+            id: Some("102855".to_string()),
+            cat: Some(vec!["IAB3-1".to_string()]),
+            domain: Some("www.foobar.com".to_string()),
+            page: Some("http://www.foobar.com/1234.html".to_string()),
+            publisher: Some(Publisher {
+                id: Some("8953".to_string()),
+                name: Some("foobar.com".to_string()),
+                cat: Some(vec!["IAB3-1".to_string()]),
+                domain: Some("foobar.com".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .user(User {
+            id: Some("55816b39711f9b5acf3b90e313ed29e51665623f".to_string()),
+            ..Default::default()
+        })
+        .build()
+        .expect("Failed to build BidRequest");
+
+    assert_eq!(request, load::<BidRequest>("request", "simple-banner.json"));
+}
+
+#[test]
+fn test_builder_numbers_impressions() {
+    let request = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250))
+        .imp(Imp::video(["video/mp4"]).id("v"))
+        .imp(Imp::native("{}"))
+        .build()
+        .unwrap();
+
+    let ids: Vec<&str> = request.imp.iter().map(|imp| imp.id.as_str()).collect();
+    assert_eq!(ids, ["1", "v", "3"]);
+}
+
+#[test]
+fn test_fixtures_are_valid() {
+    for file in [
+        "simple-banner.json",
+        "expandable-creative.json",
+        "mobile.json",
+        "multiple-imp.json",
+        "pmp-with-direct-deal.json",
+        "video.json",
+    ] {
+        let request: BidRequest = load("request", file);
+        assert_eq!(request.validate(), Ok(()), "{file}");
+    }
+
+    for file in [
+        "ad-served-on-win-notice.json",
+        "direct-deal-ad-served-on-win-notice.json",
+        "multiple-imp.json",
+        "native-markup-returned-inline.json",
+        "vast-xml-document-returned-inline.json",
+    ] {
+        let response: BidResponse = load("response", file);
+        assert_eq!(response.validate(), Ok(()), "{file}");
+    }
+}
+
+#[test]
+fn test_required_fields() {
+    let err = BidRequest::builder().imp(Imp::banner(300, 250)).build();
+    assert_eq!(
+        err,
+        Err(ValidationError::Missing {
+            path: "id".to_string()
+        })
+    );
+
+    let err = BidRequest::builder().id("1").build();
+    assert_eq!(
+        err,
+        Err(ValidationError::Missing {
+            path: "imp".to_string()
+        })
+    );
+
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(Imp::video(Vec::<String>::new()))
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "imp[0].video.mimes is required");
+
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(ImpBuilder::default())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "imp[0]: at least one of banner, video, audio or native is required"
+    );
+}
+
+#[test]
+fn test_mutual_exclusions() {
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250))
+        .site(Site::default())
+        .app(App::default())
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "site and app are mutually exclusive");
+
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250))
+        .wseat(["a"])
+        .bseat(["b"])
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "wseat and bseat are mutually exclusive");
+
+    let err = video_request(Video {
+        mimes: vec!["video/mp4".to_string()],
+        rqddurs: Some(vec![15, 30]),
+        maxduration: Some(30),
+        ..Default::default()
+    })
+    .validate()
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ValidationError::MutuallyExclusive {
+            path: "imp[0].video".to_string(),
+            fields: ("rqddurs", "maxduration"),
+        }
+    );
+}
+
+#[test]
+fn test_invalid_values() {
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250).floor(1.0, "usd"))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "imp[0].bidfloorcur: must be an ISO-4217 alpha code"
+    );
+
+    // Validation checks the format only, so new currencies are accepted.
+    assert!(BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250).floor(1.0, "XCG"))
+        .build()
+        .is_ok());
+
+    let err = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250).id("a"))
+        .imp(Imp::banner(728, 90).id("a"))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "imp[1].id: must be unique within the request"
+    );
+
+    let request = BidRequest::builder()
+        .id("1")
+        .imp(Imp::banner(300, 250))
+        .at(501)
+        .build();
+    assert!(request.is_ok());
+}
+
+#[test]
+fn test_response_builder() {
+    let response = BidResponse::builder("1234567890")
+        .bidid("abc1123")
+        .cur("USD")
+        .bid(
+            "512",
+            Bid::builder("1", "102", 9.43)
+                .nurl("http://adserver.com/winnotice?impid=102")
+                .iurl("http://adserver.com/pathtosampleimage")
+                .adomain(["advertiserdomain.com"])
+                .cid("campaign111")
+                .crid("creative112")
+                .attr([1, 2, 3, 4, 5, 6, 7, 12]),
+        )
+        .build()
+        .expect("Failed to build BidResponse");
+
+    assert_eq!(
+        response,
+        load::<BidResponse>("response", "ad-served-on-win-notice.json")
+    );
+
+    let response = BidResponse::builder("1")
+        .bid("a", Bid::builder("1", "1", 1.0))
+        .bid("b", Bid::builder("2", "1", 2.0))
+        .bid("a", Bid::builder("3", "2", 3.0))
+        .build()
+        .unwrap();
+    let seatbids = response.seatbid.unwrap();
+    assert_eq!(seatbids.len(), 2);
+    assert_eq!(seatbids[0].bid.len(), 2);
+
+    let err = BidResponse::builder("1")
+        .bid("a", Bid::builder("1", "", -1.0))
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "seatbid[0].bid[0].impid is required");
+}
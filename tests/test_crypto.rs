@@ -0,0 +1,194 @@
+use iab::auction::*;
+use iab::crypto::*;
+use iab::openrtb2::*;
+use std::time::{Duration, SystemTime};
+
+mod common;
+use common::price;
+
+fn keys() -> PriceKeys {
+    PriceKeys::from_base64(
+        "skU7Ax_NL5pPAFyKdkfZjZz2-VhIN8bjj1rVFOaJ_5o=",
+        "arO23ykdNqUQ5LEoQ0FVmPkBd7xB5CO89PDZlSjpFxo=",
+    )
+    .unwrap()
+}
+
+const IV: [u8; IV_LEN] = *b"abc123def456ghi7";
+
+#[test]
+fn test_reference_vectors() {
+    let keys = keys();
+
+    assert_eq!(
+        encrypt_price(&keys, price(0.0001), IV),
+        "YWJjMTIzZGVmNDU2Z2hpN7fhCuPemCce_6msaw"
+    );
+    assert_eq!(
+        decrypt_price(&keys, "YWJjMTIzZGVmNDU2Z2hpN7fhCuPemCce_6msaw").unwrap(),
+        0.0001
+    );
+    assert_eq!(
+        decrypt_price(&keys, "YWJjMTIzZGVmNDU2Z2hpN7fhCuPemC32prpWWw").unwrap(),
+        0.0027
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let keys = PriceKeys::new(b"encryption".to_vec(), b"integrity".to_vec());
+
+    for value in [0.0, 0.01, 1.25, 3.456789, 1234.5] {
+        let encrypted = encrypt_price(&keys, price(value), IV);
+        assert_eq!(encrypted.len(), 38);
+        assert_eq!(decrypt_price(&keys, &encrypted).unwrap(), value);
+        assert_eq!(
+            decrypt_price(&keys, &format!("{encrypted}==")).unwrap(),
+            value
+        );
+    }
+    let encrypted = encrypt_price(&keys, price(1.0), [0; IV_LEN]);
+    assert_ne!(encrypted, encrypt_price(&keys, price(1.0), [1; IV_LEN]));
+    assert_eq!(
+        decrypt_price(&keys, &encrypt_price(&keys, price(-1.0), IV)).unwrap(),
+        0.0
+    );
+}
+
+#[test]
+fn test_invalid_input() {
+    let keys = keys();
+    let encrypted = encrypt_price(&keys, price(2.5), IV);
+
+    let other = PriceKeys::new(b"encryption".to_vec(), b"integrity".to_vec());
+    assert_eq!(
+        decrypt_price(&other, &encrypted),
+        Err(PriceError::IntegrityCheckFailed)
+    );
+
+    // Changing a character of the encrypted price breaks its signature.
+    let mut tampered = encrypted.into_bytes();
+    tampered[20] = if tampered[20] == b'A' { b'B' } else { b'A' };
+    let tampered = String::from_utf8(tampered).unwrap();
+    assert_eq!(
+        decrypt_price(&keys, &tampered),
+        Err(PriceError::IntegrityCheckFailed)
+    );
+
+    assert_eq!(
+        decrypt_price(&keys, "YWJj+/"),
+        Err(PriceError::InvalidEncoding)
+    );
+    assert_eq!(
+        decrypt_price(&keys, "YWJjMTIz"),
+        Err(PriceError::InvalidLength(6))
+    );
+    assert!(PriceKeys::from_base64("not base64!", "").is_err());
+    assert_eq!(format!("{keys:?}"), "PriceKeys { .. }");
+}
+
+#[test]
+fn test_encrypted_price_macro() {
+    let request = BidRequest::builder()
+        .id("1")
+        .at(1)
+        .imp(Imp::banner(300, 250))
+        .build()
+        .unwrap();
+    let response = BidResponse::builder("1")
+        .bid(
+            "seat",
+            Bid::builder("a", "1", 1.5)
+                .nurl("https://dsp.com/win?p=${AUCTION_PRICE}&c=${AUCTION_CURRENCY}"),
+        )
+        .build()
+        .unwrap();
+    let outcome = Auction::new().run(&request, &[response]).unwrap();
+    let winner = outcome.winners().next().unwrap();
+
+    let keys = keys();
+    let mut bid = winner.candidate.bid.clone();
+    AuctionMacros::for_winner(&request, &outcome, winner)
+        .encrypt_price(&keys, IV)
+        .apply(&mut bid);
+
+    let nurl = bid.nurl.unwrap();
+    let encrypted = nurl
+        .strip_prefix("https://dsp.com/win?p=")
+        .and_then(|rest| rest.strip_suffix("&c=USD"))
+        .unwrap();
+    assert_eq!(encrypted, encrypt_price(&keys, price(1.5), IV));
+    assert_eq!(decrypt_price(&keys, encrypted).unwrap(), 1.5);
+}
+
+const WINDOW: Duration = Duration::from_secs(300);
+
+fn signed_bid(signer: &NoticeSigner, time: SystemTime) -> Bid {
+    let mut bid: Bid = Bid::builder("bid 1", "1", 2.0)
+        .nurl("https://dsp.com/win?p=1.5")
+        .burl("https://dsp.com/bill")
+        .into();
+    signer.sign_bid(&mut bid, "auction-1", price(1.5), time);
+    bid
+}
+
+#[test]
+fn test_signed_notices() {
+    let now = SystemTime::now();
+    let signer = NoticeSigner::new(b"secret".to_vec());
+    let bid = signed_bid(&signer, now);
+
+    let nurl = bid.nurl.unwrap();
+    assert!(nurl.starts_with("https://dsp.com/win?p=1.5&sig_auction=auction-1&sig_bid=bid%201&"));
+    let burl = bid.burl.unwrap();
+    assert!(burl.starts_with("https://dsp.com/bill?sig_auction=auction-1&"));
+
+    let mut verifier = NoticeVerifier::new(b"secret".to_vec(), WINDOW);
+    let notice = verifier
+        .verify(&nurl, now + Duration::from_secs(60))
+        .unwrap();
+    assert_eq!(
+        notice,
+        SignedNotice::new("auction-1", "bid 1", "1", price(1.5), now)
+    );
+    assert_eq!(verifier.verify(&burl, now).unwrap(), notice);
+
+    let mut other = NoticeVerifier::new(b"other".to_vec(), WINDOW);
+    assert_eq!(other.verify(&nurl, now), Err(NoticeError::InvalidSignature));
+    assert_eq!(format!("{signer:?}"), "NoticeSigner { .. }");
+}
+
+#[test]
+fn test_rejected_notices() {
+    let now = SystemTime::now();
+    let signer = NoticeSigner::new(b"secret".to_vec());
+    let nurl = signed_bid(&signer, now).nurl.unwrap();
+    let mut verifier = NoticeVerifier::new(b"secret".to_vec(), WINDOW);
+
+    let tampered = nurl.replace("sig_price=1.5", "sig_price=0.5");
+    assert_eq!(
+        verifier.verify(&tampered, now),
+        Err(NoticeError::InvalidSignature)
+    );
+    let tampered = nurl.replace("?p=1.5", "?p=9.5");
+    assert_eq!(
+        verifier.verify(&tampered, now),
+        Err(NoticeError::InvalidSignature)
+    );
+    assert_eq!(
+        verifier.verify("https://dsp.com/win?p=1.5", now),
+        Err(NoticeError::Unsigned)
+    );
+
+    let late = now + WINDOW + Duration::from_secs(1);
+    assert_eq!(verifier.verify(&nurl, late), Err(NoticeError::Expired));
+    let early = now - WINDOW - Duration::from_secs(1);
+    assert_eq!(verifier.verify(&nurl, early), Err(NoticeError::Expired));
+
+    assert!(verifier.verify(&nurl, now).is_ok());
+    assert_eq!(verifier.verify(&nurl, now), Err(NoticeError::Replayed));
+    assert_eq!(
+        verifier.verify(&nurl, now + WINDOW),
+        Err(NoticeError::Replayed)
+    );
+}
@@ -0,0 +1,189 @@
+use iab::currency::*;
+use iab::openrtb2::types::CurrencyCode;
+use iab::openrtb2::*;
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::price;
+
+fn path(filename: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(filename)
+}
+
+fn load<T: serde::de::DeserializeOwned>(filename: &str) -> T {
+    let content = fs::read_to_string(path(filename))
+        .unwrap_or_else(|e| panic!("Failed to read file {filename}: {e}"));
+    serde_json::from_str(&content).unwrap_or_else(|e| panic!("Failed to parse {filename}: {e}"))
+}
+
+fn code(code: &str) -> CurrencyCode {
+    CurrencyCode::new(code).unwrap()
+}
+
+#[test]
+fn test_load_rate_tables() {
+    let json = RateTable::load(path("tests/rates/rates.json")).unwrap();
+    let csv = RateTable::load(path("tests/rates/rates.csv")).unwrap();
+
+    assert_eq!(json.len(), 3);
+    assert_eq!(csv.len(), 3);
+
+    for rates in [&json, &csv] {
+        let convert = |amount: f64, from: &str, to: &str| {
+            rates
+                .convert(price(amount), &code(from), &code(to), Rounding::Nearest(4))
+                .unwrap()
+        };
+
+        assert_eq!(convert(1.0, "USD", "EUR"), 0.8);
+        assert_eq!(convert(1.0, "EUR", "USD"), 1.25);
+        assert_eq!(convert(1.0, "GBP", "JPY"), 200.0);
+        assert_eq!(convert(3.0, "JPY", "JPY"), 3.0);
+    }
+
+    assert!(json.rate(&code("USD"), &code("CHF")).is_none());
+}
+
+#[test]
+fn test_convert_request_floors() {
+    let rates = RateTable::load(path("tests/rates/rates.json")).unwrap();
+    let mut request: BidRequest = load("tests/request/pmp-with-direct-deal.json");
+
+    request
+        .convert_floors(&rates, &code("EUR"), Rounding::Up(2))
+        .unwrap();
+
+    let imp = &request.imp[0];
+    assert_eq!(imp.bidfloor.unwrap(), 0.03);
+    assert_eq!(imp.bidfloorcur.as_deref(), Some("EUR"));
+
+    let deals = imp.pmp.as_ref().unwrap().deals.as_ref().unwrap();
+    assert_eq!(deals[0].bidfloor.unwrap(), 2.0);
+    assert_eq!(deals[0].bidfloorcur.as_deref(), Some("EUR"));
+    assert_eq!(deals[1].bidfloor.unwrap(), 1.6);
+    assert_eq!(deals[1].bidfloorcur.as_deref(), Some("EUR"));
+}
+
+#[test]
+fn test_convert_media_floors() {
+    let rates = RateTable::load(path("tests/rates/rates.csv")).unwrap();
+    let mut request = BidRequest::builder()
+        .id("1")
+        .imp(
+            Imp::video(["video/mp4"])
+                .floor(10.0, "EUR")
+                .with_video(Video {
+                    mimes: vec!["video/mp4".to_string()],
+                    mincpmpersec: Some(price(0.5)),
+                    durfloors: Some(vec![DurFloors {
+                        maxdur: Some(15),
+                        bidfloor: Some(price(8.0)),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }),
+        )
+        .build()
+        .unwrap();
+
+    request
+        .convert_floors(&rates, &code("USD"), Rounding::Nearest(2))
+        .unwrap();
+
+    let imp = &request.imp[0];
+    let video = imp.video.as_ref().unwrap();
+    assert_eq!(imp.bidfloor.unwrap(), 12.5);
+    assert_eq!(video.mincpmpersec.unwrap(), 0.63);
+    assert_eq!(video.durfloors.as_ref().unwrap()[0].bidfloor.unwrap(), 10.0);
+    assert_eq!(imp.bidfloorcur.as_deref(), Some("USD"));
+}
+
+#[test]
+fn test_convert_response_prices() {
+    let rates = RateTable::load(path("tests/rates/rates.csv")).unwrap();
+    let mut response: BidResponse = load("tests/response/multiple-imp.json");
+
+    response
+        .convert_prices(&rates, &code("JPY"), Rounding::Down(0))
+        .unwrap();
+
+    assert_eq!(response.cur.as_deref(), Some("JPY"));
+    let prices: Vec<Price> = response
+        .seatbid
+        .iter()
+        .flatten()
+        .flat_map(|s| s.bid.iter().map(|b| b.price))
+        .collect();
+    assert_eq!(prices, [15.0, 30.0, 45.0]);
+}
+
+#[test]
+fn test_unknown_rate_leaves_object_untouched() {
+    let rates = RateTable::from_csv_str("USD,EUR,0.8").unwrap();
+    let mut request: BidRequest = load("tests/request/pmp-with-direct-deal.json");
+    let original = request.clone();
+
+    let error = request
+        .convert_floors(&rates, &code("CHF"), Rounding::Exact)
+        .unwrap_err();
+
+    assert!(matches!(error, CurrencyError::UnknownRate { .. }));
+    assert_eq!(error.to_string(), "no exchange rate from USD to CHF");
+    assert_eq!(request, original);
+}
+
+#[test]
+fn test_cross_rates_are_deterministic() {
+    // EUR to CHF is 2 through USD and 4 through GBP.
+    let csv = "USD,EUR,0.5\nUSD,CHF,1\nGBP,EUR,1\nGBP,CHF,4";
+    let mut rates = RateTable::from_csv_str(csv).unwrap();
+    for _ in 0..10 {
+        // Without a base, the first currency in alphabetical order with a
+        // path is the intermediate.
+        let converted = rates.convert(price(1.0), &code("EUR"), &code("CHF"), Rounding::Exact);
+        assert_eq!(converted.unwrap(), 4.0);
+    }
+
+    rates.set_base(code("USD"));
+    assert_eq!(rates.base(), Some(&code("USD")));
+    let converted = rates.convert(price(1.0), &code("EUR"), &code("CHF"), Rounding::Exact);
+    assert_eq!(converted.unwrap(), 2.0);
+
+    let json = RateTable::from_json_str(r#"{"base": "USD", "rates": {"EUR": 0.8}}"#).unwrap();
+    assert_eq!(json.base(), Some(&code("USD")));
+}
+
+#[test]
+fn test_invalid_rates() {
+    let error = RateTable::from_csv_str("USD,EUR,0.8\nUSD,GBP\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid rates on line 2: expected from,to,rate"
+    );
+
+    let error = RateTable::from_csv_str("USD,EUR,abc").unwrap_err();
+    assert!(matches!(error, CurrencyError::Parse { line: 1, .. }));
+
+    let error = RateTable::from_csv_str("USD,EUR,-1").unwrap_err();
+    assert!(matches!(error, CurrencyError::InvalidRate { .. }));
+
+    let error = RateTable::from_json_str(r#"{"base": "XXY", "rates": {}}"#).unwrap_err();
+    assert!(matches!(error, CurrencyError::InvalidCurrency(_)));
+
+    let error = RateTable::from_json_str(r#"{"rates": {"EUR": 0.8}}"#).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid rates: missing \"base\" currency"
+    );
+}
+
+#[test]
+fn test_rounding() {
+    assert_eq!(Rounding::Up(2).apply(price(1.001)), 1.01);
+    assert_eq!(Rounding::Up(2).apply(price(1.1)), 1.1);
+    assert_eq!(Rounding::Down(2).apply(price(1.019)), 1.01);
+    assert_eq!(Rounding::Nearest(2).apply(price(1.005)), 1.01);
+    assert_eq!(Rounding::Nearest(0).apply(price(2.5)), 3.0);
+    assert_eq!(Rounding::Exact.apply(price(1.23456)), 1.23456);
+}
@@ -0,0 +1,256 @@
+use iab::auction::*;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+
+mod common;
+use common::price;
+
+fn deal(id: &str, guar: bool) -> Deal {
+    Deal {
+        id: id.to_string(),
+        guar: Some(guar as i64),
+        ..Default::default()
+    }
+}
+
+fn imp(private_auction: bool) -> Imp {
+    Imp::banner(300, 250)
+        .deal(Deal {
+            wseat: Some(vec!["seat-1".to_string()]),
+            wadomain: Some(vec!["ford.com".to_string(), "kia.com".to_string()]),
+            ..deal("restricted", false)
+        })
+        .deal(Deal {
+            bidfloor: Some(price(2.0)),
+            ..deal("guaranteed", true)
+        })
+        .deal(deal("preferred", false))
+        .private_auction(private_auction)
+        .into()
+}
+
+fn bid(dealid: Option<&str>, adomain: &[&str]) -> Bid {
+    let bid = Bid::builder("1", "1", 1.0).adomain(adomain.iter().copied());
+    match dealid {
+        Some(dealid) => bid.dealid(dealid).into(),
+        None => bid.into(),
+    }
+}
+
+#[test]
+fn test_check_deal() {
+    let private = imp(true);
+    let imp = imp(false);
+
+    let deal = check_deal(
+        &imp,
+        &bid(Some("restricted"), &["Ford.com"]),
+        Some("seat-1"),
+    );
+    assert_eq!(deal.unwrap().unwrap().id, "restricted");
+    let deal = check_deal(&imp, &bid(Some("guaranteed"), &[]), None);
+    assert_eq!(deal.unwrap().unwrap().id, "guaranteed");
+    assert_eq!(check_deal(&imp, &bid(None, &[]), None), Ok(None));
+
+    assert_eq!(
+        check_deal(&imp, &bid(Some("unknown"), &[]), Some("seat-1")),
+        Err(DealRejection::UnknownDeal)
+    );
+    assert_eq!(
+        check_deal(
+            &imp,
+            &bid(Some("restricted"), &["ford.com"]),
+            Some("seat-2")
+        ),
+        Err(DealRejection::SeatNotAllowed)
+    );
+    assert_eq!(
+        check_deal(&imp, &bid(Some("restricted"), &["ford.com"]), None),
+        Err(DealRejection::SeatNotAllowed)
+    );
+    assert_eq!(
+        check_deal(
+            &imp,
+            &bid(Some("restricted"), &["ford.com", "vw.com"]),
+            Some("seat-1")
+        ),
+        Err(DealRejection::AdvertiserNotAllowed)
+    );
+    assert_eq!(
+        check_deal(&imp, &bid(Some("restricted"), &[]), Some("seat-1")),
+        Err(DealRejection::AdvertiserNotAllowed)
+    );
+
+    // Advertiser domains match as they do in badv.
+    let deal = check_deal(
+        &imp,
+        &bid(
+            Some("restricted"),
+            &["https://www.ford.com/", "shop.kia.com"],
+        ),
+        Some("seat-1"),
+    );
+    assert_eq!(deal.unwrap().unwrap().id, "restricted");
+    for adomain in ["ford.com.au", "kia.co.kr"] {
+        assert_eq!(
+            check_deal(&imp, &bid(Some("restricted"), &[adomain]), Some("seat-1")),
+            Err(DealRejection::AdvertiserNotAllowed),
+            "{adomain}"
+        );
+    }
+
+    // An allowed domain admits its subdomains, never its parent domain or
+    // the other domains under it.
+    let brand: Imp = Imp::banner(300, 250)
+        .deal(Deal {
+            wadomain: Some(vec![
+                "brand.github.io".to_string(),
+                "brand.com.pl".to_string(),
+            ]),
+            id: "brand".to_string(),
+            ..Default::default()
+        })
+        .into();
+    for adomain in ["brand.github.io", "www.brand.com.pl"] {
+        let admitted = check_deal(&brand, &bid(Some("brand"), &[adomain]), None);
+        assert_eq!(admitted.unwrap().unwrap().id, "brand", "{adomain}");
+    }
+    for adomain in ["other.github.io", "github.io", "allegro.com.pl", "com.pl"] {
+        assert_eq!(
+            check_deal(&brand, &bid(Some("brand"), &[adomain]), None),
+            Err(DealRejection::AdvertiserNotAllowed),
+            "{adomain}"
+        );
+    }
+    assert_eq!(
+        check_deal(&private, &bid(None, &[]), None),
+        Err(DealRejection::PrivateAuction)
+    );
+
+    // Without a PMP every bid on a deal is unknown.
+    let open: Imp = Imp::banner(300, 250).into();
+    assert_eq!(
+        check_deal(&open, &bid(Some("preferred"), &[]), None),
+        Err(DealRejection::UnknownDeal)
+    );
+}
+
+#[test]
+fn test_rejection_loss_reasons() {
+    assert_eq!(
+        DealRejection::UnknownDeal.loss_reason(),
+        LossReason::InvalidDealId
+    );
+    assert_eq!(
+        DealRejection::SeatNotAllowed.loss_reason(),
+        LossReason::BuyerSeatBlocked
+    );
+    assert_eq!(
+        DealRejection::AdvertiserNotAllowed.loss_reason(),
+        LossReason::NotAllowedInPmpDeal
+    );
+    assert_eq!(
+        DealRejection::PrivateAuction.loss_reason(),
+        LossReason::LostToPmpDeal
+    );
+}
+
+#[test]
+fn test_deal_priority() {
+    let guaranteed = deal("guaranteed", true);
+    let preferred = deal("preferred", false);
+    assert_eq!(DealTier::of(Some(&guaranteed)), DealTier::Guaranteed);
+    assert_eq!(DealTier::of(Some(&preferred)), DealTier::Deal);
+    assert_eq!(DealTier::of(None), DealTier::Open);
+
+    let bids = [
+        ("open", DealTier::Open, price(5.0)),
+        ("deal", DealTier::Deal, price(4.0)),
+        ("guaranteed", DealTier::Guaranteed, price(1.0)),
+        ("open-2", DealTier::Open, price(4.0)),
+    ];
+    let ranked = |priority: DealPriority| {
+        let mut bids = bids;
+        priority.rank_by(&mut bids, |(_, tier, price)| (*tier, *price));
+        bids.map(|b| b.0)
+    };
+
+    assert_eq!(
+        ranked(DealPriority::new()),
+        ["guaranteed", "open", "deal", "open-2"]
+    );
+    assert_eq!(
+        ranked(DealPriority::new().deals_first(true)),
+        ["guaranteed", "deal", "open", "open-2"]
+    );
+    assert_eq!(
+        ranked(DealPriority::new().guaranteed_first(false)),
+        ["open", "deal", "open-2", "guaranteed"]
+    );
+    assert_eq!(
+        ranked(DealPriority::price_only().deals_first(true)),
+        ["deal", "guaranteed", "open", "open-2"]
+    );
+}
+
+#[test]
+fn test_auction_prioritizes_guaranteed_deals() {
+    let request = BidRequest::builder()
+        .id("auction-1")
+        .at(2)
+        .imp(imp(false))
+        .build()
+        .unwrap();
+    let responses = [BidResponse::builder("auction-1")
+        .bid("seat-1", Bid::builder("open", "1", 10.0))
+        .bid(
+            "seat-2",
+            Bid::builder("guaranteed", "1", 3.0).dealid("guaranteed"),
+        )
+        .bid(
+            "seat-3",
+            Bid::builder("guaranteed-2", "1", 2.5).dealid("guaranteed"),
+        )
+        .bid(
+            "seat-4",
+            Bid::builder("preferred", "1", 8.0).dealid("preferred"),
+        )
+        .bid(
+            "seat-2",
+            Bid::builder("restricted", "1", 9.0)
+                .dealid("restricted")
+                .adomain(["ford.com"]),
+        )
+        .build()
+        .unwrap()];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let imp = &outcome.imps[0];
+    let winner = imp.winner.as_ref().unwrap();
+
+    // Only the other guaranteed bid sets the second price.
+    assert_eq!(winner.candidate.bid.id, "guaranteed");
+    assert_eq!(winner.clearing_price, 2.51);
+    let reasons: Vec<_> = imp
+        .losers
+        .iter()
+        .map(|l| (l.candidate.bid.id.as_str(), l.reason))
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            ("guaranteed-2", LossReason::LostToHigherBid),
+            ("open", LossReason::LostToPmpDeal),
+            ("preferred", LossReason::LostToPmpDeal),
+            ("restricted", LossReason::BuyerSeatBlocked),
+        ]
+    );
+
+    let outcome = Auction::new()
+        .deal_priority(DealPriority::price_only())
+        .run(&request, &responses)
+        .unwrap();
+    let winner = outcome.imps[0].winner.as_ref().unwrap();
+    assert_eq!(winner.candidate.bid.id, "open");
+    assert_eq!(winner.clearing_price, 8.01);
+}
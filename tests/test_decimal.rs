@@ -0,0 +1,170 @@
+#![cfg(feature = "decimal")]
+
+use iab::currency::*;
+use iab::money::*;
+use iab::openrtb2::types::CurrencyCode;
+use iab::openrtb2::*;
+use std::fs;
+use std::path::Path;
+
+fn load(path: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+}
+
+fn usd() -> CurrencyCode {
+    CurrencyCode::new("USD").unwrap()
+}
+
+#[test]
+fn test_prices_are_exact() {
+    let request: BidRequest =
+        serde_json::from_str(&load("tests/request/simple-banner.json")).unwrap();
+    let bidfloor = request.imp[0].bidfloor.unwrap();
+
+    assert_eq!(bidfloor.value(), "0.03".parse::<Decimal>().unwrap());
+    assert_eq!(bidfloor.to_string(), "0.03");
+
+    let response: BidResponse =
+        serde_json::from_str(&load("tests/response/ad-served-on-win-notice.json")).unwrap();
+    let price = response.seatbid.unwrap()[0].bid[0].price;
+
+    assert_eq!(price.to_string(), "9.43");
+    assert_eq!(price, 9.43);
+}
+
+#[test]
+fn test_prices_round_trip() {
+    let content = load("tests/response/multiple-imp.json");
+    let response: BidResponse = serde_json::from_str(&content).unwrap();
+
+    let serialized = serde_json::to_string(&response).unwrap();
+    let deserialized: BidResponse = serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(response, deserialized);
+
+    let integer: Bid = serde_json::from_str(r#"{"id": "1", "impid": "1", "price": 2}"#).unwrap();
+    assert_eq!(integer.price, Cpm::from(2));
+    assert_eq!(serde_json::to_value(&integer).unwrap()["price"], 2.0);
+}
+
+#[test]
+fn test_arithmetic() {
+    assert_ne!(0.1 + 0.2, 0.3);
+    assert_eq!(Cpm::from(0.1) + Cpm::from(0.2), Cpm::from(0.3));
+    assert_eq!(Cpm::from(1.5) - Cpm::from(0.25), Cpm::from(1.25));
+
+    let total: Cpm = [0.1, 0.1, 0.1].into_iter().map(Cpm::from).sum();
+    assert_eq!(total, Cpm::from(0.3));
+
+    assert!(Cpm::from(2.5) > 2.0);
+    assert!(Cpm::from(-0.01).is_sign_negative());
+    assert!(!Cpm::ZERO.is_sign_negative());
+}
+
+#[test]
+fn test_invalid_prices() {
+    let err =
+        serde_json::from_str::<Bid>(r#"{"id": "1", "impid": "1", "price": 1e30}"#).unwrap_err();
+    assert!(err.to_string().contains("invalid value"), "{err}");
+
+    assert_eq!(Cpm::try_from_f64(f64::NAN), None);
+    assert_eq!(Cpm::try_from_f64(f64::INFINITY), None);
+    assert_eq!(Cpm::try_from_f64(1e30), None);
+    assert_eq!(Cpm::try_from_f64(0.03), Some(Cpm::from(0.03)));
+    assert!(Cpm::ZERO.partial_cmp(&f64::INFINITY).is_none());
+    assert!(std::panic::catch_unwind(|| Cpm::from(f64::NAN)).is_err());
+}
+
+#[test]
+fn test_overflow() {
+    let max = Cpm::new(Decimal::MAX);
+    assert_eq!(max.checked_add(Cpm::from(1)), None);
+    assert_eq!(Cpm::new(Decimal::MIN).checked_sub(Cpm::from(1)), None);
+    assert_eq!(max.checked_mul(Decimal::TWO), None);
+    assert_eq!(Cpm::from(2).checked_mul(Decimal::TWO), Some(Cpm::from(4)));
+
+    // Operators saturate instead of panicking.
+    assert_eq!(max + Cpm::from(1), max);
+    assert_eq!(max * Decimal::TWO, max);
+    assert_eq!([max, max].into_iter().sum::<Cpm>(), max);
+
+    let amount = Money::new(Decimal::MAX, usd());
+    assert_eq!(amount.checked_add(&amount), Err(MoneyError::Overflow));
+}
+
+#[test]
+fn test_conversion_overflow() {
+    let code = |code: &str| CurrencyCode::new(code).unwrap();
+    let rates =
+        RateTable::from_json_str(r#"{"base": "USD", "rates": {"EUR": 1e-20, "JPY": 1e21}}"#)
+            .unwrap();
+
+    // The cross rate does not fit a decimal, so it is unknown.
+    assert_eq!(rates.rate(&code("EUR"), &code("JPY")), None);
+    assert!(matches!(
+        rates.convert(Cpm::from(1), &code("EUR"), &code("JPY"), Rounding::Exact),
+        Err(CurrencyError::UnknownRate { .. })
+    ));
+
+    let price = Cpm::new(Decimal::MAX / Decimal::TEN);
+    assert!(matches!(
+        rates.convert(price, &code("USD"), &code("JPY"), Rounding::Exact),
+        Err(CurrencyError::Overflow { .. })
+    ));
+}
+
+#[test]
+fn test_money_currency_mismatch() {
+    let dollars = Money::new(Decimal::ONE, usd());
+    let euros = Money::new(Decimal::ONE, CurrencyCode::new("EUR").unwrap());
+
+    assert_eq!(
+        dollars.checked_add(&euros),
+        Err(MoneyError::CurrencyMismatch {
+            left: usd(),
+            right: CurrencyCode::new("EUR").unwrap(),
+        })
+    );
+    assert_eq!(Money::sum([]), Err(MoneyError::Empty));
+    assert_eq!(
+        Money::sum([&dollars, &dollars]).unwrap(),
+        Money::new(Decimal::TWO, usd())
+    );
+}
+
+#[test]
+fn test_rounding_to_minor_units() {
+    let amount: Decimal = "1234.5675".parse().unwrap();
+    let round = |code: &str| {
+        Money::new(amount, CurrencyCode::new(code).unwrap())
+            .round(RoundingStrategy::MidpointNearestEven)
+            .amount()
+            .to_string()
+    };
+
+    assert_eq!(round("USD"), "1234.57");
+    assert_eq!(round("JPY"), "1235");
+    assert_eq!(round("BHD"), "1234.568");
+
+    let cost = Cpm::from(2.5).cost(1001, usd());
+    assert_eq!(cost.amount().to_string(), "2.5025");
+    assert_eq!(
+        cost.round(RoundingStrategy::MidpointNearestEven)
+            .amount()
+            .to_string(),
+        "2.50"
+    );
+    assert_eq!(
+        cost.round(RoundingStrategy::MidpointAwayFromZero)
+            .amount()
+            .to_string(),
+        "2.50"
+    );
+    assert_eq!(
+        cost.round(RoundingStrategy::AwayFromZero)
+            .amount()
+            .to_string(),
+        "2.51"
+    );
+}
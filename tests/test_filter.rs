@@ -0,0 +1,313 @@
+use iab::auction::*;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+
+fn request() -> BidRequest {
+    let banner = Banner {
+        w: Some(300),
+        h: Some(250),
+        btype: Some(vec![3]),
+        battr: Some(vec![1, 2]),
+        ..Default::default()
+    };
+    let video = Video {
+        mimes: vec!["video/mp4".to_string()],
+        battr: Some(vec![6]),
+        ..Default::default()
+    };
+    BidRequest {
+        bseat: Some(vec!["blocked-seat".to_string()]),
+        badv: Some(vec!["ford.com".to_string(), "KIA.com".to_string()]),
+        bapp: Some(vec!["com.blocked.app".to_string()]),
+        bcat: Some(vec!["IAB1".to_string(), "IAB7-39".to_string()]),
+        ..BidRequest::builder()
+            .id("1")
+            .imp(Imp::banner(300, 250).with_banner(banner).with_video(video))
+            .build()
+            .unwrap()
+    }
+}
+
+fn check(request: &BidRequest, bid: BidBuilder) -> Vec<FilterRejection> {
+    BidFilter::new(request).check(Some("seat"), &bid.into())
+}
+
+#[test]
+fn test_seats() {
+    let request = BidRequest {
+        wseat: Some(vec!["seat".to_string(), "blocked-seat".to_string()]),
+        ..request()
+    };
+    let filter = BidFilter::new(&request);
+    let bid: Bid = Bid::builder("1", "1", 1.0).into();
+
+    assert!(filter.check(Some("seat"), &bid).is_empty());
+    assert_eq!(
+        filter.check(Some("blocked-seat"), &bid),
+        [FilterRejection::SeatBlocked]
+    );
+    assert_eq!(
+        filter.check(Some("other"), &bid),
+        [FilterRejection::SeatBlocked]
+    );
+    assert_eq!(filter.check(None, &bid), [FilterRejection::SeatBlocked]);
+}
+
+#[test]
+fn test_advertiser_domains() {
+    let request = request();
+    let blocked = [
+        "ford.com",
+        "www.ford.com",
+        "shop.FORD.com",
+        "https://www.ford.com/cars",
+        "kia.com.",
+    ];
+    for domain in blocked {
+        assert_eq!(
+            check(&request, Bid::builder("1", "1", 1.0).adomain([domain])),
+            [FilterRejection::AdvertiserBlocked],
+            "{domain}"
+        );
+    }
+
+    for domain in ["notford.com", "ford.com.au", "vw.com"] {
+        assert!(
+            check(&request, Bid::builder("1", "1", 1.0).adomain([domain])).is_empty(),
+            "{domain}"
+        );
+    }
+
+    assert_eq!(
+        check(
+            &request,
+            Bid::builder("1", "1", 1.0).adomain(["vw.com", "ford.com"])
+        ),
+        [FilterRejection::AdvertiserBlocked]
+    );
+}
+
+#[test]
+fn test_listed_domains_and_subdomains() {
+    let request = BidRequest {
+        badv: Some(vec![
+            "www.ford.com".to_string(),
+            "bbc.co.uk".to_string(),
+            "x.com.pl".to_string(),
+            "com".to_string(),
+        ]),
+        ..request()
+    };
+
+    for domain in ["www.ford.com", "news.bbc.co.uk", "BBC.co.uk", "x.com.pl"] {
+        assert_eq!(
+            check(&request, Bid::builder("1", "1", 1.0).adomain([domain])),
+            [FilterRejection::AdvertiserBlocked],
+            "{domain}"
+        );
+    }
+
+    // A listed domain never blocks its parent or its siblings, and a
+    // top-level domain blocks nothing.
+    for domain in [
+        "ford.com",
+        "shop.ford.com",
+        "itv.co.uk",
+        "allegro.com.pl",
+        "vw.com",
+    ] {
+        assert!(
+            check(&request, Bid::builder("1", "1", 1.0).adomain([domain])).is_empty(),
+            "{domain}"
+        );
+    }
+}
+
+#[test]
+fn test_apps() {
+    let request = request();
+    assert_eq!(
+        check(
+            &request,
+            Bid::builder("1", "1", 1.0).bundle("com.blocked.app")
+        ),
+        [FilterRejection::AppBlocked]
+    );
+    assert!(check(
+        &request,
+        Bid::builder("1", "1", 1.0).bundle("com.other.app")
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_categories() {
+    let request = request();
+    let cat = |cat: &[&str]| {
+        check(
+            &request,
+            Bid::builder("1", "1", 1.0).cat(cat.iter().copied()),
+        )
+    };
+
+    assert_eq!(cat(&["IAB1"]), [FilterRejection::CategoryBlocked]);
+    assert_eq!(cat(&["IAB1-3"]), [FilterRejection::CategoryBlocked]);
+    assert_eq!(
+        cat(&["IAB2", "IAB7-39"]),
+        [FilterRejection::CategoryBlocked]
+    );
+    assert!(cat(&["IAB10"]).is_empty());
+    assert!(cat(&["IAB7"]).is_empty());
+    assert!(cat(&["IAB7-3"]).is_empty());
+
+    let request = BidRequest {
+        acat: Some(vec!["IAB2".to_string(), "IAB3-1".to_string()]),
+        ..request
+    };
+    let cat = |cat: &[&str]| {
+        check(
+            &request,
+            Bid::builder("1", "1", 1.0).cat(cat.iter().copied()),
+        )
+    };
+
+    assert!(cat(&["IAB2-4", "IAB3-1"]).is_empty());
+    assert_eq!(
+        cat(&["IAB2", "IAB3"]),
+        [FilterRejection::CategoryNotAllowed]
+    );
+    assert_eq!(cat(&[]), [FilterRejection::CategoryNotAllowed]);
+    // Blocked categories win over allowed ones.
+    assert_eq!(cat(&["IAB1-1"]), [FilterRejection::CategoryBlocked]);
+}
+
+#[test]
+fn test_category_taxonomies() {
+    let request = BidRequest {
+        cattax: Some(2),
+        bcat: Some(vec!["483".to_string()]),
+        ..request()
+    };
+
+    assert_eq!(
+        check(&request, Bid::builder("1", "1", 1.0).cat(["483"]).cattax(2)),
+        [FilterRejection::CategoryBlocked]
+    );
+    assert_eq!(
+        check(&request, Bid::builder("1", "1", 1.0).cat(["IAB2"])),
+        [FilterRejection::TaxonomyMismatch]
+    );
+    assert!(check(&request, Bid::builder("1", "1", 1.0).cattax(1)).is_empty());
+}
+
+#[test]
+fn test_creative_attributes() {
+    let request = request();
+    let attr = |mtype: Option<i64>, attr: i64| {
+        let bid = Bid::builder("1", "1", 1.0).attr([attr]);
+        match mtype {
+            Some(mtype) => check(&request, bid.mtype(mtype)),
+            None => check(&request, bid),
+        }
+    };
+
+    assert_eq!(attr(Some(1), 2), [FilterRejection::AttributeBlocked]);
+    assert!(attr(Some(1), 6).is_empty());
+    assert_eq!(attr(Some(2), 6), [FilterRejection::AttributeBlocked]);
+    assert!(attr(Some(2), 1).is_empty());
+    assert_eq!(attr(None, 6), [FilterRejection::AttributeBlocked]);
+
+    // Impression restrictions need the impression.
+    let bid = Bid::builder("1", "2", 1.0).attr([1]);
+    assert!(check(&request, bid).is_empty());
+}
+
+#[test]
+fn test_banner_ad_types() {
+    let request = request();
+    let adm = |adm: &str| check(&request, Bid::builder("1", "1", 1.0).adm(adm));
+
+    assert_eq!(
+        adm("<SCRIPT src=\"https://ads.example.com/ad.js\"></SCRIPT>"),
+        [FilterRejection::AdTypeBlocked]
+    );
+    assert!(adm("<iframe src=\"https://ads.example.com/ad.html\"></iframe>").is_empty());
+    assert!(adm("<a href=\"https://example.com\"><img src=\"ad.png\"></a>").is_empty());
+
+    let video = Bid::builder("1", "1", 1.0)
+        .mtype(2)
+        .adm("<script></script>");
+    assert!(check(&request, video).is_empty());
+}
+
+#[test]
+fn test_seatbid() {
+    let request = request();
+    let response = BidResponse::builder("1")
+        .bid("seat", Bid::builder("a", "1", 1.0).adomain(["vw.com"]))
+        .bid(
+            "seat",
+            Bid::builder("b", "1", 1.0)
+                .adomain(["www.ford.com"])
+                .cat(["IAB1-1"])
+                .attr([1]),
+        )
+        .build()
+        .unwrap();
+    let seatbid = &response.seatbid.as_ref().unwrap()[0];
+
+    let rejected = BidFilter::new(&request).check_seatbid(seatbid);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].0.id, "b");
+    assert_eq!(
+        rejected[0].1,
+        [
+            FilterRejection::AdvertiserBlocked,
+            FilterRejection::CategoryBlocked,
+            FilterRejection::AttributeBlocked,
+        ]
+    );
+    assert_eq!(
+        rejected[0]
+            .1
+            .iter()
+            .map(|r| r.loss_reason())
+            .collect::<Vec<_>>(),
+        [
+            LossReason::AdvertiserExclusions,
+            LossReason::CategoryExclusions,
+            LossReason::CreativeAttributeExclusions,
+        ]
+    );
+}
+
+#[test]
+fn test_auction_filters_bids() {
+    let request = request();
+    let responses = [BidResponse::builder("1")
+        .bid("seat", Bid::builder("a", "1", 5.0).adomain(["ford.com"]))
+        .bid(
+            "seat",
+            Bid::builder("b", "1", 4.0).bundle("com.blocked.app"),
+        )
+        .bid("seat", Bid::builder("c", "1", 3.0).adomain(["vw.com"]))
+        .build()
+        .unwrap()];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let imp = &outcome.imps[0];
+
+    assert_eq!(imp.winner.as_ref().unwrap().candidate.bid.id, "c");
+    let reasons: Vec<_> = imp
+        .losers
+        .iter()
+        .map(|l| (l.candidate.bid.id.as_str(), l.reason))
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            ("a", LossReason::AdvertiserExclusions),
+            ("b", LossReason::AppBundleExclusions),
+        ]
+    );
+}
@@ -0,0 +1,168 @@
+use iab::auction::*;
+use iab::currency::RateTable;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::types::CurrencyCode;
+use iab::openrtb2::*;
+
+mod common;
+use common::price;
+
+fn band(mindur: Option<i64>, maxdur: Option<i64>, bidfloor: f64) -> DurFloors {
+    DurFloors {
+        mindur,
+        maxdur,
+        bidfloor: Some(price(bidfloor)),
+        ..Default::default()
+    }
+}
+
+fn video_imp(mincpmpersec: Option<f64>) -> Imp {
+    Imp::video(["video/mp4"])
+        .floor(3.0, "USD")
+        .with_video(Video {
+            mimes: vec!["video/mp4".to_string()],
+            mincpmpersec: mincpmpersec.map(price),
+            durfloors: Some(vec![
+                band(Some(1), Some(15), 5.0),
+                band(Some(16), Some(30), 10.0),
+                band(Some(31), None, 20.0),
+            ]),
+            ..Default::default()
+        })
+        .into()
+}
+
+fn usd() -> CurrencyCode {
+    CurrencyCode::new("USD").unwrap()
+}
+
+fn resolve(imp: &Imp, deal: Option<&Deal>, duration: Option<i64>) -> Floor {
+    let rates = RateTable::from_csv_str("EUR,USD,1.25").unwrap();
+    resolve_floor(imp, deal, duration, &usd(), &rates).unwrap()
+}
+
+#[test]
+fn test_imp_floor() {
+    let imp: Imp = Imp::banner(300, 250).floor(2.0, "EUR").into();
+    let floor = resolve(&imp, None, None);
+
+    assert_eq!(floor.price, 2.5);
+    assert_eq!(floor.currency, usd());
+    assert_eq!(floor.source, FloorSource::Imp);
+    assert_eq!(floor.rule, FloorRule::BidFloor);
+    assert_eq!(floor.loss_reason(), LossReason::BelowAuctionFloor);
+
+    let floor = resolve(&Imp::banner(300, 250).into(), None, None);
+    assert_eq!(floor.price, 0.0);
+    assert_eq!(floor.rule, FloorRule::None);
+}
+
+#[test]
+fn test_duration_floors() {
+    let imp = video_imp(None);
+
+    let floor = resolve(&imp, None, Some(15));
+    assert_eq!(floor.price, 5.0);
+    assert_eq!(
+        floor.rule,
+        FloorRule::Duration {
+            mindur: Some(1),
+            maxdur: Some(15)
+        }
+    );
+
+    assert_eq!(resolve(&imp, None, Some(16)).price, 10.0);
+    assert_eq!(resolve(&imp, None, Some(60)).price, 20.0);
+
+    // Durations outside of every band, or unknown, fall back to the imp floor.
+    let floor = resolve(&imp, None, Some(0));
+    assert_eq!(floor.price, 3.0);
+    assert_eq!(floor.rule, FloorRule::BidFloor);
+    assert_eq!(resolve(&imp, None, None).rule, FloorRule::BidFloor);
+}
+
+#[test]
+fn test_price_per_second() {
+    let imp = video_imp(Some(0.5));
+
+    let floor = resolve(&imp, None, Some(30));
+    assert_eq!(floor.price, 15.0);
+    assert_eq!(
+        floor.rule,
+        FloorRule::PerSecond {
+            mincpmpersec: price(0.5),
+            dur: 30
+        }
+    );
+
+    // The duration band is higher for short creatives.
+    let floor = resolve(&imp, None, Some(12));
+    assert_eq!(floor.price, 6.0);
+    assert!(matches!(floor.rule, FloorRule::PerSecond { .. }));
+
+    let floor = resolve(&imp, None, Some(6));
+    assert_eq!(floor.price, 5.0);
+    assert!(matches!(floor.rule, FloorRule::Duration { .. }));
+}
+
+#[test]
+fn test_deal_floors() {
+    let imp = video_imp(Some(0.5));
+    let deal = Deal {
+        id: "deal-1".to_string(),
+        bidfloor: Some(price(8.0)),
+        bidfloorcur: Some("EUR".to_string()),
+        durfloors: Some(vec![band(None, Some(15), 4.0)]),
+        ..Default::default()
+    };
+
+    let floor = resolve(&imp, Some(&deal), Some(30));
+    assert_eq!(floor.price, 10.0);
+    assert_eq!(floor.source, FloorSource::Deal);
+    assert_eq!(floor.rule, FloorRule::BidFloor);
+    assert_eq!(floor.loss_reason(), LossReason::BelowDealFloor);
+
+    // The deal band replaces the deal floor, and the imp per-second floor is ignored.
+    let floor = resolve(&imp, Some(&deal), Some(10));
+    assert_eq!(floor.price, 5.0);
+    assert_eq!(
+        floor.rule,
+        FloorRule::Duration {
+            mindur: None,
+            maxdur: Some(15)
+        }
+    );
+
+    // Deals without floors use the floors of the imp.
+    let open = Deal {
+        id: "deal-2".to_string(),
+        ..Default::default()
+    };
+    let floor = resolve(&imp, Some(&open), Some(30));
+    assert_eq!(floor.source, FloorSource::Imp);
+    assert_eq!(floor.price, 15.0);
+}
+
+#[test]
+fn test_auction_applies_duration_floors() {
+    let request = BidRequest::builder()
+        .id("auction-1")
+        .at(1)
+        .imp(video_imp(Some(0.5)))
+        .build()
+        .unwrap();
+    let responses = [BidResponse::builder("auction-1")
+        .bid("a", Bid::builder("a1", "1", 14.0).dur(30))
+        .bid("b", Bid::builder("b1", "1", 9.0).dur(15))
+        .build()
+        .unwrap()];
+
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let imp = &outcome.imps[0];
+    let winner = imp.winner.as_ref().unwrap();
+
+    assert_eq!(winner.candidate.bid.id, "b1");
+    assert_eq!(winner.floor.price, 7.5);
+    assert_eq!(imp.losers[0].candidate.bid.id, "a1");
+    assert_eq!(imp.losers[0].reason, LossReason::BelowAuctionFloor);
+}
@@ -0,0 +1,190 @@
+use iab::auction::*;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+
+mod common;
+use common::price;
+
+fn candidate(response: usize, group: bool, impid: &str, value: f64) -> Candidate {
+    Candidate {
+        response,
+        seatbid: 0,
+        group,
+        seat: Some(format!("seat-{response}")),
+        currency: None,
+        bid: Bid::builder(format!("{response}-{impid}"), impid, value).into(),
+        price: price(value),
+    }
+}
+
+fn key(response: usize) -> GroupKey {
+    GroupKey {
+        response,
+        seatbid: 0,
+    }
+}
+
+#[test]
+fn test_group_clears() {
+    let rankings = [
+        vec![candidate(0, true, "1", 5.0), candidate(1, false, "1", 4.0)],
+        vec![candidate(0, true, "2", 3.0), candidate(1, false, "2", 2.0)],
+    ];
+    let resolution = GroupResolver::new().resolve(&rankings);
+
+    assert_eq!(resolution.winners, [Some(0), Some(0)]);
+    let group = resolution.group(key(0)).unwrap();
+    assert!(group.clears);
+    assert_eq!(group.seat.as_deref(), Some("seat-0"));
+    assert_eq!(group.bids.len(), 2);
+    assert!(group.bids.iter().all(|bid| bid.wins));
+    assert!(resolution.clears(None));
+    assert!(resolution.clears(Some(key(0))));
+
+    let resolution = GroupResolver::new().exclude(key(0)).resolve(&rankings);
+    assert_eq!(resolution.winners, [Some(1), Some(1)]);
+    assert!(!resolution.clears(Some(key(0))));
+}
+
+#[test]
+fn test_group_loses_as_a_whole() {
+    let rankings = [
+        vec![candidate(0, true, "1", 5.0), candidate(1, false, "1", 4.0)],
+        vec![candidate(2, false, "2", 3.0), candidate(0, true, "2", 2.0)],
+        vec![candidate(3, false, "3", 1.0)],
+    ];
+    let resolution = GroupResolver::new().resolve(&rankings);
+
+    assert_eq!(resolution.winners, [Some(1), Some(0), Some(0)]);
+    let group = resolution.group(key(0)).unwrap();
+    assert!(!group.clears);
+    let bids: Vec<_> = group
+        .bids
+        .iter()
+        .map(|bid| (bid.imp_id.as_str(), bid.bid_id.as_str(), bid.wins))
+        .collect();
+    assert_eq!(bids, [("1", "0-1", false), ("2", "0-2", false)]);
+    assert_eq!(group.bids[0].price, 5.0);
+}
+
+#[test]
+fn test_competing_groups() {
+    // Each group wins one impression and loses the other. The one of lower
+    // total price gives way, and the other then clears.
+    let rankings = [
+        vec![candidate(0, true, "1", 5.0), candidate(1, true, "1", 4.0)],
+        vec![candidate(1, true, "2", 4.0), candidate(0, true, "2", 1.0)],
+    ];
+    let resolution = GroupResolver::new().resolve(&rankings);
+
+    assert_eq!(resolution.winners, [Some(1), Some(0)]);
+    assert!(!resolution.clears(Some(key(0))));
+    assert!(resolution.clears(Some(key(1))));
+    assert_eq!(resolution.groups.len(), 2);
+}
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("auction-1")
+        .at(2)
+        .imp(Imp::banner(300, 250).floor(1.0, "USD"))
+        .imp(Imp::banner(728, 90))
+        .build()
+        .unwrap()
+}
+
+fn group(bids: Vec<BidBuilder>) -> BidResponse {
+    BidResponse::builder("auction-1")
+        .seatbid(SeatBid {
+            seat: Some("grouped".to_string()),
+            group: Some(1),
+            bid: bids.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+fn reasons(imp: &ImpOutcome) -> Vec<(&str, LossReason)> {
+    imp.losers
+        .iter()
+        .map(|l| (l.candidate.bid.id.as_str(), l.reason))
+        .collect()
+}
+
+#[test]
+fn test_auction_awards_whole_groups() {
+    let responses = [
+        group(vec![
+            Bid::builder("g1", "1", 5.0),
+            Bid::builder("g2", "2", 2.0),
+        ]),
+        BidResponse::builder("auction-1")
+            .bid("a", Bid::builder("a1", "1", 3.0))
+            .bid("a", Bid::builder("a2", "2", 1.5))
+            .build()
+            .unwrap(),
+    ];
+
+    let outcome = Auction::new().run(&request(), &responses).unwrap();
+    assert_eq!(outcome.groups.len(), 1);
+    assert!(outcome.groups[0].clears);
+    let winners: Vec<_> = outcome
+        .winners()
+        .map(|w| w.candidate.bid.id.as_str())
+        .collect();
+    assert_eq!(winners, ["g1", "g2"]);
+
+    // The group loses the second impression, so it loses the first as well,
+    // and sets the second price on neither.
+    let responses = [
+        responses[0].clone(),
+        BidResponse::builder("auction-1")
+            .bid("a", Bid::builder("a1", "1", 3.0))
+            .bid("a", Bid::builder("a2", "2", 2.5))
+            .build()
+            .unwrap(),
+        BidResponse::builder("auction-1")
+            .bid("b", Bid::builder("b2", "2", 1.0))
+            .build()
+            .unwrap(),
+    ];
+    let outcome = Auction::new().run(&request(), &responses).unwrap();
+
+    assert!(!outcome.groups[0].clears);
+    let first = &outcome.imps[0];
+    let winner = first.winner.as_ref().unwrap();
+    assert_eq!(winner.candidate.bid.id, "a1");
+    assert_eq!(winner.clearing_price, 1.01);
+    assert_eq!(reasons(first), [("g1", LossReason::LostToHigherBid)]);
+    let winner = outcome.imps[1].winner.as_ref().unwrap();
+    assert_eq!(winner.candidate.bid.id, "a2");
+    assert_eq!(winner.clearing_price, 1.01);
+    assert!(winner.candidate.group_key().is_none());
+}
+
+#[test]
+fn test_auction_group_with_ineligible_bid() {
+    let responses = [
+        group(vec![
+            Bid::builder("g1", "1", 0.5),
+            Bid::builder("g2", "2", 2.0),
+        ]),
+        BidResponse::builder("auction-1")
+            .bid("a", Bid::builder("a2", "2", 1.0))
+            .build()
+            .unwrap(),
+    ];
+
+    let outcome = Auction::new().run(&request(), &responses).unwrap();
+
+    assert!(outcome.imps[0].winner.is_none());
+    assert_eq!(
+        reasons(&outcome.imps[0]),
+        [("g1", LossReason::BelowAuctionFloor)]
+    );
+    let second = &outcome.imps[1];
+    assert_eq!(second.winner.as_ref().unwrap().candidate.bid.id, "a2");
+    assert_eq!(reasons(second), [("g2", LossReason::BelowAuctionFloor)]);
+    assert!(!outcome.groups[0].clears);
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "json-schema")]
+
+use iab::json_schema;
+use iab::openrtb2::*;
+use serde_json::{json, Value};
+use std::fs;
+
+fn definition<'a>(schema: &'a Value, name: &str) -> &'a Value {
+    schema["$defs"]
+        .get(name)
+        .unwrap_or_else(|| panic!("Missing definition for {name}"))
+}
+
+#[test]
+fn test_bid_request_schema() {
+    let schema = json_schema::schema_for::<BidRequest>().to_value();
+
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(schema["description"], "Top-level bid request object.");
+    assert_eq!(schema["required"], json!(["id", "imp"]));
+    assert_eq!(
+        schema["properties"]["tmax"]["description"],
+        "Maximum time in milliseconds for bids to be received."
+    );
+
+    assert_eq!(definition(&schema, "Video")["required"], json!(["mimes"]));
+    assert_eq!(
+        definition(&schema, "SupplyChain")["required"],
+        json!(["complete", "nodes", "ver"])
+    );
+    assert_eq!(
+        definition(&schema, "Video")["properties"]["placement"]["deprecated"],
+        true
+    );
+
+    let imp = definition(&schema, "Imp");
+    assert!(imp["properties"].get("native").is_some());
+    assert!(imp["properties"].get("native_markup").is_none());
+    assert!(definition(&schema, "Site")["properties"]
+        .get("ref")
+        .is_some());
+}
+
+#[test]
+fn test_bid_response_schema() {
+    let schema = json_schema::schema_for::<BidResponse>().to_value();
+
+    assert_eq!(schema["required"], json!(["id"]));
+    assert_eq!(
+        definition(&schema, "Bid")["required"],
+        json!(["id", "impid", "price"])
+    );
+    assert_eq!(definition(&schema, "SeatBid")["required"], json!(["bid"]));
+    assert_eq!(
+        definition(&schema, "Bid")["properties"]["price"]["type"],
+        "number"
+    );
+}
+
+#[test]
+fn test_write_schemas() {
+    let dir = std::env::temp_dir().join("iab-json-schema");
+    let paths = json_schema::write_schemas(&dir).expect("Failed to write schemas");
+
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("BidRequest.schema.json"));
+    assert!(paths[1].ends_with("BidResponse.schema.json"));
+
+    let written: Value = serde_json::from_str(&fs::read_to_string(&paths[0]).unwrap()).unwrap();
+    assert_eq!(written, json_schema::schema_for::<BidRequest>().to_value());
+
+    fs::remove_dir_all(dir).unwrap();
+}
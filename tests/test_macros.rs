@@ -0,0 +1,141 @@
+use iab::auction::*;
+use iab::currency::RateTable;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+
+mod common;
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("auction 1")
+        .at(2)
+        .cur(["EUR"])
+        .imp(Imp::banner(300, 250))
+        .build()
+        .unwrap()
+}
+
+fn outcome(request: &BidRequest) -> AuctionOutcome {
+    let response = BidResponse::builder("auction 1")
+        .cur("EUR")
+        .bid(
+            "seat/1",
+            Bid::builder("a", "1", 2.0)
+                .adid("ad-1")
+                .nurl("https://dsp.com/win?p=${AUCTION_PRICE}&c=${AUCTION_CURRENCY}")
+                .burl("https://dsp.com/bill?p=${AUCTION_PRICE:B64}&mbr=${AUCTION_MBR}"),
+        )
+        .bid(
+            "seat/2",
+            Bid::builder("b", "1", 1.0)
+                .lurl("https://dsp.com/loss?r=${AUCTION_LOSS}&min=${AUCTION_MIN_TO_WIN}"),
+        )
+        .build()
+        .unwrap();
+    Auction::new().run(request, &[response]).unwrap()
+}
+
+#[test]
+fn test_winner_macros() {
+    let request = request();
+    let outcome = outcome(&request);
+    let winner = outcome.winners().next().unwrap();
+
+    let macros = AuctionMacros::for_winner(&request, &outcome, winner).bid_id("r-1");
+    assert_eq!(macros.auction_id.as_deref(), Some("auction 1"));
+    assert_eq!(macros.price, Some(winner.clearing_price));
+    assert_eq!(macros.loss, Some(LossReason::BidWon));
+
+    let mut bid = winner.candidate.bid.clone();
+    macros.apply(&mut bid);
+    assert_eq!(
+        bid.nurl.as_deref(),
+        Some("https://dsp.com/win?p=1.01&c=EUR")
+    );
+    assert_eq!(
+        bid.burl.as_deref(),
+        Some("https://dsp.com/bill?p=MS4wMQ%3D%3D&mbr=0.505")
+    );
+
+    let ids =
+        "${AUCTION_ID}|${AUCTION_BID_ID}|${AUCTION_IMP_ID}|${AUCTION_SEAT_ID}|${AUCTION_AD_ID}";
+    assert_eq!(macros.substitute(ids), "auction%201|r-1|1|seat%2F1|ad-1");
+}
+
+#[test]
+fn test_loser_macros() {
+    let request = request();
+    let outcome = outcome(&request);
+    let loser = outcome.losers().next().unwrap();
+
+    let mut bid = loser.candidate.bid.clone();
+    AuctionMacros::for_loser(&request, &outcome, loser).apply(&mut bid);
+    assert_eq!(
+        bid.lurl.as_deref(),
+        Some("https://dsp.com/loss?r=102&min=2")
+    );
+}
+
+#[test]
+fn test_macros_in_bid_currency() {
+    let request = BidRequest::builder()
+        .id("1")
+        .at(2)
+        .cur(["EUR", "USD"])
+        .imp(Imp::banner(300, 250))
+        .build()
+        .unwrap();
+    let usd = BidResponse::builder("1")
+        .cur("USD")
+        .bid("seat", Bid::builder("a", "1", 4.0))
+        .bid("seat", Bid::builder("c", "1", 2.0))
+        .build()
+        .unwrap();
+    let eur = BidResponse::builder("1")
+        .cur("EUR")
+        .bid("seat", Bid::builder("b", "1", 1.5))
+        .build()
+        .unwrap();
+    let rates = RateTable::from_csv_str("USD,EUR,0.5").unwrap();
+    let outcome = Auction::new()
+        .converter(&rates)
+        .run(&request, &[usd, eur])
+        .unwrap();
+    let text = "${AUCTION_PRICE}|${AUCTION_CURRENCY}|${AUCTION_MIN_TO_WIN}";
+
+    // The winner pays 1.51 EUR, its bid is in USD.
+    let winner = outcome.winners().next().unwrap();
+    assert_eq!(outcome.currency.to_string(), "EUR");
+    assert_eq!(winner.clearing_price, common::price(1.51));
+    let macros = AuctionMacros::for_winner(&request, &outcome, winner);
+    assert_eq!(macros.substitute(text), "3.02|USD|");
+
+    // The winning bid is 2 EUR, or 4 USD.
+    let min_to_win = |id: &str| {
+        let loser = outcome.losers().find(|l| l.candidate.bid.id == id).unwrap();
+        AuctionMacros::for_loser(&request, &outcome, loser).substitute(text)
+    };
+    assert_eq!(min_to_win("b"), "|EUR|2");
+    assert_eq!(min_to_win("c"), "|USD|4");
+}
+
+#[test]
+fn test_unknown_and_missing_macros() {
+    let macros = AuctionMacros {
+        auction_id: Some("1".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        macros.substitute("a=${AUCTION_ID}&b=${UNKNOWN}&c=${AUCTION_PRICE}&d=${AUCTION_ID"),
+        "a=1&b=${UNKNOWN}&c=&d=${AUCTION_ID"
+    );
+    assert_eq!(macros.substitute("${X${AUCTION_ID}}"), "${X1}");
+    assert_eq!(macros.substitute("${AUCTION_ID:B64}"), "MQ%3D%3D");
+    assert_eq!(macros.substitute("${auction_id}"), "${auction_id}");
+    assert_eq!(macros.substitute("no macros"), "no macros");
+
+    let mut bid: Bid = Bid::builder("1", "1", 1.0).into();
+    macros.apply(&mut bid);
+    assert_eq!(bid.nurl, None);
+}
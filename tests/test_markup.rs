@@ -0,0 +1,112 @@
+use iab::native::NativeResponse;
+use iab::openrtb2::codes::MarkupType;
+use iab::openrtb2::*;
+use iab::vast::*;
+use std::fs;
+use std::path::Path;
+
+fn fixture_bid(filename: &str) -> Bid {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/response")
+        .join(filename);
+    let content = fs::read_to_string(path).unwrap();
+    let response: BidResponse = serde_json::from_str(&content).unwrap();
+    response.seatbid.unwrap().remove(0).bid.remove(0)
+}
+
+fn bid(adm: &str) -> BidBuilder {
+    Bid::builder("1", "1", 1.0).adm(adm)
+}
+
+const NATIVE: &str = r#"{"native": {"ver": "1.1", "link": {"url": "https://a.com"}, "imptrackers": ["https://a.com/imp"]}}"#;
+
+const VAST: &str = r#"<?xml version="1.0"?>
+<!-- served by dsp -->
+<VAST version="3.0"><Ad id="a"><InLine><AdSystem>DSP</AdSystem><AdTitle>Ad</AdTitle></InLine></Ad></VAST>"#;
+
+#[test]
+fn test_fixture_markup() {
+    let bid = fixture_bid("ad-served-on-win-notice.json");
+    assert_eq!(bid.markup(None).unwrap(), Markup::Empty);
+
+    let bid = fixture_bid("vast-xml-document-returned-inline.json");
+    let Markup::Vast(vast) = bid.markup(Some(&Imp::video(["video/mp4"]).into())).unwrap() else {
+        panic!("expected VAST markup");
+    };
+    assert_eq!(vast.version, "2.0");
+    assert_eq!(vast.ads[0].id.as_deref(), Some("12345"));
+    let AdKind::InLine(inline) = &vast.ads[0].kind else {
+        panic!("expected an inline ad");
+    };
+    assert_eq!(inline.ad_system.name, "SpotXchange");
+    assert_eq!(inline.ad_system.version.as_deref(), Some("1.0"));
+    assert_eq!(inline.ad_title, "Sample VAST");
+    assert_eq!(inline.description.as_deref(), Some("A sample VAST feed"));
+    assert_eq!(inline.impressions[0].url, "http://sample.com");
+
+    // The fixture elides the native objects with `...`.
+    let bid = fixture_bid("native-markup-returned-inline.json");
+    assert!(matches!(
+        bid.markup(None),
+        Err(MarkupError::InvalidNative(_))
+    ));
+}
+
+#[test]
+fn test_markup_inference() {
+    let banner: Imp = Imp::banner(300, 250).into();
+    let video: Imp = Imp::video(["video/mp4"]).into();
+    let multi: Imp = Imp::video(["video/mp4"])
+        .with_banner(Banner::default())
+        .into();
+
+    let native: Bid = bid(NATIVE).into();
+    let Markup::Native(response) = native.markup(None).unwrap() else {
+        panic!("expected native markup");
+    };
+    assert_eq!(
+        response,
+        NativeResponse::parse(&NATIVE[10..NATIVE.len() - 1]).unwrap()
+    );
+    assert_eq!(response.imptrackers.unwrap(), ["https://a.com/imp"]);
+
+    let vast: Bid = bid(VAST).into();
+    assert!(matches!(vast.markup(Some(&video)), Ok(Markup::Vast(_))));
+    assert!(matches!(vast.markup(Some(&multi)), Ok(Markup::Vast(_))));
+    assert!(matches!(
+        vast.markup(Some(&banner)),
+        Err(MarkupError::Mismatch {
+            expected: MarkupType::Banner,
+            found: MarkupFormat::Vast
+        })
+    ));
+
+    let html: Bid = bid("<VASTly-styled>ad</VASTly-styled>").mtype(1).into();
+    assert!(matches!(html.markup(Some(&multi)), Ok(Markup::Html(_))));
+    assert!(matches!(
+        html.markup(Some(&video)),
+        Err(MarkupError::UnofferedType(MarkupType::Banner))
+    ));
+
+    let declared: Bid = bid("<div></div>").mtype(2).into();
+    assert!(matches!(
+        declared.markup(None),
+        Err(MarkupError::Mismatch {
+            expected: MarkupType::Video,
+            found: MarkupFormat::Html
+        })
+    ));
+    let unknown: Bid = bid("<div></div>").mtype(7).into();
+    assert!(matches!(
+        unknown.markup(None),
+        Err(MarkupError::UnknownType(7))
+    ));
+
+    let broken: Bid = bid("<VAST version=\"4.0\"><Ad></VAST>").into();
+    assert!(matches!(
+        broken.markup(None),
+        Err(MarkupError::InvalidVast(VastError::InvalidXml { .. }))
+    ));
+    let blank: Bid = bid("  ").mtype(2).into();
+    assert_eq!(blank.markup(Some(&banner)).unwrap(), Markup::Empty);
+}
@@ -0,0 +1,246 @@
+use iab::auction::*;
+use iab::currency::{RateTable, Rounding};
+use iab::openrtb2::*;
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("auction-1")
+        .imp(Imp::banner(300, 250))
+        .imp(Imp::banner(728, 90))
+        .build()
+        .unwrap()
+}
+
+fn no_bid(nbr: Option<i64>) -> BidResponse {
+    BidResponse {
+        id: "auction-1".to_string(),
+        nbr,
+        ..Default::default()
+    }
+}
+
+fn seats(response: &BidResponse) -> Vec<Option<&str>> {
+    response
+        .seatbid
+        .iter()
+        .flatten()
+        .map(|s| s.seat.as_deref())
+        .collect()
+}
+
+#[test]
+fn test_merge_converts_and_namespaces() {
+    let a = BidResponse::builder("auction-1")
+        .cur("EUR")
+        .bid("seat-1", Bid::builder("1", "1", 2.0))
+        .bid("seat-2", Bid::builder("2", "2", 1.0))
+        .build()
+        .unwrap();
+    let b = BidResponse::builder("auction-1")
+        .seatbid(SeatBid {
+            bid: vec![Bid::builder("1", "1", 3.0).into()],
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    let request = BidRequest {
+        cur: Some(vec!["USD".to_string()]),
+        ..request()
+    };
+
+    let merged = ResponseMerger::new()
+        .merge(&request, [("a", &a), ("b", &b)])
+        .unwrap();
+    assert_eq!(merged.skipped, ["a"]);
+    assert_eq!(seats(&merged.response), [Some("b")]);
+
+    let rates = RateTable::from_csv_str("EUR,USD,1.255").unwrap();
+    let merger = ResponseMerger::new()
+        .converter(&rates)
+        .rounding(Rounding::Down(2));
+    let merged = merger.merge(&request, [("a", &a), ("b", &b)]).unwrap();
+    let response = &merged.response;
+
+    assert_eq!(response.id, "auction-1");
+    assert_eq!(response.cur.as_deref(), Some("USD"));
+    assert_eq!(response.nbr, None);
+    assert_eq!(
+        seats(response),
+        [Some("a:seat-1"), Some("a:seat-2"), Some("b")]
+    );
+
+    let seatbids = response.seatbid.as_ref().unwrap();
+    assert_eq!(seatbids[0].bid[0].price, 2.51);
+    assert_eq!(seatbids[1].bid[0].price, 1.25);
+    assert_eq!(seatbids[2].bid[0].price, 3.0);
+    assert!(response.validate().is_ok());
+
+    let merged = merger
+        .clone()
+        .seat_naming(SeatNaming::Keep)
+        .merge(&request, [("a", &a), ("b", &b)])
+        .unwrap();
+    assert_eq!(
+        seats(&merged.response),
+        [Some("seat-1"), Some("seat-2"), None]
+    );
+
+    let merged = merger
+        .seat_naming(SeatNaming::Bidder)
+        .merge(&request, [("a", &a), ("b", &b)])
+        .unwrap();
+    assert_eq!(seats(&merged.response), [Some("a"), Some("a"), Some("b")]);
+}
+
+#[test]
+fn test_merge_dedupes_bid_ids() {
+    let response = |seat: &str| {
+        BidResponse::builder("auction-1")
+            .bid(seat, Bid::builder("1", "1", 1.0))
+            .bid(seat, Bid::builder("1", "2", 1.0))
+            .build()
+            .unwrap()
+    };
+    let (a, b) = (response("x"), response("y"));
+
+    let merged = ResponseMerger::new()
+        .merge(&request(), [("a", &a), ("b", &b)])
+        .unwrap();
+    let ids: Vec<_> = merged
+        .response
+        .seatbid
+        .iter()
+        .flatten()
+        .flat_map(|s| &s.bid)
+        .map(|b| b.id.as_str())
+        .collect();
+    assert_eq!(ids, ["1", "a:1", "b:1", "b:1:2"]);
+
+    let source = merged.source("b:1:2").unwrap();
+    assert_eq!(source.bidder, "b");
+    assert_eq!(source.bid_id, "1");
+    assert_eq!(source.seat.as_deref(), Some("y"));
+    assert_eq!(merged.sources.len(), 4);
+    assert!(merged.source("2").is_none());
+}
+
+#[test]
+fn test_merge_keeps_groups() {
+    let a = BidResponse::builder("auction-1")
+        .seatbid(SeatBid {
+            seat: Some("seat".to_string()),
+            group: Some(1),
+            bid: vec![
+                Bid::builder("1", "1", 1.0).into(),
+                Bid::builder("2", "2", 1.0).into(),
+            ],
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    let b = BidResponse::builder("auction-1")
+        .bid("seat", Bid::builder("3", "1", 2.0))
+        .build()
+        .unwrap();
+
+    let merged = ResponseMerger::new()
+        .seat_naming(SeatNaming::Keep)
+        .merge(&request(), [("a", &a), ("b", &b)])
+        .unwrap();
+    let seatbids = merged.response.seatbid.unwrap();
+
+    // Seatbids of the same seat are not combined, so the group stays whole.
+    assert_eq!(seatbids.len(), 2);
+    assert_eq!(seatbids[0].group, Some(1));
+    assert_eq!(seatbids[0].bid.len(), 2);
+    assert_eq!(seatbids[1].group, None);
+}
+
+#[test]
+fn test_merge_no_bids() {
+    let request = request();
+    let merge = |responses: &[BidResponse]| {
+        let bidders = ["a", "b", "c", "d"];
+        ResponseMerger::new()
+            .merge(&request, bidders.into_iter().zip(responses))
+            .unwrap()
+            .response
+    };
+
+    let response = merge(&[no_bid(Some(2)), no_bid(Some(8)), no_bid(Some(8))]);
+    assert!(response.seatbid.is_none());
+    assert_eq!(response.nbr, Some(8));
+
+    // Ties go to the reason given first, and unknown reasons lose.
+    let response = merge(&[
+        no_bid(None),
+        no_bid(Some(0)),
+        no_bid(Some(3)),
+        no_bid(Some(2)),
+    ]);
+    assert_eq!(response.nbr, Some(3));
+    assert_eq!(merge(&[no_bid(None), no_bid(Some(0))]).nbr, Some(0));
+    assert_eq!(merge(&[]).nbr, Some(0));
+    assert!(merge(&[]).validate().is_ok());
+
+    // Responses with bids have no reason.
+    let bid = BidResponse::builder("auction-1")
+        .bid("seat", Bid::builder("1", "1", 1.0))
+        .build()
+        .unwrap();
+    assert_eq!(merge(&[no_bid(Some(2)), bid]).nbr, None);
+}
+
+#[test]
+fn test_merge_skips_other_auctions() {
+    let other = BidResponse::builder("auction-2")
+        .bid("seat", Bid::builder("1", "1", 1.0))
+        .build()
+        .unwrap();
+    let empty = BidResponse {
+        seatbid: Some(vec![SeatBid::default()]),
+        ..no_bid(Some(1))
+    };
+
+    let merged = ResponseMerger::new()
+        .merge(&request(), [("a", &other), ("b", &empty)])
+        .unwrap();
+
+    assert_eq!(merged.skipped, ["a"]);
+    assert!(merged.sources.is_empty());
+    assert!(merged.response.seatbid.is_none());
+    assert_eq!(merged.response.nbr, Some(1));
+}
+
+#[test]
+fn test_merge_skips_unconvertible_responses() {
+    let invalid = BidResponse {
+        cur: Some("usd".to_string()),
+        ..BidResponse::builder("auction-1")
+            .bid("seat", Bid::builder("1", "1", 1.0))
+            .build()
+            .unwrap()
+    };
+    let unknown = BidResponse::builder("auction-1")
+        .cur("JPY")
+        .bid("seat", Bid::builder("2", "1", 100.0))
+        .bid("seat", Bid::builder("3", "2", 200.0))
+        .build()
+        .unwrap();
+    let valid = BidResponse::builder("auction-1")
+        .bid("seat", Bid::builder("4", "1", 2.0))
+        .build()
+        .unwrap();
+
+    let merged = ResponseMerger::new()
+        .merge(
+            &request(),
+            [("a", &invalid), ("b", &unknown), ("c", &valid)],
+        )
+        .unwrap();
+
+    assert_eq!(merged.skipped, ["a", "b"]);
+    assert_eq!(seats(&merged.response), [Some("c:seat")]);
+    assert_eq!(merged.sources.len(), 1);
+    assert_eq!(merged.sources[0].bidder, "c");
+}
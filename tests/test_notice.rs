@@ -0,0 +1,68 @@
+use iab::auction::*;
+use iab::openrtb2::codes::LossReason;
+use iab::openrtb2::*;
+
+const LURL: &str =
+    "https://dsp.com/loss?id=${AUCTION_BID_ID}&r=${AUCTION_LOSS}&min=${AUCTION_MIN_TO_WIN}";
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("auction-1")
+        .at(1)
+        .imp(Imp::banner(300, 250).floor(1.0, "USD"))
+        .imp(Imp::banner(728, 90))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_loss_notices() {
+    let responses = [
+        BidResponse::builder("auction-1")
+            .bidid("resp-a")
+            .bid("a", Bid::builder("a1", "1", 3.0).lurl(LURL))
+            .bid("a", Bid::builder("a2", "2", 2.0).lurl(LURL))
+            .build()
+            .unwrap(),
+        BidResponse::builder("auction-1")
+            .bid("b", Bid::builder("b1", "1", 2.5).lurl(LURL))
+            .bid("b", Bid::builder("b2", "1", 0.5).lurl(LURL))
+            .bid("b", Bid::builder("b3", "2", 1.0))
+            .bid("b", Bid::builder("b4", "9", 1.0).lurl(LURL))
+            .build()
+            .unwrap(),
+    ];
+    let request = request();
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    let notices = outcome.loss_notices(&request, &responses);
+
+    // Winners and bids without `lurl` get no notice.
+    let summary: Vec<_> = notices
+        .iter()
+        .map(|n| (n.bid_id.as_str(), n.imp_id.as_str(), n.reason))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("b1", "1", LossReason::LostToHigherBid),
+            ("b2", "1", LossReason::BelowAuctionFloor),
+            ("b4", "9", LossReason::InvalidBidResponse),
+        ]
+    );
+    assert_eq!(notices[0].seat.as_deref(), Some("b"));
+    assert_eq!(notices[0].url, "https://dsp.com/loss?id=&r=102&min=3");
+    assert_eq!(notices[1].url, "https://dsp.com/loss?id=&r=100&min=3");
+    assert_eq!(notices[2].url, "https://dsp.com/loss?id=&r=3&min=");
+
+    // `${AUCTION_BID_ID}` is the `bidid` of the response of the bid.
+    let responses = [BidResponse::builder("auction-1")
+        .bidid("resp-a")
+        .bid("a", Bid::builder("a1", "1", 0.5).lurl(LURL))
+        .build()
+        .unwrap()];
+    let outcome = Auction::new().run(&request, &responses).unwrap();
+    assert_eq!(
+        outcome.loss_notices(&request, &responses)[0].url,
+        "https://dsp.com/loss?id=resp-a&r=100&min="
+    );
+}
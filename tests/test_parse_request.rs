@@ -35,7 +35,7 @@ fn test_simple_banner_request() {
     assert_eq!(req.imp.len(), 1);
     let imp = &req.imp[0];
     assert_eq!(imp.id, "1");
-    assert_eq!(imp.bidfloor, Some(0.03));
+    assert_eq!(imp.bidfloor.unwrap(), 0.03);
     assert!(imp.banner.is_some());
     let banner = imp.banner.as_ref().unwrap();
     assert_eq!(banner.w, Some(300));
@@ -56,7 +56,7 @@ fn test_expandable_creative_request() {
     assert_eq!(req.imp.len(), 1);
     let imp = &req.imp[0];
     assert_eq!(imp.id, "1");
-    assert_eq!(imp.bidfloor, Some(0.03));
+    assert_eq!(imp.bidfloor.unwrap(), 0.03);
     assert_eq!(
         imp.iframebuster,
         Some(vec!["vendor1.com".to_string(), "vendor2.com".to_string()])
@@ -110,7 +110,7 @@ fn test_mobile_request() {
     assert_eq!(req.imp.len(), 1);
     let imp = &req.imp[0];
     assert_eq!(imp.id, "1");
-    assert_eq!(imp.bidfloor, Some(0.5));
+    assert_eq!(imp.bidfloor.unwrap(), 0.5);
     assert_eq!(imp.instl, Some(0));
     assert_eq!(
         imp.tagid.as_deref(),
@@ -227,12 +227,12 @@ fn test_pmp_with_direct_deal_request() {
     // Deal 1
     assert_eq!(deals[0].id, "AB-Agency1-0001");
     assert_eq!(deals[0].at, Some(1));
-    assert_eq!(deals[0].bidfloor, Some(2.5));
+    assert_eq!(deals[0].bidfloor.unwrap(), 2.5);
     assert_eq!(deals[0].wseat, Some(vec!["Agency1".to_string()]));
     // Deal 2
     assert_eq!(deals[1].id, "XY-Agency2-0001");
     assert_eq!(deals[1].at, Some(2));
-    assert_eq!(deals[1].bidfloor, Some(2.0));
+    assert_eq!(deals[1].bidfloor.unwrap(), 2.0);
     assert_eq!(deals[1].wseat, Some(vec!["Agency2".to_string()]));
     assert!(req.site.is_some());
     let site = req.site.as_ref().unwrap();
@@ -259,7 +259,7 @@ fn test_video_request() {
     assert_eq!(req.imp.len(), 1);
     let imp = &req.imp[0];
     assert_eq!(imp.id, "1");
-    assert_eq!(imp.bidfloor, Some(0.03));
+    assert_eq!(imp.bidfloor.unwrap(), 0.03);
     assert!(imp.video.is_some());
     let video = imp.video.as_ref().unwrap();
     assert_eq!(video.w, Some(640));
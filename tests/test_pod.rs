@@ -0,0 +1,257 @@
+use iab::auction::*;
+use iab::openrtb2::*;
+
+mod common;
+use common::price;
+
+fn candidate(bid: BidBuilder) -> Candidate {
+    let bid: Bid = bid.into();
+    Candidate {
+        response: 0,
+        seatbid: 0,
+        group: false,
+        seat: None,
+        currency: None,
+        price: bid.price,
+        bid,
+    }
+}
+
+fn video(podid: &str, configure: impl FnOnce(&mut Video)) -> ImpBuilder {
+    let mut video = Video {
+        mimes: vec!["video/mp4".to_string()],
+        podid: Some(podid.to_string()),
+        ..Default::default()
+    };
+    configure(&mut video);
+    Imp::video(["video/mp4"]).with_video(video)
+}
+
+fn request(imps: Vec<ImpBuilder>) -> BidRequest {
+    imps.into_iter()
+        .fold(BidRequest::builder().id("1"), |request, imp| {
+            request.imp(imp)
+        })
+        .build()
+        .unwrap()
+}
+
+fn ids(pod: &Pod) -> Vec<&str> {
+    pod.ads
+        .iter()
+        .map(|ad| ad.candidate.bid.id.as_str())
+        .collect()
+}
+
+fn rejection(pod: &Pod, id: &str) -> Option<PodRejection> {
+    pod.rejected
+        .iter()
+        .find(|(candidate, _)| candidate.bid.id == id)
+        .map(|(_, reason)| *reason)
+}
+
+#[test]
+fn test_dynamic_pod_maximizes_revenue() {
+    let request = request(vec![video("pod-1", |v| {
+        v.poddur = Some(60);
+        v.maxseq = Some(3);
+    })]);
+
+    let pod = PodBuilder::new(&request, "pod-1").assemble([
+        candidate(Bid::builder("a", "1", 20.0).dur(60)),
+        candidate(Bid::builder("b", "1", 12.0).dur(30)),
+        candidate(Bid::builder("c", "1", 11.0).dur(30)),
+    ]);
+
+    assert_eq!(pod.podid, "pod-1");
+    assert_eq!(ids(&pod), ["b", "c"]);
+    assert_eq!(pod.revenue, 23.0);
+    assert_eq!(pod.duration, 60);
+    assert_eq!(rejection(&pod, "a"), Some(PodRejection::NotSelected));
+    assert!(pod.exhaustive);
+}
+
+#[test]
+fn test_search_budget() {
+    let request = request(vec![video("pod-1", |v| {
+        v.poddur = Some(600);
+        v.maxseq = Some(60);
+    })]);
+
+    // Bids of similar value per second, with too many ways of filling the
+    // pod for all of them to be explored.
+    let bids: Vec<_> = (0..60)
+        .map(|i| {
+            let dur = 15 + i * 7 % 31;
+            let bid = Bid::builder(format!("b{i}"), "1", dur as f64 + (i % 3) as f64 * 0.1);
+            candidate(bid.dur(dur))
+        })
+        .collect();
+    let pod = PodBuilder::new(&request, "pod-1").assemble(bids);
+
+    assert!(!pod.exhaustive);
+    assert!(pod.duration <= 600);
+    assert_eq!(pod.ads.len() + pod.rejected.len(), 60);
+}
+
+#[test]
+fn test_max_sequence() {
+    let request = request(vec![video("pod-1", |v| {
+        v.poddur = Some(90);
+        v.maxseq = Some(2);
+    })]);
+
+    let pod = PodBuilder::new(&request, "pod-1").assemble([
+        candidate(Bid::builder("a", "1", 8.0).dur(30)),
+        candidate(Bid::builder("b", "1", 10.0).dur(30)),
+        candidate(Bid::builder("c", "1", 9.0).dur(30)),
+    ]);
+
+    assert_eq!(ids(&pod), ["b", "c"]);
+    assert_eq!(pod.revenue, price(19.0));
+}
+
+#[test]
+fn test_duration_rules() {
+    let request = request(vec![
+        video("pod-1", |v| {
+            v.poddur = Some(60);
+            v.rqddurs = Some(vec![15, 30]);
+        }),
+        Imp::banner(300, 250),
+    ]);
+
+    let pod = PodBuilder::new(&request, "pod-1").assemble([
+        candidate(Bid::builder("a", "1", 5.0).dur(30)),
+        candidate(Bid::builder("b", "1", 5.0).dur(20)),
+        candidate(Bid::builder("c", "1", 5.0)),
+        candidate(Bid::builder("d", "2", 5.0).dur(15)),
+    ]);
+
+    assert_eq!(ids(&pod), ["a"]);
+    assert_eq!(rejection(&pod, "b"), Some(PodRejection::DurationNotAllowed));
+    assert_eq!(rejection(&pod, "c"), Some(PodRejection::MissingDuration));
+    assert_eq!(rejection(&pod, "d"), Some(PodRejection::NotInPod));
+}
+
+#[test]
+fn test_deduplication() {
+    let request = request(vec![video("pod-1", |v| {
+        v.poddur = Some(90);
+        v.poddedupe = Some(vec![2, 4]);
+    })]);
+
+    let bids = [
+        candidate(
+            Bid::builder("a", "1", 10.0)
+                .dur(30)
+                .adomain(["ford.com"])
+                .cid("c1"),
+        ),
+        candidate(
+            Bid::builder("b", "1", 9.0)
+                .dur(30)
+                .adomain(["ford.com"])
+                .cid("c2"),
+        ),
+        candidate(
+            Bid::builder("c", "1", 8.0)
+                .dur(30)
+                .adomain(["kia.com"])
+                .cid("c1"),
+        ),
+        candidate(
+            Bid::builder("d", "1", 1.0)
+                .dur(30)
+                .adomain(["vw.com"])
+                .cid("c3"),
+        ),
+    ];
+
+    // Dropping the highest bid lets both of the bids it conflicts with in.
+    let pod = PodBuilder::new(&request, "pod-1").assemble(bids.clone());
+    assert_eq!(ids(&pod), ["b", "c", "d"]);
+    assert_eq!(rejection(&pod, "a"), Some(PodRejection::NotSelected));
+
+    let pod = PodBuilder::new(&request, "pod-1")
+        .dedupe([DedupeBy::CampaignId])
+        .assemble(bids);
+    assert_eq!(ids(&pod), ["a", "b", "d"]);
+}
+
+#[test]
+fn test_structured_pod_slots() {
+    let request = request(vec![
+        video("pod-1", |v| v.slotinpod = Some(-1)),
+        video("pod-1", |v| v.slotinpod = Some(0)),
+        video("pod-1", |v| v.slotinpod = Some(1)),
+        video("pod-2", |_| {}),
+    ]);
+
+    let pod = PodBuilder::new(&request, "pod-1").assemble([
+        candidate(Bid::builder("last", "1", 5.0).dur(15)),
+        candidate(Bid::builder("middle", "2", 5.0).dur(15)),
+        candidate(Bid::builder("middle-2", "2", 4.0).dur(15)),
+        candidate(Bid::builder("first", "3", 5.0).dur(15).slotinpod(2)),
+        candidate(Bid::builder("wrong-slot", "3", 9.0).dur(15).slotinpod(-1)),
+        candidate(Bid::builder("other-pod", "4", 9.0).dur(15)),
+    ]);
+
+    assert_eq!(ids(&pod), ["first", "middle", "last"]);
+    assert_eq!(pod.ads[0].imp_id, "3");
+    assert_eq!(pod.ads[0].position, SlotPosition::First);
+    assert_eq!(pod.ads[2].position, SlotPosition::Last);
+    assert_eq!(pod.duration, 45);
+    assert_eq!(rejection(&pod, "middle-2"), Some(PodRejection::NotSelected));
+    assert_eq!(
+        rejection(&pod, "wrong-slot"),
+        Some(PodRejection::SlotNotAvailable)
+    );
+    assert_eq!(rejection(&pod, "other-pod"), Some(PodRejection::NotInPod));
+}
+
+#[test]
+fn test_slot_guarantees_in_dynamic_pod() {
+    let request = request(vec![video("pod-1", |v| v.poddur = Some(60))]);
+
+    let pod = PodBuilder::new(&request, "pod-1").assemble([
+        candidate(Bid::builder("a", "1", 10.0).dur(15).slotinpod(1)),
+        candidate(Bid::builder("b", "1", 9.0).dur(15).slotinpod(1)),
+        candidate(Bid::builder("c", "1", 8.0).dur(15)),
+        candidate(Bid::builder("d", "1", 7.0).dur(15).slotinpod(2)),
+    ]);
+
+    assert_eq!(ids(&pod), ["a", "c", "d"]);
+    assert_eq!(pod.ads[2].position, SlotPosition::FirstOrLast);
+    assert_eq!(rejection(&pod, "b"), Some(PodRejection::NotSelected));
+}
+
+#[test]
+fn test_audio_pod() {
+    let request = request(vec![Imp::audio(["audio/mp4"]).with_audio(Audio {
+        mimes: vec!["audio/mp4".to_string()],
+        podid: Some("radio".to_string()),
+        poddur: Some(60),
+        maxseq: Some(4),
+        minduration: Some(15),
+        maxduration: Some(30),
+        ..Default::default()
+    })]);
+
+    let bids = [
+        candidate(Bid::builder("a", "1", 6.0).dur(30).cat(["IAB2"])),
+        candidate(Bid::builder("b", "1", 5.0).dur(15).cat(["IAB2"])),
+        candidate(Bid::builder("c", "1", 4.0).dur(15).cat(["IAB3"])),
+        candidate(Bid::builder("d", "1", 9.0).dur(45)),
+    ];
+
+    let pod = PodBuilder::new(&request, "radio").assemble(bids.clone());
+    assert_eq!(ids(&pod), ["a", "b", "c"]);
+    assert_eq!(rejection(&pod, "d"), Some(PodRejection::DurationNotAllowed));
+
+    let pod = PodBuilder::new(&request, "radio")
+        .dedupe([DedupeBy::Category])
+        .assemble(bids);
+    assert_eq!(ids(&pod), ["a", "c"]);
+    assert_eq!(pod.revenue, 10.0);
+}
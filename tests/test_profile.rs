@@ -0,0 +1,281 @@
+use iab::openrtb2::codes::MarkupType;
+use iab::openrtb2::*;
+use serde_json::json;
+
+fn eid(source: &str) -> EID {
+    EID {
+        source: Some(source.to_string()),
+        uids: Some(vec![UID {
+            id: Some("abc".to_string()),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+fn segments(count: usize) -> Data {
+    Data {
+        segment: Some(vec![Segment::default(); count]),
+        ..Default::default()
+    }
+}
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("1")
+        .tmax(300)
+        .imp(Imp::banner(300, 250).with_video(Video {
+            mimes: vec!["video/mp4".to_string()],
+            podid: Some("pod".to_string()),
+            ..Default::default()
+        }))
+        .imp(Imp::video(["video/mp4"]))
+        .imp(Imp::banner(728, 90))
+        .imp(Imp::audio(["audio/mp4"]))
+        .user(User {
+            eids: Some(vec![eid("liveramp.com"), eid("id5-sync.com")]),
+            data: Some(vec![segments(2), segments(3)]),
+            kwarray: Some(vec!["sports".to_string()]),
+            ..Default::default()
+        })
+        .source(Source {
+            schain: Some(SupplyChain {
+                complete: 1,
+                ver: "1.0".to_string(),
+                nodes: vec![SupplyChainNode {
+                    asi: "exchange.com".to_string(),
+                    sid: "1".to_string(),
+                    hp: Some(1),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+fn ids(shaped: &Shaped) -> Vec<&str> {
+    shaped
+        .request
+        .imp
+        .iter()
+        .map(|imp| imp.id.as_str())
+        .collect()
+}
+
+#[test]
+fn test_default_profile_keeps_everything() {
+    let request = request();
+    let shaped = BidderProfile::new().shape(&request);
+
+    assert_eq!(shaped.request, request);
+    assert!(shaped.removed.is_empty());
+    assert!(!shaped.is_empty());
+}
+
+#[test]
+fn test_unsupported_media() {
+    let profile = BidderProfile::new().media_types([MarkupType::Banner, MarkupType::Native]);
+    let shaped = profile.shape(&request());
+
+    assert_eq!(ids(&shaped), ["1", "3"]);
+    assert!(shaped.request.imp[0].video.is_none());
+    assert!(shaped.request.imp[0].banner.is_some());
+    assert_eq!(
+        shaped.removed,
+        [
+            Removal::Field {
+                path: "imp[0].video".to_string()
+            },
+            Removal::Field {
+                path: "imp[1].video".to_string()
+            },
+            Removal::UnsupportedImp {
+                id: "2".to_string()
+            },
+            Removal::Field {
+                path: "imp[3].audio".to_string()
+            },
+            Removal::UnsupportedImp {
+                id: "4".to_string()
+            },
+        ]
+    );
+
+    let shaped = BidderProfile::new().media_types([]).shape(&request());
+    assert!(shaped.is_empty());
+}
+
+#[test]
+fn test_openrtb_25() {
+    let mut request = request();
+    request.dooh = Some(DOOH::default());
+    let shaped = BidderProfile::new().openrtb_26(false).shape(&request);
+
+    let paths: Vec<_> = shaped
+        .removed
+        .iter()
+        .filter_map(|removal| match removal {
+            Removal::Field { path } => Some(path.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(paths, ["dooh", "imp[0].video.podid", "user.kwarray"]);
+
+    let source = shaped.request.source.as_ref().unwrap();
+    assert!(source.schain.is_none());
+    assert!(source.ext.as_ref().unwrap().get("schain").is_some());
+    assert!(shaped.request.validate().is_ok());
+
+    let shaped = BidderProfile::new().dooh(false).shape(&request);
+    assert_eq!(
+        shaped.removed,
+        [Removal::Field {
+            path: "dooh".to_string()
+        }]
+    );
+    assert!(shaped.request.source.unwrap().schain.is_some());
+}
+
+#[test]
+fn test_openrtb_25_privacy() {
+    let mut request = request();
+    request.regs = Some(Regs {
+        gdpr: Some(1),
+        us_privacy: Some("1YNN".to_string()),
+        gpp: Some("DBABMA~CPXxRfAPXxRfAAfKABENB-CgAAAAAAAAAAYgAAAAAAAA".to_string()),
+        gpp_sid: Some(vec![2]),
+        ..Default::default()
+    });
+    request.site = Some(Site {
+        content: Some(Content {
+            langb: Some("en".to_string()),
+            network: Some(Network::default()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    if let Some(user) = &mut request.user {
+        user.consent = Some("CPXxRfAPXxRfAAfKABENB".to_string());
+    }
+
+    let profile = BidderProfile::new()
+        .openrtb_26(false)
+        .eid_sources(["liveramp.com"]);
+    let shaped = profile.shape(&request);
+
+    let regs = shaped.request.regs.as_ref().unwrap();
+    assert_eq!(
+        regs,
+        &Regs {
+            ext: Some(json!({"gdpr": 1, "us_privacy": "1YNN"})),
+            ..Default::default()
+        }
+    );
+    let user = shaped.request.user.as_ref().unwrap();
+    assert!(user.consent.is_none() && user.eids.is_none());
+    let ext = user.ext.as_ref().unwrap();
+    assert_eq!(ext["consent"], "CPXxRfAPXxRfAAfKABENB");
+    assert_eq!(ext["eids"].as_array().unwrap().len(), 1);
+    assert_eq!(ext["eids"][0]["source"], "liveramp.com");
+    let content = shaped.request.site.as_ref().unwrap().content.as_ref();
+    assert_eq!(content, Some(&Content::default()));
+
+    for path in [
+        "site.content.langb",
+        "site.content.network",
+        "regs.gpp",
+        "regs.gpp_sid",
+    ] {
+        let removal = Removal::Field {
+            path: path.to_string(),
+        };
+        assert!(shaped.removed.contains(&removal), "{path}");
+    }
+
+    request.regs = Some(Regs {
+        gdpr: Some(1),
+        ext: Some(json!("exchange")),
+        ..Default::default()
+    });
+    let shaped = profile.shape(&request);
+    assert!(shaped.removed.contains(&Removal::Field {
+        path: "regs.gdpr".to_string()
+    }));
+    assert_eq!(shaped.request.regs.unwrap().gdpr, None);
+}
+
+#[test]
+fn test_user_pruning() {
+    let profile = BidderProfile::new()
+        .eid_sources(["LiveRamp.com"])
+        .max_segments(3);
+    let shaped = profile.shape(&request());
+
+    let user = shaped.request.user.as_ref().unwrap();
+    let eids = user.eids.as_ref().unwrap();
+    assert_eq!(eids.len(), 1);
+    assert_eq!(eids[0].source.as_deref(), Some("liveramp.com"));
+    let data = user.data.as_ref().unwrap();
+    assert_eq!(data[0].segment.as_ref().unwrap().len(), 2);
+    assert_eq!(data[1].segment.as_ref().unwrap().len(), 1);
+    assert_eq!(
+        shaped.removed,
+        [
+            Removal::Eid {
+                source: Some("id5-sync.com".to_string())
+            },
+            Removal::Segments { data: 1, count: 2 },
+        ]
+    );
+
+    let shaped = BidderProfile::new()
+        .eid_sources(Vec::<String>::new())
+        .shape(&request());
+    assert!(shaped.request.user.unwrap().eids.is_none());
+}
+
+#[test]
+fn test_imp_limits_and_tmax() {
+    let request = request();
+    let shaped = BidderProfile::new()
+        .max_imps(3)
+        .tmax_margin(50)
+        .max_tmax(200)
+        .shape(&request);
+
+    assert_eq!(ids(&shaped), ["1", "2", "3"]);
+    assert_eq!(
+        shaped.removed,
+        [Removal::ExcessImp {
+            id: "4".to_string()
+        }]
+    );
+    assert_eq!(shaped.request.tmax, Some(200));
+
+    let shaped = BidderProfile::new().tmax_margin(50).shape(&request);
+    assert_eq!(shaped.request.tmax, Some(250));
+    let shaped = BidderProfile::new().tmax_margin(i64::MAX).shape(&request);
+    assert_eq!(shaped.request.tmax, Some(0));
+    let mut negative = request.clone();
+    negative.tmax = Some(i64::MIN);
+    let shaped = BidderProfile::new().tmax_margin(50).shape(&negative);
+    assert_eq!(shaped.request.tmax, Some(0));
+
+    // Each impression dropped brings the request closer to the limit.
+    let full = serde_json::to_vec(&request).unwrap().len();
+    let shaped = BidderProfile::new().max_bytes(full - 1).shape(&request);
+    assert_eq!(ids(&shaped), ["1", "2", "3"]);
+    assert_eq!(
+        shaped.removed,
+        [Removal::OversizedImp {
+            id: "4".to_string()
+        }]
+    );
+    assert!(BidderProfile::new()
+        .max_bytes(10)
+        .shape(&request)
+        .is_empty());
+}
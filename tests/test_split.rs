@@ -0,0 +1,192 @@
+use iab::openrtb2::codes::MarkupType;
+use iab::openrtb2::*;
+
+fn request() -> BidRequest {
+    BidRequest::builder()
+        .id("auction")
+        .at(1)
+        .tmax(120)
+        .imp(Imp::banner(300, 250).with_video(Video {
+            mimes: vec!["video/mp4".to_string()],
+            ..Default::default()
+        }))
+        .imp(Imp::banner(728, 90))
+        .imp(Imp::audio(["audio/mp4"]))
+        .site(Site {
+            domain: Some("example.com".to_string()),
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+fn response(id: &str, bids: Vec<(&str, BidBuilder)>) -> BidResponse {
+    bids.into_iter()
+        .fold(BidResponse::builder(id), |response, (seat, bid)| {
+            response.bid(seat, bid)
+        })
+        .build()
+        .unwrap()
+}
+
+fn bids(response: &BidResponse) -> Vec<(Option<&str>, &str, &str)> {
+    response
+        .seatbid
+        .iter()
+        .flatten()
+        .flat_map(|s| {
+            s.bid
+                .iter()
+                .map(move |b| (s.seat.as_deref(), b.id.as_str(), b.impid.as_str()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_split_by_imp() {
+    let request = request();
+    let requests = request.split_by_imp();
+
+    let ids: Vec<_> = requests.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["auction:imp:1", "auction:imp:2", "auction:imp:3"]);
+    for (sub, imp) in requests.iter().zip(&request.imp) {
+        assert_eq!(&sub.imp, std::slice::from_ref(imp));
+        assert_eq!(sub.site, request.site);
+        assert_eq!(sub.tmax, Some(120));
+        assert!(sub.validate().is_ok());
+    }
+    assert_eq!(request.split_by_imp(), requests);
+}
+
+#[test]
+fn test_split_by_media_type() {
+    let request = request();
+    let requests = request.split_by_media_type();
+
+    let ids: Vec<_> = requests.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["auction:banner", "auction:video", "auction:audio"]);
+
+    let banner = &requests[0];
+    assert_eq!(banner.imp.len(), 2);
+    assert!(banner.imp.iter().all(|imp| imp.video.is_none()));
+    assert_eq!(banner.imp[0].banner, request.imp[0].banner);
+
+    let video = &requests[1];
+    assert_eq!(video.imp.len(), 1);
+    assert_eq!(video.imp[0].id, "1");
+    assert!(video.imp[0].banner.is_none());
+    assert!(video.imp[0].video.is_some());
+    assert!(requests.iter().all(|r| r.validate().is_ok()));
+
+    assert_eq!(MarkupType::from_code(2), Some(MarkupType::Video));
+    assert_eq!(MarkupType::from_code(5), None);
+}
+
+#[test]
+fn test_merge_imp_responses() {
+    let request = request();
+    let requests = request.split_by_imp();
+    let first = response(&requests[0].id, vec![("a", Bid::builder("1", "1", 1.0))]);
+    let second = response(
+        &requests[1].id,
+        vec![
+            ("a", Bid::builder("1", "2", 2.0)),
+            ("b", Bid::builder("2", "2", 3.0)),
+        ],
+    );
+    let none = BidResponse {
+        id: requests[2].id.clone(),
+        nbr: Some(2),
+        ..Default::default()
+    };
+
+    let merged = request.merge_responses([&first, &second, &none]).unwrap();
+    assert_eq!(merged.id, "auction");
+    assert_eq!(merged.nbr, None);
+    assert_eq!(merged.cur.as_deref(), Some("USD"));
+    assert_eq!(
+        bids(&merged),
+        [
+            (Some("a"), "1", "1"),
+            (Some("a"), "imp:2:1", "2"),
+            (Some("b"), "2", "2"),
+        ]
+    );
+    assert!(merged.validate().is_ok());
+
+    // Bids must be for the impression of the sub-request answered.
+    let wrong = response(&requests[0].id, vec![("a", Bid::builder("1", "2", 1.0))]);
+    assert_eq!(
+        request.merge_responses([&wrong]),
+        Err(MergeError::UnknownImp {
+            response: "auction:imp:1".to_string(),
+            impid: "2".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_merge_media_type_responses() {
+    let request = request();
+    let requests = request.split_by_media_type();
+    let banner = response(&requests[0].id, vec![("a", Bid::builder("1", "1", 1.0))]);
+    let video = response(
+        &requests[1].id,
+        vec![("a", Bid::builder("2", "1", 5.0).mtype(2))],
+    );
+
+    let merged = request.merge_responses([&banner, &video]).unwrap();
+    let seatbids = merged.seatbid.as_ref().unwrap();
+    assert_eq!(seatbids.len(), 1);
+    let mtypes: Vec<_> = seatbids[0].bid.iter().map(|b| b.mtype).collect();
+    assert_eq!(mtypes, [Some(1), Some(2)]);
+
+    // The audio impression offers no video.
+    let wrong = response(&requests[1].id, vec![("a", Bid::builder("1", "3", 1.0))]);
+    assert!(matches!(
+        request.merge_responses([&wrong]),
+        Err(MergeError::UnknownImp { .. })
+    ));
+}
+
+#[test]
+fn test_merge_errors_and_no_bids() {
+    let request = request();
+    let other = response("other:banner", vec![("a", Bid::builder("1", "1", 1.0))]);
+    assert_eq!(
+        request.merge_responses([&other]),
+        Err(MergeError::UnknownResponse {
+            id: "other:banner".to_string()
+        })
+    );
+    let unknown = response("auction:imp:9", vec![]);
+    assert!(request.merge_responses([&unknown]).is_err());
+
+    let usd = response("auction:banner", vec![("a", Bid::builder("1", "1", 1.0))]);
+    let eur = BidResponse {
+        id: "auction:video".to_string(),
+        cur: Some("EUR".to_string()),
+        ..usd.clone()
+    };
+    assert!(matches!(
+        request.merge_responses([&usd, &eur]),
+        Err(MergeError::CurrencyMismatch { .. })
+    ));
+
+    let no_bid = |id: &str, nbr: Option<i64>| BidResponse {
+        id: id.to_string(),
+        nbr,
+        ..Default::default()
+    };
+    let merged = request
+        .merge_responses([
+            &no_bid("auction:banner", None),
+            &no_bid("auction:video", Some(8)),
+            &no_bid("auction:audio", Some(2)),
+        ])
+        .unwrap();
+    assert!(merged.seatbid.is_none());
+    assert_eq!(merged.nbr, Some(8));
+    assert_eq!(merged.cur, None);
+    assert_eq!(request.merge_responses([]).unwrap().nbr, Some(0));
+}
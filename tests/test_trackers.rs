@@ -0,0 +1,167 @@
+use iab::openrtb2::codes::Protocol;
+use iab::openrtb2::*;
+use iab::vast::*;
+use std::fs;
+use std::path::Path;
+
+fn trackers() -> Trackers {
+    Trackers::new()
+        .impression("https://exchange.com/imp?a=1&b=2")
+        .error("https://exchange.com/error?code=[ERRORCODE]")
+        .event("start", "https://exchange.com/start")
+        .event("complete", "https://exchange.com/complete")
+}
+
+fn fixture_bid() -> Bid {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/response/vast-xml-document-returned-inline.json");
+    let response: BidResponse = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    response.seatbid.unwrap().remove(0).bid.remove(0)
+}
+
+fn video(protocols: &[i64]) -> Imp {
+    let mut imp: Imp = Imp::video(["video/mp4"]).into();
+    imp.video.as_mut().unwrap().protocols = Some(protocols.to_vec());
+    imp
+}
+
+#[test]
+fn test_inject_inline() {
+    let mut bid = fixture_bid();
+    let original = Vast::parse(bid.adm.as_deref().unwrap()).unwrap();
+
+    trackers().apply(&mut bid).unwrap();
+    let injected = bid.adm.clone().unwrap();
+    trackers().apply(&mut bid).unwrap();
+    assert_eq!(bid.adm.as_deref(), Some(injected.as_str()));
+
+    let vast = Vast::parse(&injected).unwrap();
+    let AdKind::InLine(inline) = &vast.ads[0].kind else {
+        panic!("expected an inline ad");
+    };
+    let urls: Vec<_> = inline.impressions.iter().map(|i| i.url.as_str()).collect();
+    assert_eq!(
+        urls,
+        ["http://sample.com", "https://exchange.com/imp?a=1&b=2"]
+    );
+    assert_eq!(
+        inline.errors,
+        ["https://exchange.com/error?code=[ERRORCODE]"]
+    );
+    let linear = inline.creatives[0].linear.as_ref().unwrap();
+    let events: Vec<_> = linear
+        .tracking_events
+        .iter()
+        .map(|t| t.event.as_str())
+        .collect();
+    assert_eq!(events, ["start", "complete"]);
+    assert_eq!(vast.duration(), original.duration());
+    assert_eq!(
+        vast.media_files().collect::<Vec<_>>(),
+        original.media_files().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_inject_keeps_unknown_elements() {
+    let markup = r#"<VAST version="4.1">
+      <Ad id="1"><Wrapper>
+        <AdSystem>DSP</AdSystem>
+        <VASTAdTagURI><![CDATA[https://dsp.com/vast]]></VASTAdTagURI>
+        <Impression><![CDATA[https://exchange.com/imp?a=1&b=2]]></Impression>
+        <Pricing model="CPM" currency="USD">1.5</Pricing>
+      </Wrapper></Ad>
+      <Ad id="2"><InLine>
+        <AdSystem>DSP</AdSystem><AdTitle>Overlay</AdTitle>
+        <Creatives><Creative><NonLinearAds><NonLinear width="300" height="50"/></NonLinearAds></Creative></Creatives>
+      </InLine></Ad>
+    </VAST>"#;
+
+    let injected = trackers().inject(markup).unwrap();
+    assert!(injected.contains(r#"<Pricing model="CPM" currency="USD">1.5</Pricing>"#));
+    assert!(injected.contains(r#"<NonLinear width="300" height="50"/>"#));
+    assert_eq!(trackers().inject(&injected).unwrap(), injected);
+
+    let vast = Vast::parse(&injected).unwrap();
+    let AdKind::Wrapper(wrapper) = &vast.ads[0].kind else {
+        panic!("expected a wrapper");
+    };
+    assert_eq!(wrapper.impressions.len(), 1);
+    let linear = wrapper.creatives[0].linear.as_ref().unwrap();
+    assert_eq!(linear.tracking_events.len(), 2);
+    assert_eq!(linear.duration, None);
+
+    // Event trackers are only added to linear creatives of inline ads.
+    let AdKind::InLine(inline) = &vast.ads[1].kind else {
+        panic!("expected an inline ad");
+    };
+    assert_eq!(inline.impressions.len(), 1);
+    assert_eq!(inline.creatives[0].linear, None);
+
+    let empty = trackers().inject(r#"<VAST version="3.0"></VAST>"#).unwrap();
+    assert_eq!(
+        Vast::parse(&empty).unwrap().errors,
+        ["https://exchange.com/error?code=[ERRORCODE]"]
+    );
+    assert!(matches!(
+        trackers().inject("<div></div>"),
+        Err(VastError::NotVast { .. })
+    ));
+}
+
+#[test]
+fn test_wrap() {
+    let bid: Bid = Bid::builder("bid-1", "1", 2.0)
+        .nurl("https://dsp.com/win?p=1.5&id=bid-1")
+        .into();
+
+    let vast = trackers()
+        .wrap("Exchange", &bid, &video(&[2, 3, 5, 6, 7, 8]))
+        .unwrap();
+    assert_eq!(vast.version, "4.0");
+    assert_eq!(vast.protocol(), Some(Protocol::Vast40Wrapper));
+    assert_eq!(vast.ads[0].id.as_deref(), Some("bid-1"));
+    let AdKind::Wrapper(wrapper) = &vast.ads[0].kind else {
+        panic!("expected a wrapper");
+    };
+    assert_eq!(wrapper.ad_system.name, "Exchange");
+    assert_eq!(
+        wrapper.vast_ad_tag_uri,
+        "https://dsp.com/win?p=1.5&id=bid-1"
+    );
+    assert_eq!(
+        wrapper.creatives[0]
+            .linear
+            .as_ref()
+            .unwrap()
+            .tracking_events
+            .len(),
+        2
+    );
+    assert_eq!(Vast::parse(&vast.to_xml()).unwrap(), vast);
+
+    let vast = trackers()
+        .wrap("Exchange", &bid, &video(&[14, 12, 16, 13]))
+        .unwrap();
+    assert_eq!(vast.protocol(), Some(Protocol::Vast43Wrapper));
+
+    let mut audio: Imp = Imp::audio(["audio/mp4"]).into();
+    audio.audio.as_mut().unwrap().protocols = Some(vec![5]);
+    let vast = trackers().wrap("Exchange", &bid, &audio).unwrap();
+    assert_eq!(vast.version, "2.0");
+
+    assert_eq!(
+        trackers().wrap("Exchange", &bid, &video(&[2, 3, 4])),
+        Err(WrapError::UnsupportedProtocols)
+    );
+    assert_eq!(
+        Trackers::new().wrap("Exchange", &bid, &video(&[6])),
+        Err(WrapError::MissingImpression)
+    );
+    let served: Bid = Bid::builder("bid-2", "1", 2.0).into();
+    assert_eq!(
+        trackers().wrap("Exchange", &served, &video(&[6])),
+        Err(WrapError::MissingNurl)
+    );
+    assert_eq!(wrapper_protocol(&[]), None);
+}
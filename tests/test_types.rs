@@ -0,0 +1,137 @@
+use iab::openrtb2::codes::{LossReason, NoBidReason};
+use iab::openrtb2::types::*;
+use iab::openrtb2::*;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_identifiers() {
+    assert_eq!(ImpId::new("1").unwrap().as_str(), "1");
+    assert_eq!(DealId::new("AB-1234").unwrap(), "AB-1234");
+    assert_eq!(
+        SeatId::new(""),
+        Err(InvalidValue {
+            expected: "seat ID",
+            value: String::new()
+        })
+    );
+    assert_eq!(
+        BidId::new("").unwrap_err().to_string(),
+        "invalid bid ID: \"\""
+    );
+}
+
+#[test]
+fn test_codes() {
+    assert!(CurrencyCode::new("USD").is_ok());
+    assert!(CurrencyCode::new("EUR").is_ok());
+    assert!(CurrencyCode::new("usd").is_err());
+    assert!(CurrencyCode::new("USA").is_err());
+    assert!(CurrencyCode::new("XCG").is_ok());
+    assert!(CurrencyCode::new("ZWG").is_ok());
+    assert!(CurrencyCode::new("HRK").is_err());
+    assert!(CurrencyCode::new("SLL").is_err());
+
+    assert!(CountryCode::new("USA").is_ok());
+    assert!(CountryCode::new("BRA").is_ok());
+    assert!(CountryCode::new("US").is_err());
+    assert!(CountryCode::new("USD").is_err());
+
+    assert!(LanguageCode::new("en").is_ok());
+    assert!(LanguageCode::new("pt").is_ok());
+    assert!(LanguageCode::new("EN").is_err());
+    assert!(LanguageCode::new("eng").is_err());
+}
+
+#[test]
+fn test_domains() {
+    assert!(Domain::new("ford.com").is_ok());
+    assert!(Domain::new("www.ford.com").is_ok());
+    assert!(Domain::new("my-site.co.uk").is_ok());
+    assert!(Domain::new("ford").is_err());
+    assert!(Domain::new("-ford.com").is_err());
+    assert!(Domain::new("ford..com").is_err());
+    assert!(Domain::new("http://ford.com").is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Typed {
+    cur: CurrencyCode,
+    country: CountryCode,
+    deal: Option<DealId>,
+}
+
+#[test]
+fn test_serde_transparent() {
+    let typed: Typed =
+        serde_json::from_str(r#"{"cur": "USD", "country": "USA", "deal": "AB-1234"}"#).unwrap();
+
+    assert_eq!(typed.cur, CurrencyCode::new("USD").unwrap());
+    assert_eq!(
+        serde_json::to_value(&typed).unwrap(),
+        serde_json::json!({"cur": "USD", "country": "USA", "deal": "AB-1234"})
+    );
+
+    let err = serde_json::from_str::<Typed>(r#"{"cur": "USA", "country": "USA", "deal": null}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid currency code: \"USA\""));
+}
+
+#[test]
+fn test_typed_accessors() {
+    let imp = Imp {
+        id: "1".to_string(),
+        bidfloorcur: Some("EUR".to_string()),
+        ..Default::default()
+    };
+    let bid = Bid {
+        id: "a".to_string(),
+        impid: "1".to_string(),
+        dealid: Some("AB-1234".to_string()),
+        adomain: Some(vec!["ford.com".to_string()]),
+        ..Default::default()
+    };
+
+    assert_eq!(bid.imp_id().unwrap(), imp.imp_id().unwrap());
+    assert_eq!(bid.deal_id().unwrap().unwrap(), "AB-1234");
+    assert_eq!(
+        bid.advertiser_domains().unwrap(),
+        vec![Domain::new("ford.com").unwrap()]
+    );
+    assert_eq!(imp.floor_currency().unwrap().unwrap(), "EUR");
+
+    let geo = Geo {
+        country: Some("US".to_string()),
+        ..Default::default()
+    };
+    assert!(geo.country_code().is_err());
+
+    let request = BidRequest {
+        cur: Some(vec!["USD".to_string(), "EUR".to_string()]),
+        ..Default::default()
+    };
+    assert_eq!(request.currencies().unwrap().len(), 2);
+    assert!(request.blocked_seats().unwrap().is_empty());
+}
+
+#[test]
+fn test_reason_codes() {
+    for code in (0..=17).chain([500, 1234]) {
+        assert_eq!(NoBidReason::from_code(code).unwrap().code(), code);
+    }
+    assert_eq!(NoBidReason::from_code(18), None);
+    assert_eq!(NoBidReason::from_code(-1), None);
+    assert_eq!(NoBidReason::InvalidRequest.to_string(), "2");
+
+    for code in (0..=10).chain(100..=104).chain(200..=213).chain([1000]) {
+        assert_eq!(LossReason::from_code(code).unwrap().code(), code);
+    }
+    assert_eq!(LossReason::from_code(500), None);
+
+    let response = BidResponse::builder("1")
+        .no_bid_reason(NoBidReason::UnmatchedUser)
+        .build()
+        .unwrap();
+    assert_eq!(response.nbr, Some(8));
+    assert_eq!(response.no_bid_reason(), Some(NoBidReason::UnmatchedUser));
+    assert_eq!(BidResponse::default().no_bid_reason(), None);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "typescript")]
+
+use iab::typescript;
+use std::fs;
+
+/// Returns the body of an exported interface.
+fn interface<'a>(definitions: &'a str, name: &str) -> &'a str {
+    let start = definitions
+        .find(&format!("export interface {name} {{"))
+        .unwrap_or_else(|| panic!("Missing interface {name}"));
+    let end = start + definitions[start..].find("\n}\n").unwrap();
+    &definitions[start..end]
+}
+
+#[test]
+fn test_required_and_optional_fields() {
+    let definitions = typescript::definitions();
+
+    let request = interface(&definitions, "BidRequest");
+    assert!(request.contains("  id: string;"));
+    assert!(request.contains("  imp: Imp[];"));
+    assert!(request.contains("  tmax?: number;"));
+    assert!(request.contains("  ext?: unknown;"));
+
+    let bid = interface(&definitions, "Bid");
+    assert!(bid.contains("  price: number;"));
+    assert!(bid.contains("  adomain?: string[];"));
+
+    let response = interface(&definitions, "BidResponse");
+    assert!(response.contains("  seatbid?: SeatBid[];"));
+
+    // Every array has an element type.
+    assert!(!definitions.contains(": [];"));
+}
+
+#[test]
+fn test_serde_renames() {
+    let definitions = typescript::definitions();
+
+    assert!(interface(&definitions, "Imp").contains("  native?: Native;"));
+    assert!(interface(&definitions, "Site").contains("  ref?: string;"));
+    assert!(interface(&definitions, "Metric").contains("  type: string;"));
+    assert!(interface(&definitions, "Geo").contains("  type?: number;"));
+    assert!(!definitions.contains("  native_markup?:"));
+    assert!(!definitions.contains("  type_"));
+    assert!(!definitions.contains("  refer?:"));
+}
+
+#[test]
+fn test_deprecations() {
+    let definitions = typescript::definitions();
+
+    let video = interface(&definitions, "Video");
+    assert!(video.contains("   * @deprecated\n   */\n  placement?: number;"));
+    assert!(video.contains("   * @deprecated\n   */\n  sequence?: number;"));
+    assert!(!video.contains("   * @deprecated\n   */\n  plcmt?: number;"));
+
+    let bid = interface(&definitions, "Bid");
+    assert!(bid.contains("   * @deprecated\n   */\n  api?: number;"));
+}
+
+#[test]
+fn test_every_nested_type_is_defined() {
+    let definitions = typescript::definitions();
+
+    for name in [
+        "BidRequest",
+        "BidResponse",
+        "SeatBid",
+        "Bid",
+        "Imp",
+        "Banner",
+        "Video",
+        "Audio",
+        "Native",
+        "Pmp",
+        "Deal",
+        "DurFloors",
+        "Device",
+        "UserAgent",
+        "BrandVersion",
+        "SupplyChainNode",
+        "EID",
+        "UID",
+        "DOOH",
+        "RefSettings",
+    ] {
+        interface(&definitions, name);
+    }
+}
+
+#[test]
+fn test_write_definitions() {
+    let path = std::env::temp_dir().join("iab-openrtb2.d.ts");
+    typescript::write_definitions(&path).expect("Failed to write definitions");
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        typescript::definitions()
+    );
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,310 @@
+use iab::openrtb2::codes::Protocol;
+use iab::openrtb2::*;
+use iab::vast::*;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const INLINE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<VAST version="4.2" xmlns="http://www.iab.com/VAST">
+  <Ad id="ad-1" sequence="1" adType="video">
+    <InLine>
+      <AdSystem version="2.1">DSP</AdSystem>
+      <Error><![CDATA[https://dsp.com/error?code=[ERRORCODE]]]></Error>
+      <Impression id="imp"><![CDATA[https://dsp.com/imp?a=1&b=2]]></Impression>
+      <AdServingId>serving-1</AdServingId>
+      <AdTitle>Spring &amp; summer</AdTitle>
+      <Advertiser>Brand</Advertiser>
+      <AdVerifications>
+        <Verification vendor="verifier.com-omid">
+          <JavaScriptResource apiFramework="omid" browserOptional="true">
+            <![CDATA[https://verifier.com/omid.js]]>
+          </JavaScriptResource>
+          <VerificationParameters><![CDATA[{"id": 1}]]></VerificationParameters>
+          <TrackingEvents>
+            <Tracking event="verificationNotExecuted"><![CDATA[https://verifier.com/ne?r=[REASON]]]></Tracking>
+          </TrackingEvents>
+        </Verification>
+      </AdVerifications>
+      <Creatives>
+        <Creative id="c-1" sequence="1" adId="a-1">
+          <UniversalAdId idRegistry="ad-id.org">CNPA0484000H</UniversalAdId>
+          <Linear skipoffset="00:00:05">
+            <Duration>00:00:15.500</Duration>
+            <TrackingEvents>
+              <Tracking event="start"><![CDATA[https://dsp.com/start]]></Tracking>
+              <Tracking event="progress" offset="00:00:10"><![CDATA[https://dsp.com/10s]]></Tracking>
+            </TrackingEvents>
+            <VideoClicks>
+              <ClickThrough id="landing"><![CDATA[https://brand.com]]></ClickThrough>
+              <ClickTracking><![CDATA[https://dsp.com/click]]></ClickTracking>
+            </VideoClicks>
+            <MediaFiles>
+              <MediaFile id="hd" delivery="progressive" type="video/mp4" width="1920" height="1080"
+                  bitrate="4000" codec="H.264" scalable="true" maintainAspectRatio="1">
+                <![CDATA[https://cdn.dsp.com/hd.mp4]]>
+              </MediaFile>
+              <MediaFile delivery="streaming" type="application/x-mpegURL" minBitrate="500" maxBitrate="2500">
+                <![CDATA[https://cdn.dsp.com/ad.m3u8]]>
+              </MediaFile>
+            </MediaFiles>
+          </Linear>
+        </Creative>
+        <Creative id="c-2">
+          <CompanionAds>
+            <Companion id="banner" width="300" height="250" adSlotID="side">
+              <StaticResource creativeType="image/png"><![CDATA[https://cdn.dsp.com/300x250.png]]></StaticResource>
+              <AltText>Brand</AltText>
+              <CompanionClickThrough><![CDATA[https://brand.com/companion]]></CompanionClickThrough>
+              <TrackingEvents>
+                <Tracking event="creativeView"><![CDATA[https://dsp.com/companion]]></Tracking>
+              </TrackingEvents>
+            </Companion>
+          </CompanionAds>
+        </Creative>
+      </Creatives>
+      <Extensions>
+        <Extension type="dsp"><Floor currency="USD">1.5</Floor></Extension>
+      </Extensions>
+    </InLine>
+  </Ad>
+</VAST>"#;
+
+#[test]
+fn test_parse_inline() {
+    let vast = Vast::parse(INLINE).unwrap();
+    assert_eq!(vast.version, "4.2");
+    assert_eq!(vast.protocol(), Some(Protocol::Vast42));
+    assert_eq!(vast.duration(), Some(Duration::from_millis(15_500)));
+
+    let ad = &vast.ads[0];
+    assert_eq!(ad.id.as_deref(), Some("ad-1"));
+    assert_eq!(ad.sequence, Some(1));
+    assert_eq!(ad.ad_type.as_deref(), Some("video"));
+    let AdKind::InLine(inline) = &ad.kind else {
+        panic!("expected an inline ad");
+    };
+    assert_eq!(inline.ad_system.version.as_deref(), Some("2.1"));
+    assert_eq!(inline.ad_title, "Spring & summer");
+    assert_eq!(inline.ad_serving_id.as_deref(), Some("serving-1"));
+    assert_eq!(inline.impressions[0].url, "https://dsp.com/imp?a=1&b=2");
+    assert_eq!(inline.errors, ["https://dsp.com/error?code=[ERRORCODE]"]);
+
+    let verification = &inline.ad_verifications[0];
+    assert_eq!(verification.vendor.as_deref(), Some("verifier.com-omid"));
+    assert_eq!(
+        verification.javascript_resources[0],
+        JavaScriptResource {
+            api_framework: Some("omid".to_string()),
+            browser_optional: Some(true),
+            url: "https://verifier.com/omid.js".to_string(),
+        }
+    );
+    assert_eq!(
+        verification.verification_parameters.as_deref(),
+        Some(r#"{"id": 1}"#)
+    );
+    assert_eq!(
+        verification.tracking_events[0].event,
+        "verificationNotExecuted"
+    );
+
+    let creative = &inline.creatives[0];
+    assert_eq!(creative.ad_id.as_deref(), Some("a-1"));
+    assert_eq!(
+        creative.universal_ad_id.as_ref().unwrap().value,
+        "CNPA0484000H"
+    );
+    let linear = creative.linear.as_ref().unwrap();
+    assert_eq!(linear.skip_offset.as_deref(), Some("00:00:05"));
+    assert_eq!(
+        linear.tracking_events[1].offset.as_deref(),
+        Some("00:00:10")
+    );
+    let clicks = linear.video_clicks.as_ref().unwrap();
+    assert_eq!(clicks.click_through.as_deref(), Some("https://brand.com"));
+    assert_eq!(clicks.click_tracking, ["https://dsp.com/click"]);
+
+    let files: Vec<_> = vast.media_files().collect();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].url, "https://cdn.dsp.com/hd.mp4");
+    assert_eq!((files[0].width, files[0].height), (Some(1920), Some(1080)));
+    assert_eq!(files[0].bitrate, Some(4000));
+    assert_eq!(files[0].maintain_aspect_ratio, Some(true));
+    assert_eq!(files[1].delivery, "streaming");
+    assert_eq!(files[1].max_bitrate, Some(2500));
+
+    let companion = &inline.creatives[1].companions[0];
+    assert_eq!(companion.ad_slot_id.as_deref(), Some("side"));
+    assert_eq!(
+        companion.static_resource.as_ref().unwrap().creative_type,
+        "image/png"
+    );
+    assert_eq!(companion.tracking_events[0].event, "creativeView");
+
+    assert_eq!(
+        inline.extensions[0],
+        Extension {
+            extension_type: Some("dsp".to_string()),
+            xml: r#"<Floor currency="USD">1.5</Floor>"#.to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let vast = Vast::parse(INLINE).unwrap();
+    let xml = vast.to_xml();
+    assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><VAST version="4.2">"#));
+    assert!(xml.contains("<AdTitle><![CDATA[Spring & summer]]></AdTitle>"));
+    assert!(
+        xml.contains("<Impression id=\"imp\"><![CDATA[https://dsp.com/imp?a=1&b=2]]></Impression>")
+    );
+    assert!(xml.contains("<Duration>00:00:15.500</Duration>"));
+    assert_eq!(Vast::parse(&xml).unwrap(), vast);
+
+    let wrapper = Vast {
+        version: "3.0".to_string(),
+        ads: vec![Ad {
+            id: Some("w".to_string()),
+            sequence: None,
+            conditional_ad: None,
+            ad_type: None,
+            kind: AdKind::Wrapper(Wrapper {
+                ad_system: AdSystem {
+                    name: "Exchange".to_string(),
+                    version: None,
+                },
+                vast_ad_tag_uri: "https://dsp.com/vast?id=1&w=2".to_string(),
+                follow_additional_wrappers: Some(false),
+                impressions: vec![Impression {
+                    id: None,
+                    url: "https://exchange.com/imp".to_string(),
+                }],
+                ..Default::default()
+            }),
+        }],
+        errors: Vec::new(),
+    };
+    assert_eq!(wrapper.protocol(), Some(Protocol::Vast30Wrapper));
+    assert_eq!(wrapper.duration(), None);
+    assert_eq!(
+        wrapper.to_xml(),
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<VAST version="3.0"><Ad id="w"><Wrapper followAdditionalWrappers="false">"#,
+            "<AdSystem>Exchange</AdSystem>",
+            "<VASTAdTagURI><![CDATA[https://dsp.com/vast?id=1&w=2]]></VASTAdTagURI>",
+            "<Impression>https://exchange.com/imp</Impression>",
+            "</Wrapper></Ad></VAST>"
+        )
+    );
+    assert_eq!(Vast::parse(&wrapper.to_xml()).unwrap(), wrapper);
+}
+
+#[test]
+fn test_fixture_duration() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/response/vast-xml-document-returned-inline.json");
+    let response: BidResponse = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    let bid = &response.seatbid.unwrap()[0].bid[0];
+
+    let vast = Vast::parse(bid.adm.as_deref().unwrap()).unwrap();
+    assert_eq!(vast.protocol(), Some(Protocol::Vast20));
+    assert_eq!(vast.duration(), Some(Duration::from_secs(30)));
+    let file = vast.media_files().next().unwrap();
+    assert_eq!(file.url, "http://sample.com/video.mp4");
+    assert_eq!(file.mime_type, "video/mp4");
+    assert_eq!(
+        (file.width, file.height, file.bitrate),
+        (Some(640), Some(480), Some(256))
+    );
+
+    let linear = vast.ads[0].creatives()[0].linear.as_ref().unwrap();
+    assert_eq!(
+        linear
+            .video_clicks
+            .as_ref()
+            .unwrap()
+            .click_through
+            .as_deref(),
+        Some("http://sample.com/openrtb test")
+    );
+    assert!(linear.tracking_events.is_empty());
+}
+
+#[test]
+fn test_invalid_documents() {
+    assert_eq!(
+        Vast::parse("<html></html>"),
+        Err(VastError::NotVast {
+            root: "html".to_string()
+        })
+    );
+    assert_eq!(
+        Vast::parse(r#"<VAST version="3.0"><Ad id="1"></Ad></VAST>"#),
+        Err(VastError::MissingElement {
+            parent: "Ad",
+            name: "InLine"
+        })
+    );
+    assert!(matches!(
+        Vast::parse(r#"<VAST version="3.0"><Ad></VAST>"#),
+        Err(VastError::InvalidXml { .. })
+    ));
+
+    let empty = Vast::parse(
+        r#"<VAST version="4.0"><Error><![CDATA[https://dsp.com/noad]]></Error></VAST>"#,
+    )
+    .unwrap();
+    assert!(empty.ads.is_empty());
+    assert_eq!(empty.errors, ["https://dsp.com/noad"]);
+    assert_eq!(empty.protocol(), Some(Protocol::Vast40));
+    assert_eq!(Protocol::from_code(14), Some(Protocol::Vast42Wrapper));
+    assert_eq!(Protocol::Daast10.vast_version(), None);
+}
+
+#[test]
+fn test_duration_overflow() {
+    let overflowing = INLINE.replace("00:00:15.500", "18446744073709551615:00:00");
+    let vast = Vast::parse(&overflowing).unwrap();
+    assert_eq!(vast.duration(), None);
+
+    let longest = INLINE.replace("00:00:15.500", "5124095576030431:00:15.999");
+    let vast = Vast::parse(&longest).unwrap();
+    assert_eq!(vast.duration(), Some(Duration::new(u64::MAX, 999_000_000)));
+
+    let bid: Bid = Bid::builder("1", "1", 1.0).adm(overflowing).into();
+    let Ok(Markup::Vast(vast)) = bid.markup(None) else {
+        panic!("not VAST markup");
+    };
+    assert_eq!(vast.duration(), None);
+}
+
+#[test]
+fn test_deeply_nested_markup() {
+    let depth = 20_000;
+    let nested = format!(
+        "<VAST version=\"4.0\">{}{}</VAST>",
+        "<a>".repeat(depth),
+        "</a>".repeat(depth)
+    );
+    assert!(matches!(
+        Vast::parse(&nested),
+        Err(VastError::InvalidXml {
+            message: "elements nested too deep",
+            ..
+        })
+    ));
+    assert!(Trackers::new().inject(&nested).is_err());
+    let bid: Bid = Bid::builder("1", "1", 1.0).adm(nested).into();
+    assert!(bid.markup(None).is_err());
+
+    // Nesting short of the limit is fine.
+    let nested = format!(
+        "<VAST version=\"4.0\">{}{}</VAST>",
+        "<a>".repeat(100),
+        "</a>".repeat(100)
+    );
+    assert!(Vast::parse(&nested).unwrap().ads.is_empty());
+}
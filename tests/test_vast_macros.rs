@@ -0,0 +1,106 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use iab::openrtb2::*;
+use iab::vast::*;
+
+fn request(lmt: Option<i64>) -> BidRequest {
+    BidRequest {
+        id: "auction 1".to_string(),
+        device: Some(Device {
+            ua: Some("Roku/DVP-9.10".to_string()),
+            ip: Some("192.0.2.1".to_string()),
+            ifa: Some("6d92078a-8246-4ba4-ae5b-76104861e7dc".to_string()),
+            lmt,
+            geo: Some(Geo {
+                lat: Some(48.8566),
+                lon: Some(2.3522),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        app: Some(App {
+            bundle: Some("com.example.tv".to_string()),
+            domain: Some("example.com".to_string()),
+            ..Default::default()
+        }),
+        user: Some(User {
+            consent: Some("CPXxRfAPXxRfAAfKABENB-CgAAAAAAAAAAYgAAAAAAAA".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_request_macros() {
+    let macros = VastMacros::from_request(&request(None))
+        .timestamp(UNIX_EPOCH + Duration::from_millis(1_453_036_507_127))
+        .cache_busting(4242)
+        .content_playhead(Duration::from_millis(3_723_500));
+
+    assert_eq!(
+        macros.substitute("t=[TIMESTAMP]&cb=[CACHEBUSTING]&p=[CONTENTPLAYHEAD]"),
+        "t=2016-01-17T13%3A15%3A07.127Z&cb=00004242&p=01%3A02%3A03.500"
+    );
+    for (seconds, timestamp) in [
+        (0, "1970-01-01T00%3A00%3A00.000Z"),
+        (951_782_400, "2000-02-29T00%3A00%3A00.000Z"),
+        (4_107_542_399, "2100-02-28T23%3A59%3A59.000Z"),
+    ] {
+        let macros = VastMacros::default().timestamp(UNIX_EPOCH + Duration::from_secs(seconds));
+        assert_eq!(macros.substitute("[TIMESTAMP]"), timestamp);
+    }
+    assert_eq!(
+        macros.substitute("ifa=[IFA]&ip=[DEVICEIP]&ll=[LATLONG]&lmt=[LIMITADTRACKING]"),
+        "ifa=6d92078a-8246-4ba4-ae5b-76104861e7dc&ip=192.0.2.1&ll=48.8566%2C2.3522&lmt=0"
+    );
+    assert_eq!(
+        macros.substitute("ua=[DEVICEUA]&b=[APPBUNDLE]&d=[DOMAIN]&gdpr=[GDPRCONSENT]"),
+        "ua=Roku%2FDVP-9.10&b=com.example.tv&d=example.com\
+         &gdpr=CPXxRfAPXxRfAAfKABENB-CgAAAAAAAAAAYgAAAAAAAA"
+    );
+
+    // Macros without a value, unknown macros and stray brackets are kept.
+    assert_eq!(
+        macros.substitute("e=[ERRORCODE]&u=[PAGEURL]&x=[UNKNOWN]&[[IFA]&a[0]=]"),
+        "e=[ERRORCODE]&u=[PAGEURL]&x=[UNKNOWN]&[6d92078a-8246-4ba4-ae5b-76104861e7dc&a[0]=]"
+    );
+
+    let site = BidRequest {
+        site: Some(Site {
+            domain: Some("news.com".to_string()),
+            page: Some("https://news.com/a?b=c".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let macros = VastMacros::from_request(&site);
+    assert_eq!(
+        macros.substitute("[DOMAIN] [PAGEURL] [IFA] [TIMESTAMP]"),
+        "news.com https%3A%2F%2Fnews.com%2Fa%3Fb%3Dc [IFA] [TIMESTAMP]"
+    );
+}
+
+#[test]
+fn test_limit_ad_tracking() {
+    let macros = VastMacros::from_request(&request(Some(1)));
+    assert_eq!(
+        macros
+            .substitute("ifa=[IFA]&ip=[DEVICEIP]&ll=[LATLONG]&lmt=[LIMITADTRACKING]&b=[APPBUNDLE]"),
+        "ifa=-2&ip=-2&ll=-2&lmt=1&b=com.example.tv"
+    );
+
+    let mut request = request(None);
+    request.device.as_mut().unwrap().dnt = Some(1);
+    let macros = VastMacros::from_request(&request)
+        .error_code(303)
+        .timestamp(SystemTime::now());
+    let mut bid: Bid = Bid::builder("a", "1", 2.0)
+        .adm("<VAST version=\"4.1\"><Error>https://dsp.com/e?c=[ERRORCODE]&amp;ifa=[IFA]</Error></VAST>")
+        .into();
+    macros.apply(&mut bid);
+    assert_eq!(
+        bid.adm.unwrap(),
+        "<VAST version=\"4.1\"><Error>https://dsp.com/e?c=303&amp;ifa=-2</Error></VAST>"
+    );
+}
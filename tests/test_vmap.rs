@@ -0,0 +1,288 @@
+use std::time::Duration;
+
+use iab::auction::*;
+use iab::openrtb2::*;
+use iab::vast::*;
+use iab::vmap::*;
+
+const SCHEDULE: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<vmap:VMAP xmlns:vmap="http://www.iab.net/videosuite/vmap" version="1.0">
+  <vmap:AdBreak timeOffset="start" breakType="linear" breakId="preroll">
+    <vmap:AdSource id="preroll-ads" allowMultipleAds="true" followRedirects="true">
+      <vmap:VASTAdData>
+        <VAST version="3.0">
+          <Ad id="house"><InLine><AdSystem>Publisher</AdSystem><AdTitle>Promo</AdTitle>
+            <Impression>https://publisher.com/imp</Impression>
+          </InLine></Ad>
+        </VAST>
+      </vmap:VASTAdData>
+    </vmap:AdSource>
+    <vmap:TrackingEvents>
+      <vmap:Tracking event="breakStart"><![CDATA[https://publisher.com/break?e=start]]></vmap:Tracking>
+    </vmap:TrackingEvents>
+  </vmap:AdBreak>
+  <vmap:AdBreak timeOffset="25%" breakType="linear,nonlinear">
+    <vmap:AdSource>
+      <vmap:CustomAdData templateType="proprietary"><Ads count="2"/></vmap:CustomAdData>
+    </vmap:AdSource>
+  </vmap:AdBreak>
+  <vmap:AdBreak timeOffset="#2" breakType="display" breakId="overlay"/>
+  <vmap:AdBreak timeOffset="end" breakType="linear" breakId="postroll" repeatAfter="00:15:00"/>
+</vmap:VMAP>"##;
+
+fn template() -> Video {
+    Video {
+        mimes: vec!["video/mp4".to_string()],
+        protocols: Some(vec![3, 6, 7, 8]),
+        poddur: Some(120),
+        maxseq: Some(4),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_vmap() {
+    let vmap = Vmap::parse(SCHEDULE).unwrap();
+    assert_eq!(vmap.version, "1.0");
+    assert_eq!(vmap.ad_breaks.len(), 4);
+
+    let preroll = &vmap.ad_breaks[0];
+    assert_eq!(preroll.time_offset, TimeOffset::Start);
+    assert_eq!(preroll.tracking_events[0].event, "breakStart");
+    assert_eq!(
+        preroll.tracking_events[0].url,
+        "https://publisher.com/break?e=start"
+    );
+    let source = preroll.ad_source.as_ref().unwrap();
+    assert_eq!(source.allow_multiple_ads, Some(true));
+    let AdData::Vast(vast) = &source.data else {
+        panic!("expected VAST ad data");
+    };
+    assert_eq!(vast.ads[0].id.as_deref(), Some("house"));
+
+    let midroll = &vmap.ad_breaks[1];
+    assert_eq!(midroll.time_offset, TimeOffset::Percentage(25.0));
+    assert!(midroll.is_linear());
+    assert_eq!(
+        midroll.ad_source.as_ref().unwrap().data,
+        AdData::Custom {
+            template_type: "proprietary".to_string(),
+            xml: r#"<Ads count="2"/>"#.to_string(),
+        }
+    );
+
+    assert_eq!(vmap.ad_breaks[2].time_offset, TimeOffset::Position(2));
+    assert!(!vmap.ad_breaks[2].is_linear());
+    assert_eq!(
+        vmap.ad_breaks[3].repeat_after,
+        Some(Duration::from_secs(900))
+    );
+
+    let xml = vmap.to_xml();
+    assert!(
+        xml.contains(r#"<vmap:AdBreak timeOffset="start" breakType="linear" breakId="preroll">"#)
+    );
+    assert_eq!(Vmap::parse(&xml).unwrap(), vmap);
+
+    // The namespace prefix is optional.
+    let bare = Vmap::parse(
+        r#"<VMAP version="1.0"><AdBreak timeOffset="00:05:00.500" breakType="linear"/></VMAP>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        bare.ad_breaks[0].time_offset,
+        TimeOffset::Time(Duration::from_millis(300_500))
+    );
+
+    assert!(matches!(
+        Vmap::parse(r#"<VAST version="4.0"/>"#),
+        Err(VmapError::NotVmap { .. })
+    ));
+    assert_eq!(
+        Vmap::parse(
+            r#"<vmap:VMAP version="1.0"><vmap:AdBreak timeOffset="soon" breakType="linear"/></vmap:VMAP>"#
+        ),
+        Err(VmapError::InvalidTimeOffset("soon".to_string()))
+    );
+    assert_eq!(
+        Vmap::parse(
+            r#"<vmap:VMAP version="1.0"><vmap:AdBreak timeOffset="18446744073709551615:00:00" breakType="linear"/></vmap:VMAP>"#
+        ),
+        Err(VmapError::InvalidTimeOffset(
+            "18446744073709551615:00:00".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_vmap_to_pods() {
+    let vmap = Vmap::parse(SCHEDULE).unwrap();
+    let mut request = BidRequest {
+        id: "auction 1".to_string(),
+        app: Some(App {
+            content: Some(Content {
+                len: Some(2400),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    vmap.add_pods(&mut request, &template());
+
+    let pods: Vec<_> = request
+        .imp
+        .iter()
+        .map(|imp| {
+            let video = imp.video.as_ref().unwrap();
+            (
+                imp.id.as_str(),
+                video.podid.as_deref().unwrap(),
+                video.podseq.unwrap(),
+                video.startdelay.unwrap(),
+                video.poddur.unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        pods,
+        [
+            ("1", "preroll", 1, 0, 120),
+            ("2", "break-2", 0, 600, 120),
+            ("3", "postroll", -1, -2, 120),
+        ]
+    );
+
+    // Without the length of the content, percentages are generic mid-rolls.
+    let mut request = BidRequest::default();
+    vmap.add_pods(&mut request, &template());
+    assert_eq!(request.imp[1].video.as_ref().unwrap().startdelay, Some(-1));
+
+    // Impression IDs already in the request are not reused.
+    let mut request = BidRequest {
+        imp: vec![Imp::banner(300, 250).id("2").into()],
+        ..Default::default()
+    };
+    vmap.add_pods(&mut request, &template());
+    let ids: Vec<_> = request.imp.iter().map(|imp| imp.id.as_str()).collect();
+    assert_eq!(ids, ["2", "3", "4", "5"]);
+}
+
+#[test]
+fn test_pods_to_vmap() {
+    let schedule = Vmap::parse(SCHEDULE).unwrap();
+    let mut request = BidRequest::default();
+    schedule.add_pods(&mut request, &template());
+
+    let candidate = |bid: BidBuilder| {
+        let bid: Bid = bid.into();
+        Candidate {
+            response: 0,
+            seatbid: 0,
+            group: false,
+            seat: None,
+            currency: None,
+            price: bid.price,
+            bid,
+        }
+    };
+    let inline = r#"<VAST version="4.1"><Ad id="a"><InLine><AdSystem>DSP</AdSystem><AdTitle>A</AdTitle>
+      <Error>https://dsp.com/ad-error</Error>
+      <Creatives><Creative><Linear><Duration>00:00:30</Duration></Linear></Creative></Creatives>
+    </InLine></Ad><Error>https://dsp.com/error</Error></VAST>"#;
+    let preroll = PodBuilder::new(&request, "preroll").assemble([
+        candidate(Bid::builder("a", "1", 10.0).dur(30).adm(inline)),
+        candidate(
+            Bid::builder("b", "1", 8.0)
+                .dur(15)
+                .nurl("https://dsp.com/win?id=b"),
+        ),
+    ]);
+    let postroll = PodBuilder::new(&request, "postroll").assemble([]);
+    assert_eq!(preroll.ads.len(), 2);
+
+    let pods = [preroll, postroll];
+    assert_eq!(
+        Vmap::from_pods(&Trackers::new(), "Exchange", &request, &pods),
+        Err(VmapError::Wrap {
+            bid: "b".to_string(),
+            error: WrapError::MissingImpression
+        })
+    );
+
+    let trackers = Trackers::new().impression("https://exchange.com/imp");
+    let vmap = Vmap::from_pods(&trackers, "Exchange", &request, &pods).unwrap();
+    assert_eq!(vmap.ad_breaks.len(), 1);
+    let ad_break = &vmap.ad_breaks[0];
+    assert_eq!(ad_break.time_offset, TimeOffset::Start);
+    assert_eq!(ad_break.break_id.as_deref(), Some("preroll"));
+
+    let AdData::Vast(vast) = &ad_break.ad_source.as_ref().unwrap().data else {
+        panic!("expected VAST ad data");
+    };
+    assert_eq!(vast.version, "4.1");
+    assert_eq!(vast.ads.len(), 2);
+    assert_eq!(
+        (vast.ads[0].id.as_deref(), vast.ads[0].sequence),
+        (Some("a"), Some(1))
+    );
+    assert!(vast.errors.is_empty());
+    let AdKind::InLine(ad) = &vast.ads[0].kind else {
+        panic!("expected an inline ad");
+    };
+    assert_eq!(
+        ad.errors,
+        ["https://dsp.com/ad-error", "https://dsp.com/error"]
+    );
+    // The exchange trackers are added to the markup of the bid too.
+    let urls: Vec<_> = ad.impressions.iter().map(|i| i.url.as_str()).collect();
+    assert_eq!(urls, ["https://exchange.com/imp"]);
+    assert_eq!(vast.ads[1].sequence, Some(2));
+    let AdKind::Wrapper(wrapper) = &vast.ads[1].kind else {
+        panic!("expected a wrapper");
+    };
+    assert_eq!(wrapper.vast_ad_tag_uri, "https://dsp.com/win?id=b");
+    assert_eq!(wrapper.ad_system.name, "Exchange");
+    assert_eq!(wrapper.impressions[0].url, "https://exchange.com/imp");
+
+    assert_eq!(Vmap::parse(&vmap.to_xml()).unwrap(), vmap);
+
+    // Generic mid-rolls have no time to play the break at.
+    let midroll = PodBuilder::new(&request, "break-2")
+        .assemble([candidate(Bid::builder("c", "2", 5.0).dur(30).adm(inline))]);
+    assert_eq!(request.imp[1].video.as_ref().unwrap().startdelay, Some(-1));
+    assert_eq!(
+        Vmap::from_pods(
+            &trackers,
+            "Exchange",
+            &request,
+            std::slice::from_ref(&midroll)
+        ),
+        Err(VmapError::UnknownStart {
+            podid: "break-2".to_string()
+        })
+    );
+
+    // The schedule the pods were made from keeps the breaks as they were.
+    let [preroll, _] = pods;
+    let served = schedule
+        .serve_pods(&trackers, "Exchange", &request, &[preroll, midroll])
+        .unwrap();
+    let offsets: Vec<_> = served
+        .ad_breaks
+        .iter()
+        .map(|ad_break| (ad_break.time_offset, ad_break.break_id.as_deref()))
+        .collect();
+    assert_eq!(
+        offsets,
+        [
+            (TimeOffset::Start, Some("preroll")),
+            (TimeOffset::Percentage(25.0), None),
+        ]
+    );
+    assert_eq!(
+        served.ad_breaks[0].tracking_events,
+        schedule.ad_breaks[0].tracking_events
+    );
+    assert_eq!(served.ad_breaks[0].ad_source, vmap.ad_breaks[0].ad_source);
+}